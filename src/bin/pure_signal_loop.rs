@@ -1,7 +1,7 @@
 extern crate reactrust;
 
 use reactrust::processes::*;
-use reactrust::signals::signals::Signal;
+use reactrust::signals::signals::ObservableSignal;
 use reactrust::signals::pure_signal::PureSignal;
 
 
@@ -48,11 +48,7 @@ fn main () {
   .while_loop();
 
   // Final process: join all above processes
-  let main_process = emit_and_pause_process.join(
-    present_or_absent_process.join(
-      await_process
-    )
-  );
+  let main_process = emit_and_pause_process.join3(present_or_absent_process, await_process);
 
   execute_process(main_process);
 }
@@ -0,0 +1,131 @@
+extern crate reactrust;
+
+use std::cmp;
+
+use reactrust::processes::*;
+use reactrust::signals::pure_signal::PureSignal;
+use reactrust::signals::value_signal::ValueSignal;
+
+
+/// State of a traffic light.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum LightState {
+  Red,
+  Green,
+  Yellow
+}
+
+
+impl LightState {
+  /// Returns the number of instants the light stays in this state.
+  fn duration(self) -> usize {
+    match self {
+      LightState::Red    => 10,
+      LightState::Green  => 20,
+      LightState::Yellow => 3
+    }
+  }
+
+  /// Returns the state following this state.
+  fn next(self) -> LightState {
+    match self {
+      LightState::Red    => LightState::Green,
+      LightState::Green  => LightState::Yellow,
+      LightState::Yellow => LightState::Red
+    }
+  }
+}
+
+
+/// Maximal number of instants the light stays green once a pedestrian has requested to cross.
+const GREEN_AFTER_REQUEST: usize = 2;
+
+
+/// Returns a process running a traffic light during the given number of instants, starting red.
+///
+/// The state of the light is emitted on `output` during each instant, and a pedestrian request
+/// (i.e. an emission of `request`) shortens the green phase.
+fn traffic_light(request: PureSignal, output: ValueSignal<LightState, LightState>, instants: usize)
+  -> impl Process<Value = ()>
+{
+  let initial = (LightState::Red, LightState::Red.duration());
+
+  let step = move |(light, remaining): (LightState, usize), ctx: &mut StepCtx| {
+    // Requests are sampled during the instant following them
+    let remaining = if light == LightState::Green && ctx.was_present(&request) {
+      cmp::min(remaining, GREEN_AFTER_REQUEST)
+    }
+    else {
+      remaining
+    };
+
+    ctx.emit(&output, light);
+
+    let state = if remaining == 1 { (light.next(), light.next().duration()) } else { (light, remaining - 1) };
+    if ctx.instant_index() + 1 == instants { Transition::Stop(state) } else { Transition::Next(state) }
+  };
+
+  state_machine(initial, step).map(|_| ())
+}
+
+
+/// Example program, which runs a traffic light during 60 instants, with a pedestrian request
+/// during instant 20, and prints the state of the light during each instant.
+fn main () {
+  let request   = PureSignal::new();
+  let output    = ValueSignal::new_memory(LightState::Red);
+  let recording = output.record();
+
+  let light = traffic_light(request.clone(), output.clone(), 60);
+  execute_process(light.join(at_instant(20, request.emit())));
+
+  for (instant, state) in recording.into_vec() {
+    println!("{:>2}: {:?}", instant, state.unwrap());
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use reactrust::testing::SignalAssertions;
+  use super::*;
+
+
+  // Run a traffic light during 60 instants, with pedestrian requests during the given instants,
+  // and returns the state of the light during each instant (`R`, `G` or `Y`).
+  fn light_timeline(requests: Vec<usize>) -> String {
+    let request = PureSignal::new();
+    let output  = ValueSignal::new_memory(LightState::Red);
+    let log     = output.attach();
+
+    let request_1     = request.clone();
+    let emit_requests = for_each_concurrent(requests, move |i| at_instant(i, request_1.clone().emit()));
+    execute_process(traffic_light(request, output, 60).join(emit_requests));
+
+    (0..60).map(|i| match log.value_at(i) {
+      Some(LightState::Red)    => 'R',
+      Some(LightState::Green)  => 'G',
+      Some(LightState::Yellow) => 'Y',
+      None                     => '.'
+    }).collect()
+  }
+
+  #[test]
+  fn regular_cycle () {
+    let expected = ["R"; 10].concat() + &["G"; 20].concat() + "YYY"
+                 + &["R"; 10].concat() + &["G"; 17].concat();
+    assert_eq!(light_timeline(vec![]), expected);
+  }
+
+  #[test]
+  fn pedestrian_request_shortens_green () {
+    // The request of instant 20 is sampled during instant 21, and the light stays green 2 instants
+    let expected = ["R"; 10].concat() + &["G"; 13].concat() + "YYY"
+                 + &["R"; 10].concat() + &["G"; 20].concat() + "YYY" + "R";
+    assert_eq!(light_timeline(vec![20]), expected);
+  }
+}
@@ -0,0 +1,79 @@
+use std::rc::Rc;
+use std::cell::Cell;
+use std::time::{Duration, Instant};
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// CLOCK
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A source of wall-clock time.
+pub trait Clock {
+  /// Returns the time elapsed since the origin of the clock.
+  fn now(&self) -> Duration;
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SYSTEM CLOCK
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A clock measuring real time, whose origin is its creation.
+pub struct SystemClock {
+  origin: Instant
+}
+
+
+impl SystemClock {
+  /// Create a new `SystemClock`, starting now.
+  pub fn new() -> Self {
+    SystemClock { origin: Instant::now() }
+  }
+}
+
+
+impl Default for SystemClock {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+impl Clock for SystemClock {
+  fn now(&self) -> Duration {
+    self.origin.elapsed()
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// MOCK CLOCK
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A clock whose time only changes when it is explicitly advanced.
+///
+/// All the clones of a mock clock share the same time.
+#[derive(Clone, Default)]
+pub struct MockClock {
+  time: Rc<Cell<Duration>>
+}
+
+
+impl MockClock {
+  /// Create a new `MockClock`, starting at time zero.
+  pub fn new() -> Self {
+    MockClock { time: Rc::new(Cell::new(Duration::from_secs(0))) }
+  }
+
+  /// Moves the time of the clock forward by the given duration.
+  pub fn advance(&self, duration: Duration) {
+    self.time.set(self.time.get() + duration);
+  }
+}
+
+
+impl Clock for MockClock {
+  fn now(&self) -> Duration {
+    self.time.get()
+  }
+}
@@ -1,22 +1,58 @@
-use runtime::Runtime;
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::any::Any;
+use std::marker::PhantomData;
+
+use runtime::{Runtime, ReactiveRuntime};
+use signals::signal_core::SignalCore;
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // CONTINUATION
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A reactive continuation awaiting a value of type `V`.
+/// A reactive continuation awaiting a value of type `V`, executed by a runtime of type `R`
+/// (see `ReactiveRuntime`), which is the runtime of this crate by default.
 /// For the sake of simplicity, continuations must be valid on the `static` lifetime.
-pub trait Continuation<V>: 'static {
+pub trait Continuation<V, R = Runtime>: 'static {
   /// Calls the continuation.
-  fn call(self, runtime: &mut Runtime, value: V);
+  fn call(self, runtime: &mut R, value: V);
 
   /// Calls the continuation. Works even if the continuation is boxed.
   ///
   /// This is necessary because the size of a value must be known to unbox it. It is
   /// thus impossible to take the ownership of a `Box<Continuation>` whitout knowing the
   /// underlying type of the `Continuation`.
-  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: V);
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V);
+
+  /// Returns the label of the continuation, if it has one (see `named`).
+  /// It is only used for debugging purposes (see `Runtime::dump_state`).
+  fn label(&self) -> Option<&'static str> {
+    None
+  }
+
+  /// Returns the continuation as a boxed continuation.
+  ///
+  /// A continuation which is already boxed is returned as is, instead of being boxed again:
+  /// a continuation passed around and boxed many times (e.g. by a recursive process) thus does
+  /// not end up nested in as many boxes.
+  fn boxed(self) -> Box<dyn Continuation<V, R>>
+  where
+    Self: Sized
+  {
+    Box::new(self)
+  }
+
+  /// Creates a new continuation behaving like `Self`, labelled with the given name.
+  fn named(self, label: &'static str) -> Named<Self>
+  where
+    Self: Sized
+  {
+    Named {
+      continuation: self,
+      label
+    }
+  }
 
   /// Creates a new continuation that applies a function to the input value before calling `Self`.
   fn map<F, V2>(self, map: F) -> Map<Self, F>
@@ -26,7 +62,7 @@ pub trait Continuation<V>: 'static {
   {
     Map {
       continuation: self,
-      map: map
+      map
     }
   }
 
@@ -39,26 +75,108 @@ pub trait Continuation<V>: 'static {
       continuation: self
     }
   }
+
+  /// Creates a new continuation that waits for `instants` instants before running a continuation,
+  /// i.e. behaves like `Self` if `instants` is 0.
+  fn pause_n(self, instants: usize) -> PauseN<Self>
+  where
+    Self: Sized
+  {
+    PauseN {
+      continuation: self,
+      instants
+    }
+  }
+
+  /// Creates a new continuation that, once called, runs `Self` as soon as the signal
+  /// is present (see `DelayedOnPresent`).
+  ///
+  /// This is the primitive for writing processes that react immediately to a signal.
+  fn delay_until_present<S, SV, E>(self, signal_core: S) -> DelayedOnPresent<Self, S, SV, E>
+  where
+    Self: Sized,
+    S: SignalCore<SV, E>
+  {
+    DelayedOnPresent {
+      continuation: self,
+      signal_core,
+      phantom     : PhantomData
+    }
+  }
+
+  /// Creates a new continuation that, once called, runs `Self` with the value of the signal
+  /// during the instant following its presence (see `DelayedAfterPresent`).
+  ///
+  /// This is the primitive for writing processes that need the value of a signal.
+  fn delay_after_present<S, SV, E>(self, signal_core: S) -> DelayedAfterPresent<Self, S, SV, E>
+  where
+    Self: Sized,
+    S: SignalCore<SV, E>
+  {
+    DelayedAfterPresent {
+      continuation: self,
+      signal_core,
+      phantom     : PhantomData
+    }
+  }
+
+  /// Creates a new continuation that, once called, runs `Self` during next instant
+  /// if the signal is absent during this instant (see `DelayedOnAbsent`).
+  fn delay_until_absent<S, SV, E>(self, signal_core: S) -> DelayedOnAbsent<Self, S, SV, E>
+  where
+    Self: Sized,
+    S: SignalCore<SV, E>
+  {
+    DelayedOnAbsent {
+      continuation: self,
+      signal_core,
+      phantom     : PhantomData
+    }
+  }
 }
 
 
 /// Functions of type `FnOnce` are considered to be continuations.
 ///
 /// This is used in order to make continuations out of Rust closures.
-impl<V, F> Continuation<V> for F
+impl<V, R, F> Continuation<V, R> for F
 where
-  F: FnOnce(&mut Runtime, V) + 'static
+  F: FnOnce(&mut R, V) + 'static
 {
-  fn call(self, runtime: &mut Runtime, value: V) {
+  fn call(self, runtime: &mut R, value: V) {
     self(runtime, value);
   }
 
-  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: V) {
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V) {
     (*self).call(runtime, value);
   }
 }
 
 
+/// Boxed continuations also are continuations.
+impl<V, R> Continuation<V, R> for Box<dyn Continuation<V, R>>
+where
+  V: 'static,
+  R: 'static
+{
+  fn call(self, runtime: &mut R, value: V) {
+    self.call_box(runtime, value);
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V) {
+    (*self).call_box(runtime, value);
+  }
+
+  fn label(&self) -> Option<&'static str> {
+    (**self).label()
+  }
+
+  fn boxed(self) -> Box<dyn Continuation<V, R>> {
+    self
+  }
+}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // MAP
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -70,17 +188,17 @@ pub struct Map<C, F> {
   map: F
 }
 
-impl<C, F, V1, V2> Continuation<V1> for Map<C, F>
+impl<C, F, V1, V2, R> Continuation<V1, R> for Map<C, F>
 where
-  C: Continuation<V2>,
+  C: Continuation<V2, R>,
   F: FnOnce(V1) -> V2 + 'static
 {
-  fn call(self, runtime: &mut Runtime, value: V1) {
+  fn call(self, runtime: &mut R, value: V1) {
     let result = (self.map)(value);
     self.continuation.call(runtime, result);
   }
 
-  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: V1) {
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V1) {
     (*self).call(runtime, value);
   }
 }
@@ -95,22 +213,285 @@ pub struct Pause<C> {
   continuation: C
 }
 
-impl<C, V> Continuation<V> for Pause<C>
+impl<C, V, R> Continuation<V, R> for Pause<C>
 where
-  C: Continuation<V>, V: 'static
+  C: Continuation<V, R>, V: 'static, R: ReactiveRuntime
 {
-  fn call(self, runtime: &mut Runtime, value: V) {
-    runtime.on_next_instant(Box::new(move |r: &mut Runtime, ()| {
+  fn call(self, runtime: &mut R, value: V) {
+    runtime.schedule_next(move |r: &mut R, ()| {
       self.continuation.call(r, value);
-    }));
+    });
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V) {
+    (*self).call(runtime, value);
+  }
+}
+
+
+/// A continuation that postpones its execution by a given number of instants,
+/// registering itself again during each instant until it has waited enough.
+pub struct PauseN<C> {
+  continuation: C,
+  instants    : usize
+}
+
+impl<C, V, R> Continuation<V, R> for PauseN<C>
+where
+  C: Continuation<V, R>, V: 'static, R: ReactiveRuntime
+{
+  fn call(self, runtime: &mut R, value: V) {
+    if self.instants == 0 {
+      self.continuation.call(runtime, value);
+      return;
+    }
+
+    runtime.schedule_next(move |r: &mut R, ()| {
+      self.continuation.pause_n(self.instants - 1).call(r, value);
+    });
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V) {
+    (*self).call(runtime, value);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// NAMED
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A continuation labelled with a name, for debugging purposes.
+pub struct Named<C> {
+  continuation: C,
+  label: &'static str
+}
+
+impl<C, V, R> Continuation<V, R> for Named<C>
+where
+  C: Continuation<V, R>
+{
+  fn call(self, runtime: &mut R, value: V) {
+    self.continuation.call(runtime, value);
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut R, value: V) {
+    (*self).call(runtime, value);
+  }
+
+  fn label(&self) -> Option<&'static str> {
+    Some(self.label)
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL DELAYS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A continuation that, once called, waits for a signal to be present.
+///
+/// The wrapped continuation runs during the first instant the signal is present, starting from the
+/// instant it is called: immediately if the signal has already been emitted, or when it is emitted.
+/// Continuations delayed on the same signal run in the order they were called.
+pub struct DelayedOnPresent<C, S, V, E> {
+  continuation: C,
+  signal_core : S,
+  phantom     : PhantomData<(V, E)>
+}
+
+impl<C, S, V, E> Continuation<()> for DelayedOnPresent<C, S, V, E>
+where
+  S: SignalCore<V, E>,
+  C: Continuation<()>,
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  fn call(self, runtime: &mut Runtime, _: ()) {
+    self.signal_core.register_on_present(runtime, self.continuation);
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: ()) {
+    (*self).call(runtime, value);
+  }
+}
+
+
+/// A continuation that, once called, waits for a signal to be present,
+/// and runs during the next instant.
+///
+/// The wrapped continuation runs during the instant following the first instant the signal is
+/// present, starting from the instant it is called (whether the signal has already been emitted
+/// or not), and receives the value of the signal gathered during the instant it is present.
+/// Continuations delayed on the same signal run in the order they were called.
+pub struct DelayedAfterPresent<C, S, V, E> {
+  continuation: C,
+  signal_core : S,
+  phantom     : PhantomData<(V, E)>
+}
+
+impl<C, S, V, E> Continuation<()> for DelayedAfterPresent<C, S, V, E>
+where
+  S: SignalCore<V, E>,
+  C: Continuation<V>,
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  fn call(self, runtime: &mut Runtime, _: ()) {
+    self.signal_core.register_later_on_present(runtime, self.continuation);
   }
 
-  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: V) {
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: ()) {
     (*self).call(runtime, value);
   }
 }
 
 
+/// A continuation that, once called, waits for a signal to be absent.
+///
+/// Since absence is only known at the end of an instant, if the signal is not emitted during the
+/// instant it is called, the wrapped continuation runs during next instant. If the signal has
+/// already been emitted when it is called, or is emitted later during the same instant,
+/// it is dropped. Continuations delayed on the same signal run in the order they were called.
+pub struct DelayedOnAbsent<C, S, V, E> {
+  continuation: C,
+  signal_core : S,
+  phantom     : PhantomData<(V, E)>
+}
+
+impl<C, S, V, E> Continuation<()> for DelayedOnAbsent<C, S, V, E>
+where
+  S: SignalCore<V, E>,
+  C: Continuation<()>,
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  fn call(self, runtime: &mut Runtime, _: ()) {
+    self.signal_core.register_later_on_absent(runtime, self.continuation);
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: ()) {
+    (*self).call(runtime, value);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// CANCEL HANDLE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle for cancelling a group of continuations.
+///
+/// A runtime always runs continuations within a (possibly empty) *cancellation context*:
+/// any continuation registered to the runtime (or to a signal) is bound to the current context,
+/// and is silently dropped instead of being called if its handle has been cancelled in between.
+///
+/// Handles can be nested: a handle is considered cancelled as soon as one of its ancestors is.
+///
+/// A handle may also hold a `Gate`, which suspends the continuations bound to the handle
+/// (or to any of its children) while it is closed, or a `PanicCatcher`, which is called with the
+/// payload of any panic raised by these continuations, instead of letting it unwind.
+#[derive(Clone)]
+pub struct CancelHandle {
+  cancelled: Rc<Cell<bool>>,
+  gate     : Option<Rc<Gate>>,
+  catcher  : Option<PanicCatcher>,
+  parent   : Option<Rc<CancelHandle>>
+}
+
+/// A function called with the payload of a panic raised by a continuation
+/// (see `CancelHandle::catching`).
+pub type PanicCatcher = Rc<dyn Fn(&mut Runtime, Box<dyn Any + Send>)>;
+
+impl CancelHandle {
+  /// Create a new `CancelHandle`, which is a child of `parent` if any.
+  pub fn new(parent: Option<CancelHandle>) -> Self {
+    CancelHandle { cancelled: Rc::new(Cell::new(false)), gate: None, catcher: None, parent: parent.map(Rc::new) }
+  }
+
+  /// Create a new `CancelHandle` holding the given gate, which is a child of `parent` if any.
+  pub fn gated(parent: Option<CancelHandle>, gate: Rc<Gate>) -> Self {
+    CancelHandle { gate: Some(gate), ..CancelHandle::new(parent) }
+  }
+
+  /// Create a new `CancelHandle` holding the given panic catcher, which is a child of `parent`
+  /// if any.
+  pub fn catching(parent: Option<CancelHandle>, catcher: PanicCatcher) -> Self {
+    CancelHandle { catcher: Some(catcher), ..CancelHandle::new(parent) }
+  }
+
+  /// Returns the panic catcher held by this handle or by its closest ancestor holding one, if any.
+  pub fn panic_catcher(&self) -> Option<PanicCatcher> {
+    match self.catcher {
+      Some(ref catcher) => Some(catcher.clone()),
+      None              => self.parent.as_ref().and_then(|parent| parent.panic_catcher())
+    }
+  }
+
+  /// Returns the first closed gate held by this handle or by one of its ancestors, if any.
+  pub fn closed_gate(&self, runtime: &Runtime) -> Option<Rc<Gate>> {
+    match self.gate {
+      Some(ref gate) if !gate.is_open(runtime) => Some(gate.clone()),
+      _ => self.parent.as_ref().and_then(|parent| parent.closed_gate(runtime))
+    }
+  }
+
+  /// Cancel all continuations bound to this handle (or to any of its children).
+  pub fn cancel(&self) {
+    self.cancelled.set(true);
+  }
+
+  /// Indicates whether this handle (or any of its ancestors) has been cancelled.
+  pub fn is_cancelled(&self) -> bool {
+    self.cancelled.get() || self.parent.as_ref().is_some_and(|parent| parent.is_cancelled())
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// GATE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A gate suspending the continuations bound to a `CancelHandle` (see `CancelHandle::gated`):
+/// a continuation called while the gate is closed is *parked* instead of being called,
+/// until the gate releases it.
+///
+/// Whether the gate is open is computed from the runtime each time a continuation is called.
+pub struct Gate {
+  is_open: Box<dyn Fn(&Runtime) -> bool>,
+  parked : RefCell<Vec<Box<dyn Continuation<()>>>>
+}
+
+impl Gate {
+  /// Create a new `Gate`, which is open whenever `is_open` returns `true`.
+  pub fn new<F>(is_open: F) -> Rc<Self> where F: Fn(&Runtime) -> bool + 'static {
+    Rc::new(Gate { is_open: Box::new(is_open), parked: RefCell::new(Vec::new()) })
+  }
+
+  /// Indicates whether the gate is open.
+  pub fn is_open(&self, runtime: &Runtime) -> bool {
+    (self.is_open)(runtime)
+  }
+
+  /// Parks a continuation until the gate releases it.
+  pub fn park(&self, c: Box<dyn Continuation<()>>) {
+    self.parked.borrow_mut().push(c);
+  }
+
+  /// Returns the number of parked continuations.
+  pub fn parked(&self) -> usize {
+    self.parked.borrow().len()
+  }
+
+  /// Registers the parked continuations to run during current instant, in the order they have
+  /// been parked. They are parked again if the gate is still closed when they run.
+  pub fn release(&self, runtime: &mut Runtime) {
+    let parked = ::std::mem::take(&mut *self.parked.borrow_mut());
+    runtime.on_current_instant_batch(parked);
+  }
+}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // TESTS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -120,6 +501,8 @@ mod tests {
   use std::rc::Rc;
   use std::cell::RefCell;
 
+  use signals::runtime::SignalRuntimeRef;
+  use testing::Recorder;
   use super::*;
 
 
@@ -142,18 +525,93 @@ mod tests {
 
     let mut work_remains = runtime.instant();
     assert_eq!(*flag_ref.borrow_mut(), 0);
-    assert_eq!(work_remains, true);
+    assert!(work_remains);
 
     work_remains = runtime.instant();
     assert_eq!(*flag_ref.borrow_mut(), 0);
-    assert_eq!(work_remains, true);
+    assert!(work_remains);
 
     work_remains = runtime.instant();
     assert_eq!(*flag_ref.borrow_mut(), 42);
-    assert_eq!(work_remains, false);
+    assert!(!work_remains);
 
     // There should not be any task left
     work_remains = runtime.instant();
-    assert_eq!(work_remains, false);
+    assert!(!work_remains);
   }
+
+  /// Runs `register` during the first instant, and emits a signal gathering a sum during the
+  /// first instant, either before or after the call to `register`.
+  /// Returns the instants and values received by the continuations registered by `register`.
+  fn delay_on_signal<F>(emit_before: bool, emit: bool, register: F) -> Vec<(usize, i32)>
+  where
+    F: FnOnce(&mut Runtime, SignalRuntimeRef<i32, i32>, Recorder<(usize, i32)>) + 'static
+  {
+    let signal_runtime = SignalRuntimeRef::new(0, Box::new(|e: i32, v: &mut i32| *v += e));
+    let log            = Recorder::new();
+    let recorder       = log.clone();
+
+    let mut runtime = Runtime::new();
+    runtime.on_current_instant(Box::new(move |r: &mut Runtime, ()| {
+      if emit && emit_before {
+        signal_runtime.clone().emit(r, 2);
+      }
+
+      register(r, signal_runtime.clone(), recorder);
+
+      if emit && !emit_before {
+        signal_runtime.clone().emit(r, 2);
+      }
+      if emit {
+        signal_runtime.emit(r, 3);
+      }
+    }));
+    runtime.execute();
+
+    log.values()
+  }
+
+  #[test]
+  fn delay_until_present () {
+    for (emit_before, emit, expected) in [(true,  true,  vec![(0, 0)]),
+                                           (false, true,  vec![(0, 0)]),
+                                           (false, false, vec![])] {
+      let log = delay_on_signal(emit_before, emit, |r, signal_runtime, log| {
+        let record = move |r: &mut Runtime, _: ()| log.record((r.instant_index(), 0));
+        record.delay_until_present(signal_runtime).call(r, ());
+      });
+
+      assert_eq!(log, expected);
+    }
+  }
+
+  #[test]
+  fn delay_after_present () {
+    // The delivered value is the one gathered during the whole instant of the call
+    for (emit_before, emit, expected) in [(true,  true,  vec![(1, 5)]),
+                                           (false, true,  vec![(1, 5)]),
+                                           (false, false, vec![])] {
+      let log = delay_on_signal(emit_before, emit, |r, signal_runtime, log| {
+        let record = move |r: &mut Runtime, v: i32| log.record((r.instant_index(), v));
+        record.delay_after_present(signal_runtime).call(r, ());
+      });
+
+      assert_eq!(log, expected);
+    }
+  }
+
+  #[test]
+  fn delay_until_absent () {
+    for (emit_before, emit, expected) in [(true,  true,  vec![]),
+                                           (false, true,  vec![]),
+                                           (false, false, vec![(1, 0)])] {
+      let log = delay_on_signal(emit_before, emit, |r, signal_runtime, log| {
+        let record = move |r: &mut Runtime, _: ()| log.record((r.instant_index(), 0));
+        record.delay_until_absent(signal_runtime).call(r, ());
+      });
+
+      assert_eq!(log, expected);
+    }
+  }
+
 }
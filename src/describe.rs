@@ -0,0 +1,194 @@
+use std::fmt;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PROCESS SHAPE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Structure of a composed process, as a tree of the combinators it is built from.
+///
+/// Nodes built from a signal carry the name of the signal, if it has one. Functions given to the
+/// combinators are opaque: the processes they return at runtime are not part of the shape.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum ProcessShape {
+  /// A process returning a value.
+  Value,
+  /// A process applying a function to the value of a process.
+  Map { inner: Box<ProcessShape> },
+  /// A process pausing after a process.
+  Pause { inner: Box<ProcessShape> },
+  /// A process running the process returned by a process.
+  Flatten { inner: Box<ProcessShape> },
+  /// Two processes ran concurrently.
+  Join { left: Box<ProcessShape>, right: Box<ProcessShape> },
+  /// A process ran in a loop.
+  Loop { body: Box<ProcessShape> },
+  /// A process awaiting a signal, and completing during the instant following its emission.
+  Await { signal_name: Option<&'static str> },
+  /// A process awaiting a signal, and completing during the instant of its emission.
+  AwaitImmediate { signal_name: Option<&'static str> },
+  /// A process emitting a signal.
+  Emit { signal_name: Option<&'static str> },
+  /// A process running one of two processes, depending on the presence of a signal.
+  Present { signal_name: Option<&'static str>, if_present: Box<ProcessShape>, if_absent: Box<ProcessShape> },
+  /// A process returning the previous value of a signal.
+  Pre { signal_name: Option<&'static str> },
+  /// A process preempted by a signal.
+  Until { inner: Box<ProcessShape>, signal_name: Option<&'static str> },
+  /// Any other combinator, with its name and the processes it is built from.
+  Combinator { name: &'static str, inner: Vec<ProcessShape> },
+  /// A process whose structure is not known, e.g. built by a function.
+  Opaque { name: &'static str }
+}
+
+
+impl ProcessShape {
+  /// Returns the name of the node, and the processes it is built from.
+  fn node(&self) -> (String, Vec<&ProcessShape>) {
+    let with_signal = |name: &str, signal_name: &Option<&'static str>| match *signal_name {
+      Some(signal_name) => format!("{}({})", name, signal_name),
+      None              => name.to_string()
+    };
+
+    match *self {
+      ProcessShape::Value                       => ("Value".to_string(), vec![]),
+      ProcessShape::Map { ref inner }           => ("Map".to_string(), vec![&**inner]),
+      ProcessShape::Pause { ref inner }         => ("Pause".to_string(), vec![&**inner]),
+      ProcessShape::Flatten { ref inner }       => ("Flatten".to_string(), vec![&**inner]),
+      ProcessShape::Join { ref left, ref right } => ("Join".to_string(), vec![&**left, &**right]),
+      ProcessShape::Loop { ref body }           => ("Loop".to_string(), vec![&**body]),
+
+      ProcessShape::Await { ref signal_name }          => (with_signal("Await", signal_name), vec![]),
+      ProcessShape::AwaitImmediate { ref signal_name } => (with_signal("AwaitImmediate", signal_name), vec![]),
+      ProcessShape::Emit { ref signal_name }           => (with_signal("Emit", signal_name), vec![]),
+      ProcessShape::Pre { ref signal_name }            => (with_signal("Pre", signal_name), vec![]),
+
+      ProcessShape::Present { ref signal_name, ref if_present, ref if_absent } =>
+        (with_signal("Present", signal_name), vec![&**if_present, &**if_absent]),
+      ProcessShape::Until { ref inner, ref signal_name } =>
+        (with_signal("Until", signal_name), vec![&**inner]),
+
+      ProcessShape::Combinator { name, ref inner } => (name.to_string(), inner.iter().collect()),
+      ProcessShape::Opaque { name }                => (name.to_string(), vec![])
+    }
+  }
+
+  /// Writes the tree rooted at this node, indented according to the given depth.
+  fn fmt_indented(&self, f: &mut fmt::Formatter, depth: usize) -> fmt::Result {
+    let (name, children) = self.node();
+    write!(f, "{:indent$}{}", "", name, indent = 2 * depth)?;
+
+    for child in children {
+      writeln!(f)?;
+      child.fmt_indented(f, depth + 1)?;
+    }
+
+    Ok(())
+  }
+}
+
+
+/// Renders the shape as an indented tree, with one node per line.
+impl fmt::Display for ProcessShape {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    self.fmt_indented(f, 0)
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DESCRIBE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Processes which can describe their structure, for diagnostics.
+///
+/// It is implemented by the processes of this crate, whenever the processes they are built from
+/// implement it. Describing a process neither consumes nor modifies it.
+pub trait Describe {
+  /// Returns the structure of the process.
+  fn describe(&self) -> ProcessShape;
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use signals::signals::ObservableSignal;
+  use signals::pure_signal::PureSignal;
+  use super::*;
+
+
+  #[test]
+  fn describe_joined_loops () {
+    // Same program as the `pure_signal_loop` example, with a named signal
+    let signal_1 = PureSignal::new().with_name("s");
+    let signal_2 = signal_1.clone();
+    let signal_3 = signal_1.clone();
+
+    let continue_status_1 = |_| -> LoopStatus<()> { LoopStatus::Continue };
+    let continue_status_2 = |_| -> LoopStatus<()> { LoopStatus::Continue };
+    let continue_status_3 = |_| -> LoopStatus<()> { LoopStatus::Continue };
+    let print_present     = |_| { println!("Present"); };
+    let print_absent      = |_| { println!("Absent"); };
+    let print_received    = |_| { println!("Signal received"); };
+
+    let emit_and_pause = signal_1.emit()
+      .pause()
+      .map(continue_status_1)
+      .while_loop();
+
+    let present_or_absent = signal_2.present(
+      value(()).map(print_present).pause(),
+      value(()).map(print_absent)
+    )
+    .map(continue_status_2)
+    .while_loop();
+
+    let await_immediate = signal_3.await_immediate()
+      .map(print_received)
+      .pause()
+      .map(continue_status_3)
+      .while_loop();
+
+    let process = emit_and_pause.join(present_or_absent.join(await_immediate));
+
+    let expected = [
+      "Join",
+      "  Loop",
+      "    Map",
+      "      Pause",
+      "        Emit(s)",
+      "  Join",
+      "    Loop",
+      "      Map",
+      "        Present(s)",
+      "          Pause",
+      "            Map",
+      "              Value",
+      "          Map",
+      "            Value",
+      "    Loop",
+      "      Map",
+      "        Pause",
+      "          Map",
+      "            AwaitImmediate(s)"
+    ].join("\n");
+
+    assert_eq!(process.describe().to_string(), expected);
+    assert_eq!(process.describe(), process.describe());
+  }
+
+  #[test]
+  fn describe_leaves_process_unchanged () {
+    let signal  = PureSignal::new();
+    let process = signal.clone().emit().join(signal.clone().await().map(|_| 42));
+
+    let shape = process.describe();
+    assert_eq!(shape.to_string(), "Join\n  Emit\n  Map\n    Await");
+    assert_eq!(execute_process(process), ((), 42));
+  }
+}
@@ -51,11 +51,35 @@ pub mod continuations;
 ///   the new content of `current_instant_tasks`.
 ///
 /// A runtime run continuations contained in those lists in this very order, until they are empty.
+/// Within each list, continuations are ran in the order they have been registered.
 /// The `instant` method is designed to do this over one instant, while the `execute` method does it
 /// until there is no more work to do.
 ///
+/// Once the end of current instant has begun, current instant is over: continuations registered
+/// to run during current instant are run during next instant, and continuations registered to run
+/// at the end of current instant are run at the end of next instant.
+///
+/// A runtime can be stopped gracefully (see `Runtime::stop`): it then emits a *shutdown signal*
+/// during one final instant, so that processes can release their resources before it stops.
+///
+/// Continuations are always registered within a *cancellation context* (see `CancelHandle`),
+/// which allows to cancel a whole group of continuations at once, e.g. for preempting a process,
+/// or to suspend them (see `Gate`).
+///
+/// The scheduling surface of a runtime is described by the `ReactiveRuntime` trait, so that
+/// continuations and basic processes can also be executed by alternative runtimes.
+/// Continuations and processes are executed by `Runtime` unless stated otherwise.
+///
 pub mod runtime;
 
+/// **Clocks are sources of wall-clock time.**
+///
+/// Reactive programs only deal with logical time (instants), but runtimes may need to measure
+/// real time, e.g. for aborting runaway executions. They do so through the `Clock` trait,
+/// so that a `MockClock` can replace the `SystemClock` in tests.
+///
+pub mod clock;
+
 /// **Processes are abstractions over continuations,
 /// which allow a simpler manipulation of reactive concepts.**
 ///
@@ -91,6 +115,14 @@ pub mod runtime;
 ///
 pub mod processes;
 
+/// **Processes can describe their structure, for diagnostics.**
+///
+/// The type of a composed process is hard to read, and a process has no other representation
+/// at runtime: the `Describe` trait returns the tree of combinators a process is built from
+/// (a `ProcessShape`), which can be printed as an indented tree.
+///
+pub mod describe;
+
 /// **Signals are a communication mechanisms available for processes.**
 ///
 /// A signal has a unique core (`SignalRuntime`), shared as an inner reference
@@ -101,6 +133,15 @@ pub mod processes;
 /// process concepts (see module `processes`). The semantics used by this module is inspired
 /// by the semantics of ReactiveML's signals.
 ///
+/// Observing a signal and emitting it are provided by two distinct traits, `ObservableSignal` and
+/// `EmittableSignal` (both implemented by any `Signal`), so that a read-only handle
+/// (`SignalReader`) or a write-only handle (`SignalEmitter`) can be given to parts of a program
+/// which must only observe or only emit a signal.
+///
+/// Custom processes reacting to signals can be written using the `delay_until_present`,
+/// `delay_after_present` and `delay_until_absent` methods of continuations, which encode the timing
+/// of signals (and on top of which the `await`, `await_immediate` and `present` processes are built).
+///
 /// This module actually is a super-module, which contains both signals' inner mechanisms,
 /// as well as two different types of signals (though they all use the same core):
 ///
@@ -124,3 +165,13 @@ pub mod processes;
 /// They can also use all the mechanisms available for pure signals.
 ///
 pub mod signals;
+
+/// **Testing utilities for reactive programs.**
+///
+/// Signals can be logged during an execution (see `SignalAssertions::attach`), so that tests can
+/// make assertions about the instants during which they are present, and about their values.
+///
+/// Signals can also be emitted chaotically but reproducibly (see `StressEmitter`),
+/// for testing the robustness of the processes using them.
+///
+pub mod testing;
@@ -1,23 +1,53 @@
+use std::fmt;
 use std::rc::Rc;
-use std::cell::Cell;
+use std::cell::{Cell, RefCell};
+use std::any::Any;
+use std::panic;
+use std::collections::BTreeMap;
+use std::sync::atomic::{AtomicUsize, Ordering};
 
-use continuations::Continuation;
-use runtime::Runtime;
+use continuations::{Continuation, CancelHandle, Gate};
+use runtime::{Runtime, ReactiveRuntime, RuntimeError};
+use describe::{Describe, ProcessShape};
+use signals::signals::{SignalRuntimeOf, ObservableSignal, EmittableSignal, DoUntilProcess, EmitOutputProcess};
+use signals::signal_core::SignalCore;
+use signals::pure_signal::PureSignal;
+use signals::value_signal::ValueSignal;
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A reactive process.
-pub trait Process: 'static {
+/// A reactive process, executed by a runtime of type `R` (see `ReactiveRuntime`),
+/// which is the runtime of this crate by default.
+///
+/// Only the combinators which schedule their continuations through `ReactiveRuntime` are generic,
+/// and can be executed by any runtime their sub-processes can be executed by:
+///
+/// * `pause`, `pause_n`, `map`, `inspect`, `map_with_runtime`, `flatten`, `and_then`, `then`,
+///   `if_else` and `finally`;
+/// * `join`, `join3`, `join4`, `zip`, `with`, `race` and `select`, as well as `join_all`,
+///   `sequence` and `select_any`;
+/// * `while_loop`, `while_loop_max`, `while_fold`, `scan`, `filter_map_loop`,
+///   `loop_forever`, `take` and `count_instants`;
+/// * `lazy`.
+///
+/// All other processes, including `value` and the processes dealing with signals, cancellation
+/// or the time of the runtime, are only executed by `Runtime`: alternative runtimes need to
+/// provide their own leaf processes.
+///
+/// Processes built by combinators can be cloned whenever the processes and the functions they are
+/// built from can: e.g. `value(x).pause().map(f)` is `Clone` if `x` and `f` are, which is the case
+/// of a closure whose captured variables are `Clone`.
+pub trait Process<R = Runtime>: 'static {
   /// The value created by the process.
   type Value;
 
   /// Executes the reactive process in the runtime, calls `next` with the resulting value.
-  fn call<C>(self, runtime: &mut Runtime, next: C)
+  fn call<C>(self, runtime: &mut R, next: C)
   where
-    C: Continuation<Self::Value>;
+    C: Continuation<Self::Value, R>;
 
   /// Returns a process which waits an instant before running.
   fn pause(self) -> PauseProcess<Self>
@@ -27,6 +57,15 @@ pub trait Process: 'static {
     PauseProcess { process: self }
   }
 
+  /// Returns a process which waits `instants` instants before running, i.e. which behaves like
+  /// itself if `instants` is 0.
+  fn pause_n(self, instants: usize) -> PauseNProcess<Self>
+  where
+    Self: Sized
+  {
+    PauseNProcess { process: self, instants }
+  }
+
   /// Returns a process which applies the given function to its value
   /// before passing the result to the continuation.
   fn map<F, O>(self, function: F) -> MapProcess<Self, F>
@@ -34,14 +73,34 @@ pub trait Process: 'static {
     Self: Sized,
     F: FnOnce(Self::Value) -> O + 'static
   {
-    MapProcess { process: self, function: function }
+    MapProcess { process: self, function }
+  }
+
+  /// Returns a process which calls the given function with a reference to its value,
+  /// before passing the value untouched to the continuation.
+  fn inspect<F>(self, function: F) -> InspectProcess<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(&Self::Value) + 'static
+  {
+    InspectProcess { process: self, function }
+  }
+
+  /// Returns a process which applies the given function to the runtime and its value,
+  /// e.g. to register tasks or emit signals imperatively, and returns the result.
+  fn map_with_runtime<F, O>(self, function: F) -> MapWithRuntimeProcess<Self, F>
+  where
+    Self: Sized,
+    F: FnOnce(&mut R, Self::Value) -> O + 'static
+  {
+    MapWithRuntimeProcess { process: self, function }
   }
 
   /// Returns a process which run the process returned by itself.
   fn flatten(self) -> FlattenProcess<Self>
   where
     Self: Sized,
-    Self::Value: Process
+    Self::Value: Process<R>
   {
     FlattenProcess { process: self }
   }
@@ -51,20 +110,248 @@ pub trait Process: 'static {
   where
     Self: Sized,
     F: FnOnce(Self::Value) -> O + 'static,
-    O: Process
+    O: Process<R>
   {
     self.map(function).flatten()
   }
 
+  /// Returns a process which runs itself, discards its value, then runs `process`
+  /// and gives the value of `process` to the continuation.
+  fn then<P>(self, process: P) -> ThenProcess<Self, P>
+  where
+    Self: Sized,
+    P: Process<R>
+  {
+    ThenProcess { process_1: self, process_2: process }
+  }
+
+  /// Returns a process which runs itself, then runs `then_process` if its value is `true`,
+  /// or `else_process` otherwise, and gives the value of the chosen process to the continuation.
+  fn if_else<P1, P2>(self, then_process: P1, else_process: P2) -> IfElseProcess<Self, P1, P2>
+  where
+    Self: Sized + Process<R, Value = bool>,
+    P1: Process<R>,
+    P2: Process<R, Value = P1::Value>
+  {
+    IfElseProcess { condition: self, then_process, else_process }
+  }
+
   /// Return a process which run two sub-processes and waits for both to terminate,
   /// so it can give a couple formed by both results to the continuation it is given.
   fn join<P, V>(self, process: P) -> JoinProcess<Self, P>
   where
     Self: Sized,
-    P: Process<Value = V>
+    P: Process<R, Value = V>
   {
     JoinProcess { process_1: self, process_2: process }
   }
+
+  /// Returns a process which runs three processes concurrently (itself first), and gives the
+  /// triple of their values to the continuation once all of them have completed.
+  fn join3<P2, P3>(self, process_2: P2, process_3: P3) -> Join3Process<Self, P2, P3>
+  where
+    Self: Sized,
+    P2: Process<R>,
+    P3: Process<R>
+  {
+    let join = JoinProcess { process_1: process_2, process_2: process_3 };
+    Join3Process { join: JoinProcess { process_1: self, process_2: join } }
+  }
+
+  /// Returns a process which runs four processes concurrently (itself first), and gives the
+  /// quadruple of their values to the continuation once all of them have completed.
+  fn join4<P2, P3, P4>(self, process_2: P2, process_3: P3, process_4: P4) -> Join4Process<Self, P2, P3, P4>
+  where
+    Self: Sized,
+    P2: Process<R>,
+    P3: Process<R>,
+    P4: Process<R>
+  {
+    let join = JoinProcess { process_1: process_3, process_2: process_4 };
+    let join = JoinProcess { process_1: process_2, process_2: join };
+    Join4Process { join: JoinProcess { process_1: self, process_2: join } }
+  }
+
+  /// Returns a process which runs itself and `side` concurrently, like `join`, but only gives
+  /// its own value to the continuation once both have completed: the value of `side` is discarded.
+  fn with<P>(self, side: P) -> WithProcess<Self, P>
+  where
+    Self: Sized,
+    P: Process<R>
+  {
+    WithProcess { join: JoinProcess { process_1: self, process_2: side } }
+  }
+
+  /// Returns a process which runs itself and `process` concurrently, and gives the value of the
+  /// first one to complete to the continuation. The value of the other one is discarded.
+  fn race<P>(self, process: P) -> RaceProcess<Self, P>
+  where
+    Self: Sized,
+    P: Process<R, Value = Self::Value>
+  {
+    RaceProcess { process_1: self, process_2: process }
+  }
+
+  /// Returns a process which runs itself and `process` concurrently, like `race`, but whose
+  /// processes may have values of different types: the value of the first one to complete is
+  /// given to the continuation, wrapped in `Either::Left` or `Either::Right` respectively.
+  fn select<P>(self, process: P) -> SelectEitherProcess<Self, P>
+  where
+    Self: Sized,
+    P: Process<R>
+  {
+    SelectEitherProcess { process_1: self, process_2: process }
+  }
+
+  /// Returns a process which completes exactly one instant after it starts:
+  /// if the process completes during the instant it started, its value is delivered
+  /// during next instant. See `OneInstantProcess` for processes lasting longer.
+  fn one_instant(self) -> OneInstantProcess<Self>
+  where
+    Self: Sized
+  {
+    OneInstantProcess { process: self, overrun_policy: OverrunPolicy::Panic }
+  }
+
+  /// Returns a process which records the instants during which it starts and completes
+  /// into the given profile, under the given label.
+  fn trace_timing(self, profile: TimingProfile, label: &'static str) -> TraceTimingProcess<Self>
+  where
+    Self: Sized
+  {
+    TraceTimingProcess { process: self, profile, label }
+  }
+
+  /// Returns a process which runs itself, and returns its value along with the number of instants
+  /// it took, i.e. the number of instants between the ones during which it started and completed.
+  fn count_instants(self) -> CountInstantsProcess<Self>
+  where
+    Self: Sized
+  {
+    CountInstantsProcess { process: self }
+  }
+
+  /// Returns a process which runs itself until `signal` is emitted: if the signal is emitted
+  /// before the process completes, the process is cancelled, and `None` is immediately returned.
+  /// Otherwise, the value of the process is returned.
+  fn until<S>(self, signal: S) -> UntilProcess<Self, S>
+  where
+    Self: Sized,
+    S: ObservableSignal + 'static
+  {
+    UntilProcess { process: self, signal }
+  }
+
+  /// Returns a process which runs itself until the end of an instant during which `signal` is
+  /// emitted, like the `do ... until` construct of ReactiveML: if the signal is emitted before
+  /// the process completes, the process still runs until the end of the instant, then it is
+  /// cancelled, and `None` is returned. Otherwise, the value of the process is returned.
+  ///
+  /// Unlike `until`, the preemption is thus weak: see `DoUntilProcess`.
+  fn do_until<S>(self, signal: S) -> DoUntilProcess<Self, S>
+  where
+    Self: Sized,
+    S: ObservableSignal + 'static
+  {
+    DoUntilProcess { process: self, signal }
+  }
+
+  /// Returns a process which runs itself, emits `signal` with its value during the instant it
+  /// completes, and returns this value.
+  fn emit_output<S>(self, signal: S) -> EmitOutputProcess<Self, S>
+  where
+    Self: Sized,
+    S: EmittableSignal<Element = Self::Value> + 'static
+  {
+    EmitOutputProcess { process: self, signal }
+  }
+
+  /// Returns a process which runs itself only during the instants `signal` is present, like the
+  /// `do ... when` construct of ReactiveML: the steps of the process (including the first one)
+  /// are suspended until an instant during which the signal is emitted.
+  ///
+  /// Pausing thus waits for the next instant the signal is present.
+  fn when<S>(self, signal: S) -> WhenProcess<Self, S>
+  where
+    Self: Sized,
+    S: ObservableSignal + 'static
+  {
+    WhenProcess { process: self, signal }
+  }
+
+  /// Returns a process which runs itself, suspending it from the instant following an emission of
+  /// `suspend`, and resuming it from the instant following an emission of `resume`,
+  /// like the `control ... with` construct of ReactiveML.
+  ///
+  /// While the process is suspended, its steps are parked until it is resumed. Emitting both
+  /// signals during the same instant does nothing.
+  fn control<S1, S2>(self, suspend: S1, resume: S2) -> ControlProcess<Self, S1, S2>
+  where
+    Self: Sized,
+    S1: ObservableSignal + 'static,
+    S2: ObservableSignal + 'static
+  {
+    ControlProcess { process: self, suspend, resume }
+  }
+
+  /// Returns a process which runs itself during at most `instants` instants: if the process has
+  /// not completed by the end of the `instants`-th instant following its start, it is cancelled,
+  /// and `None` is returned. Otherwise, the value of the process is returned.
+  ///
+  /// A process completing during the instant the timeout expires thus wins.
+  fn timeout(self, instants: usize) -> TimeoutProcess<Self>
+  where
+    Self: Sized
+  {
+    TimeoutProcess { process: self, instants }
+  }
+
+  /// Returns a process which runs itself, and returns its value, or the payload of the first panic
+  /// raised while running it (during any instant), in which case the process is cancelled.
+  fn catch_panic(self) -> CatchPanicProcess<Self>
+  where
+    Self: Sized
+  {
+    CatchPanicProcess { process: self }
+  }
+
+  /// Returns a process which runs itself and returns its value, along with a handle through which
+  /// any number of other processes can await a clone of this value (see `SharedHandle`).
+  fn shared(self) -> (SharedProcess<Self, Self::Value>, SharedHandle<Self::Value>)
+  where
+    Self: Sized,
+    Self::Value: Clone
+  {
+    let handle = SharedHandle { state: Rc::new(RefCell::new(SharedState { value: None, waiters: Vec::new() })) };
+    (SharedProcess { process: self, handle: handle.clone() }, handle)
+  }
+
+  /// Returns a process which starts itself in the background during current instant, and
+  /// immediately returns a handle through which its value can be awaited (see `SpawnHandle`).
+  fn spawn_background(self) -> SpawnBackgroundProcess<Self>
+  where
+    Self: Sized
+  {
+    SpawnBackgroundProcess { process: self }
+  }
+
+  /// Returns a process hiding the type of this process, e.g. to store processes of different types
+  /// in a collection, or to choose between processes at runtime.
+  fn boxed(self) -> BoxProcess<<Self as Process>::Value>
+  where
+    Self: Sized + Process
+  {
+    BoxProcess::new(self)
+  }
+
+  /// Returns a process which calls `cleanup` once it has completed, before returning its value.
+  fn finally<F>(self, cleanup: F) -> FinallyProcess<Self, F>
+  where
+    Self: Sized,
+    F: FnOnce(&mut R) + 'static
+  {
+    FinallyProcess { process: self, cleanup }
+  }
 }
 
 
@@ -72,28 +359,101 @@ pub trait Process: 'static {
 // MUTABLE PROCESSES
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-// Hack by Mathieu Fehr, for testing Value field equality on Process
-pub trait Is {
-    type Value;
-}
-
-impl<T> Is for T {
-    type Value = T;
-}
-
 /// A process that can be executed multiple times, modifying its environement each time.
-pub trait ProcessMut: Process {
+pub trait ProcessMut<R = Runtime>: Process<R> {
   /// Executes the mutable process in the runtime, then calls `next` with the process and the
   /// process's return value.
-  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where
+  fn call_mut<C>(self, runtime: &mut R, next: C) where
     Self: Sized,
-    C: Continuation<(Self, Self::Value)>;
+    C: Continuation<(Self, Self::Value), R>;
 
-  fn while_loop<T>(self) -> WhileProcess<Self> where
+  /// Returns a process which alternately runs itself and `process`, pausing after each run.
+  /// Its value is the value of the process which has been ran during this run.
+  fn interleave<P>(self, process: P) -> InterleaveProcess<Self, P>
+  where
     Self: Sized,
-    Self::Value: Is<Value = LoopStatus<T>>
+    P: ProcessMut<R>
   {
-    WhileProcess { process: self }
+    InterleaveProcess { process_1: self, process_2: process, first_turn: true }
+  }
+
+  /// Returns a process which runs itself `n` times in a row, and returns the values of the runs.
+  /// It is the same process as `take`.
+  fn first_n(self, n: usize) -> FirstNProcess<Self> where Self: Sized {
+    self.take(n)
+  }
+
+  /// Returns a mutable process which runs one iteration of itself and of `process` concurrently,
+  /// and returns the pair of their values, like `Iterator::zip`: each run waits for both
+  /// iterations to complete, so that the fastest process never runs ahead of the other one.
+  fn zip<P>(self, process: P) -> ZipProcess<Self, P>
+  where
+    Self: Sized,
+    P: ProcessMut<R>
+  {
+    ZipProcess { join: JoinProcess { process_1: self, process_2: process } }
+  }
+
+  /// Returns a process which runs itself `n` times in a row, and returns the values of the runs,
+  /// like `Iterator::take`: see `TakeProcess`. `first_n` and `repeat` are other names for it.
+  fn take(self, n: usize) -> TakeProcess<Self> where Self: Sized {
+    TakeProcess { process: self, n }
+  }
+
+  /// Returns a process which runs itself `n` times in a row, and returns the values of the runs.
+  /// It is the same process as `take`.
+  fn repeat(self, n: usize) -> RepeatProcess<Self> where Self: Sized {
+    self.take(n)
+  }
+
+  /// Returns a process which runs itself in a loop, threading a state across the iterations:
+  /// after each run, `step` is called with the current state (initially `initial`) and the value
+  /// of the run, and returns either the state of the next iteration, or the value of the loop.
+  fn while_fold<S, F, V>(self, initial: S, step: F) -> WhileFoldProcess<Self, S, F>
+  where
+    Self: Sized,
+    F: FnMut(S, Self::Value) -> LoopStatusWith<S, V> + 'static
+  {
+    WhileFoldProcess { process: self, state: initial, step }
+  }
+
+  /// Returns a mutable process which runs itself, and returns the result of `function` applied to
+  /// a state and its value, like `Iterator::scan`: the state (initially `initial`) can be modified
+  /// by `function`, and is kept across the runs.
+  fn scan<S, F, O>(self, initial: S, function: F) -> ScanProcess<Self, S, F>
+  where
+    Self: Sized,
+    F: FnMut(&mut S, Self::Value) -> O + 'static
+  {
+    ScanProcess { process: self, state: initial, function }
+  }
+
+  /// Returns a mutable process which runs itself until `function` maps its value to `Some`, and
+  /// returns the value it holds: runs whose value is mapped to `None` are skipped, and followed by
+  /// another run, scheduled during the same instant.
+  fn filter_map_loop<F, O>(self, function: F) -> FilterMapLoopProcess<Self, F>
+  where
+    Self: Sized,
+    F: FnMut(Self::Value) -> Option<O> + 'static
+  {
+    FilterMapLoopProcess { process: self, function }
+  }
+
+  /// Returns a process which runs itself again each time it completes, and thus never completes.
+  fn loop_forever(self) -> LoopProcess<Self> where Self: Sized {
+    LoopProcess { process: self }
+  }
+
+  /// Returns a mutable process whose first run also runs itself `n` more times beforehand,
+  /// discarding their values: the values of the first `n` runs are thus never seen downstream.
+  fn skip(self, n: usize) -> SkipProcess<Self> where Self: Sized {
+    SkipProcess { process: self, n }
+  }
+
+  /// Returns an iterator running the process once in the given runtime for each value it yields,
+  /// executing as many instants as needed. See `ValuesIter`.
+  fn into_values(self, runtime: Runtime) -> ValuesIter<Self> where Self: Sized {
+    ValuesIter { process: Some(self), runtime }
   }
 }
 
@@ -111,28 +471,65 @@ where
 {
   let mut runtime = Runtime::new();
 
-  let mut return_value = Rc::new(Cell::new(None));
-  let mut return_value_clone = return_value.clone();
+  let return_value = Rc::new(Cell::new(None));
+  let return_value_clone = return_value.clone();
 
-  let main_continuation = move |r: &mut Runtime, v: ()| {
-    process.call(r, move |r: &mut Runtime, v: V| {
+  let main_continuation = move |r: &mut Runtime, _: ()| {
+    process.call(r, move |_: &mut Runtime, v: V| {
       // println!("Return value has been computed: {:?}", v);
       return_value.set(Some(v));
     });
   };
 
-  runtime.on_current_instant(Box::new(main_continuation));
+  runtime.schedule_current(main_continuation);
   runtime.execute();
 
   return_value_clone.take().unwrap()
 }
 
 
+/// Execute the given process in a freshly created `Runtime`, and return the result value,
+/// or the error which prevented the runtime from completing the process.
+pub fn try_execute_process<P, V>(process: P) -> Result<V, RuntimeError>
+where
+  P: Process<Value = V>,
+  V: 'static
+{
+  let mut runtime = Runtime::new();
+
+  let return_value_1 = Rc::new(Cell::new(None));
+  let return_value_2 = return_value_1.clone();
+
+  runtime.try_on_current_instant(Box::new(move |r: &mut Runtime, _: ()| {
+    process.call(r, move |_: &mut Runtime, v: V| return_value_1.set(Some(v)));
+  }))?;
+  runtime.try_execute()?;
+
+  Ok(return_value_2.take().expect("the process did not complete"))
+}
+
+
+/// Iterator over the values of the successive runs of a mutable process (see `iterate`).
+pub type ProcessIterator<P> = ValuesIter<P>;
+
+
+/// Returns an iterator running the given mutable process once per call to `next`, in a runtime
+/// kept across the calls, and returning the value of each run.
+///
+/// Each call executes instants until the run completes, starting with the instant following the
+/// completion of the previous run. If the runtime runs out of work before (e.g. because the
+/// process awaits a signal which is never emitted), `None` is returned, and the iteration is over.
+pub fn iterate<P>(process: P) -> ProcessIterator<P> where P: ProcessMut {
+  process.into_values(Runtime::new())
+}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // VALUE PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
 /// A process returning a single value it holds.
+#[derive(Clone)]
 pub struct ValueProcess<V> {
   value: V
 }
@@ -140,7 +537,7 @@ pub struct ValueProcess<V> {
 
 /// Returns a new `ValueProcess` containing the given value.
 pub fn value<V> (value: V) -> ValueProcess<V> {
-  ValueProcess { value: value }
+  ValueProcess { value }
 }
 
 
@@ -168,412 +565,5912 @@ where
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// PAUSE PROCESS
+// FN PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A process pausing one instant before calling itself.
-pub struct PauseProcess<P> {
-  process: P
+/// A process returning the value computed by a function, called when the process is executed.
+#[derive(Clone)]
+pub struct FnProcess<F> {
+  function: F
+}
+
+
+/// Returns a new `FnProcess` calling the given function, i.e. a process like `value(function())`,
+/// which only calls `function` once it is executed rather than when it is built.
+///
+/// Functions without arguments being processes themselves, `FnProcess` only wraps one, so that
+/// the process has a named type, and runs it as such.
+pub fn from_fn<F, V>(function: F) -> FnProcess<F> where F: FnOnce() -> V + 'static {
+  FnProcess { function }
 }
 
-impl<P> Process for PauseProcess<P>
+
+impl<F, V> Process for FnProcess<F>
 where
-  P: Process + 'static
+  F: FnOnce() -> V + 'static
 {
-  type Value = P::Value;
+  type Value = V;
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    self.process.call(runtime, next.pause());
+    Process::call(self.function, runtime, next);
   }
 }
 
 
-impl<P, V> ProcessMut for PauseProcess<P>
+impl<F, V> ProcessMut for FnProcess<F>
 where
-  P: ProcessMut<Value = V>,
-  V: 'static
+  F: FnMut() -> V + 'static
 {
   fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
-    self.process.call_mut(runtime, |r: &mut Runtime, (p, v): (P, V)| {
-      next.pause().call(r, (p.pause(), v))
-    });
+    ProcessMut::call_mut(self.function, runtime, next.map(|(function, v)| (FnProcess { function }, v)));
   }
 }
 
 
-///////////////////////////////////////////////////////////////////////////////////////////////////
-// MAP PROCESS
-///////////////////////////////////////////////////////////////////////////////////////////////////
-
-/// A process applying a function to its output value.
-pub struct MapProcess<P, F> {
-  process: P,
-  function: F
-}
-
-impl<P, F, I, O> Process for MapProcess<P, F>
+/// Functions without arguments are processes: e.g. `signal.present(|| 1, || 2)` returns `1`
+/// if `signal` is present, and `2` otherwise.
+///
+/// `Process` is implemented for the functions themselves rather than through an `IntoProcess`
+/// conversion accepted by the combinators: a conversion implemented both for any process and for
+/// any function would overlap, since the compiler cannot rule out a type being both. It does not
+/// overlap with the implementation of `Continuation` for functions, which is another trait.
+impl<F, V> Process for F
 where
-  P: Process<Value = I>,
-  F: FnOnce(I) -> O + 'static
+  F: FnOnce() -> V + 'static
 {
-  type Value = O;
+  type Value = V;
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    self.process.call(runtime, next.map(self.function));
+    next.call(runtime, self());
   }
 }
 
 
-impl<P, F, I, O> ProcessMut for MapProcess<P, F>
+impl<F, V> ProcessMut for F
 where
-  P: ProcessMut<Value = I>,
-  F: FnMut(I) -> O + 'static,
+  F: FnMut() -> V + 'static
 {
-  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
-    let mut f = self.function;
-
-    self.process.call_mut(runtime, move |r: &mut Runtime, (p, v): (P, I)| {
-      let value = f(v);
-      next.call(r, (p.map(f), value));
-    });
+  fn call_mut<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let value = self();
+    next.call(runtime, (self, value));
   }
 }
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// FLATTEN PROCESS
+// LAZY PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A process calling the process it contains, and giving the resulting value to itself.
-pub struct FlattenProcess<PP> {
-  process: PP
+/// A process running the process built by a function, called when the process is executed.
+///
+/// It is a mutable process if the function can be called multiple times: each run builds and
+/// runs a new process, which therefore does not need to be mutable.
+#[derive(Clone)]
+pub struct LazyProcess<F> {
+  function: F
+}
+
+
+/// Returns a new `LazyProcess` calling the given function, i.e. a process like `function()`,
+/// which only builds the process once it is executed rather than when it is built.
+pub fn lazy<F, P>(function: F) -> LazyProcess<F> where F: FnOnce() -> P + 'static {
+  LazyProcess { function }
 }
 
-impl<PP, P> Process for FlattenProcess<PP>
+
+impl<F, P, R> Process<R> for LazyProcess<F>
 where
-  PP: Process<Value = P>,
-  P: Process
+  F: FnOnce() -> P + 'static,
+  P: Process<R>
 {
   type Value = P::Value;
 
-  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    self.process.call(runtime, |runtime: &mut Runtime, value: P| {
-      value.call(runtime, next);
-    });
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    (self.function)().call(runtime, next);
   }
 }
 
 
-impl<PP, P, V> ProcessMut for FlattenProcess<PP>
+impl<F, P, R> ProcessMut<R> for LazyProcess<F>
 where
-  PP: ProcessMut<Value = P>,
-  P:  ProcessMut<Value = V>
+  F: FnMut() -> P + 'static,
+  P: Process<R>,
+  R: 'static
 {
-  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
-    self.process.call_mut(runtime, |runtime: &mut Runtime, (pp, p): (PP, P)| {
-      p.call_mut(runtime, |r: &mut Runtime, (p, v): (P, V)| {
-        next.call(r, (pp.flatten(), v));
-      });
-    });
+  fn call_mut<C>(mut self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let process = (self.function)();
+    process.call(runtime, move |r: &mut R, v: P::Value| next.call(r, (self, v)));
   }
 }
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// JOIN PROCESS
+// EVERY INSTANT PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A process calling two sub-processes before calling the next process with both returned values.
+/// A mutable process returning the value computed by a function, at most once per instant.
+#[derive(Clone)]
+pub struct EveryInstantProcess<F> {
+  function    : F,
+  iterations  : u64,
+  last_instant: Option<usize>
+}
 
-/// A helper structure, used by `JoinProcess` to synchronize the call of two processes.
-///
-/// This version is specific to the implementaion of `Process`.
-struct JoinPoint<V1, V2, C>
-where
-  C: Continuation<(V1, V2)>
-{
-  P1_result: Rc<Cell<Option<V1>>>,
-  P2_result: Rc<Cell<Option<V2>>>,
-  next     : Rc<Cell<Option<C>>>
+
+/// Returns a new `EveryInstantProcess` calling the given function with the number of previous
+/// runs of the process, i.e. a mutable process like `from_fn`, but which implicitly pauses
+/// between its runs: a run starting during the instant of the previous one waits for the next
+/// instant. When ran in a loop, it thus returns exactly one value per instant.
+pub fn every_instant<F, V>(function: F) -> EveryInstantProcess<F> where F: FnMut(u64) -> V + 'static {
+  EveryInstantProcess { function, iterations: 0, last_instant: None }
 }
 
-impl<V1, V2, C> JoinPoint<V1, V2, C>
+
+impl<F, V> Process for EveryInstantProcess<F>
 where
-  C: Continuation<(V1, V2)> + 'static
+  F: FnMut(u64) -> V + 'static
 {
-  /// Create a new `JoinPoint` with the given `next` continuation.
-  fn new(next: C) -> JoinPoint<V1, V2, C> {
-    JoinPoint {
-      P1_result: Rc::new(Cell::new(None)),
-      P2_result: Rc::new(Cell::new(None)),
-      next:      Rc::new(Cell::new(Some(next)))
-    }
+  type Value = V;
+
+  fn call<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let value = (self.function)(self.iterations);
+    next.call(runtime, value);
   }
 }
 
 
-/// A process calling two sub-processes in a *synchronized* way,
-/// i.e. waiting for both to finnish running before running the given `next` continuation.
-pub struct JoinProcess<P1, P2>
+impl<F, V> ProcessMut for EveryInstantProcess<F>
 where
-  P1: Process + 'static,
-  P2: Process + 'static
+  F: FnMut(u64) -> V + 'static
 {
-  process_1: P1,
-  process_2: P2
+  fn call_mut<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    if self.last_instant == Some(runtime.instant_index()) {
+      return runtime.schedule_next(move |r: &mut Runtime, ()| self.call_mut(r, next));
+    }
+
+    let value = (self.function)(self.iterations);
+    self.iterations  += 1;
+    self.last_instant = Some(runtime.instant_index());
+
+    next.call(runtime, (self, value));
+  }
 }
 
 
-impl<P1, P2> Process for JoinProcess<P1, P2>
-where
-  P1: Process + 'static,
-  P2: Process + 'static
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PAUSE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process pausing one instant before calling itself.
+#[derive(Clone)]
+pub struct PauseProcess<P> {
+  process: P
+}
+
+impl<P, R> Process<R> for PauseProcess<P>
+where
+  P: Process<R> + 'static,
+  R: ReactiveRuntime
 {
-  type Value = (P1::Value, P2::Value);
+  type Value = P::Value;
 
-  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    let join_point_1 = Rc::new(JoinPoint::new(next));
-    let join_point_2 = join_point_1.clone();
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.process.call(runtime, next.pause());
+  }
+}
 
-    self.process_1.call(runtime, move |runtime: &mut Runtime, P1_result: P1::Value| {
-      println!("Running process 1 in JoinProcess");
-      let P2_result = join_point_1.P2_result.take();
 
-      if P2_result.is_some() {
-        let next_input = (P1_result, P2_result.unwrap());
-        let next = join_point_1.next.take().unwrap();
-        next.call(runtime, next_input);
-      }
-      else {
-        join_point_1.P1_result.set(Some(P1_result));
-      }
+impl<P, V, R> ProcessMut<R> for PauseProcess<P>
+where
+  P: ProcessMut<R, Value = V>,
+  V: 'static,
+  R: ReactiveRuntime
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.process.call_mut(runtime, |r: &mut R, (p, v): (P, V)| {
+      next.pause().call(r, (p.pause(), v))
     });
+  }
+}
 
-    self.process_2.call(runtime, move |runtime: &mut Runtime, P2_result: P2::Value| {
-      println!("Running process 2 in JoinProcess");
-      let P1_result = join_point_2.P1_result.take();
 
-      if P1_result.is_some() {
-        let next_input = (P1_result.unwrap(), P2_result);
-        let next = join_point_2.next.take().unwrap();
-        next.call(runtime, next_input);
-      }
-      else {
-        join_point_2.P2_result.set(Some(P2_result));
-      }
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PAUSE N PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process pausing a given number of instants before calling itself.
+///
+/// Unlike a chain of `PauseProcess`, its size does not depend on the number of instants.
+#[derive(Clone)]
+pub struct PauseNProcess<P> {
+  process : P,
+  instants: usize
+}
+
+impl<P, R> Process<R> for PauseNProcess<P>
+where
+  P: Process<R> + 'static,
+  R: ReactiveRuntime
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.process.call(runtime, next.pause_n(self.instants));
+  }
+}
+
+
+impl<P, V, R> ProcessMut<R> for PauseNProcess<P>
+where
+  P: ProcessMut<R, Value = V>,
+  V: 'static,
+  R: ReactiveRuntime
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let instants = self.instants;
+
+    self.process.call_mut(runtime, move |r: &mut R, (p, v): (P, V)| {
+      next.pause_n(instants).call(r, (p.pause_n(instants), v))
     });
   }
 }
 
 
-// Mutable process version below
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// MAP PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// A helper structure, used by `JoinProcess` to synchronize the call of two processes.
+/// A process applying a function to its output value.
 ///
-/// This version is specific to the implementaion of `ProcessMut`.
-struct JoinPointMut<C, P1, P2, V1, V2>
+/// Mapping a `MapProcess` again fuses both functions into a single one (see `Compose`),
+/// so that a chain of `map` calls runs as a single mapping stage.
+#[derive(Clone)]
+pub struct MapProcess<P, F> {
+  process: P,
+  function: F
+}
+
+impl<P, F> MapProcess<P, F> {
+  /// Returns a process which applies the given function to its value, after its own function.
+  ///
+  /// This shadows the `map` method of processes, whose result would wrap this process
+  /// instead of composing both functions.
+  pub fn map<I, G, O>(self, function: G) -> MapProcess<P, Compose<F, G>>
+  where
+    F: MapFunction<I>,
+    G: FnOnce(F::Output) -> O + 'static
+  {
+    MapProcess { process: self.process, function: Compose { first: self.function, second: function } }
+  }
+}
+
+impl<P, F, I, O, R> Process<R> for MapProcess<P, F>
 where
-  C: Continuation<(JoinProcess<P1, P2>, (V1, V2))>,
-  P1: ProcessMut<Value = V1>,
-  P2: ProcessMut<Value = V2>
+  P: Process<R, Value = I>,
+  F: MapFunction<I, Output = O> + 'static
 {
-  P1_result: Rc<Cell<Option<V1>>>,
-  P2_result: Rc<Cell<Option<V2>>>,
-  next     : Rc<Cell<Option<C>>>,
-  p1       : Rc<Cell<Option<P1>>>,
-  p2       : Rc<Cell<Option<P2>>>
+  type Value = O;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let function = self.function;
+    self.process.call(runtime, next.map(move |v: I| function.apply_once(v)));
+  }
 }
 
 
-impl<C, P1, P2, V1, V2> JoinPointMut<C, P1, P2, V1, V2>
+impl<P, F, I, O, R> ProcessMut<R> for MapProcess<P, F>
 where
-  C: Continuation<(JoinProcess<P1, P2>, (V1, V2))> + 'static,
-  P1: ProcessMut<Value = V1>,
-  P2: ProcessMut<Value = V2>
+  P: ProcessMut<R, Value = I>,
+  F: MapFunctionMut<I, Output = O> + 'static,
+  R: 'static
 {
-  fn new(p1: P1, p2: P2, next: C) -> JoinPointMut<C, P1, P2, V1, V2> {
-    JoinPointMut {
-      P1_result: Rc::new(Cell::new(None)),
-      P2_result: Rc::new(Cell::new(None)),
-      next:      Rc::new(Cell::new(Some(next))),
-      p1:   Rc::new(Cell::new(Some(p1))),
-      p2:   Rc::new(Cell::new(Some(p2)))
-    }
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let mut function = self.function;
+
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, I)| {
+      let value = function.apply(v);
+      next.call(r, (MapProcess { process, function }, value));
+    });
+  }
+}
+
+
+/// Function applied by a `MapProcess` to the value of its process: either a closure,
+/// or the composition of several closures (see `Compose`).
+pub trait MapFunction<I> {
+  /// Type of the result of the function.
+  type Output;
+
+  /// Applies the function, which can only be applied once.
+  fn apply_once(self, input: I) -> Self::Output;
+}
+
+
+/// Function applied by a mutable `MapProcess`, which can be applied multiple times.
+pub trait MapFunctionMut<I>: MapFunction<I> {
+  /// Applies the function.
+  fn apply(&mut self, input: I) -> Self::Output;
+}
+
+
+impl<F, I, O> MapFunction<I> for F where F: FnOnce(I) -> O {
+  type Output = O;
+
+  fn apply_once(self, input: I) -> O {
+    self(input)
+  }
+}
+
+
+impl<F, I, O> MapFunctionMut<I> for F where F: FnMut(I) -> O {
+  fn apply(&mut self, input: I) -> O {
+    self(input)
   }
 }
 
 
-impl<P1, P2> ProcessMut for JoinProcess<P1, P2>
+/// Composition of two functions, applying `second` to the result of `first`.
+/// It results from fusing adjacent `map` calls, without boxing either function.
+#[derive(Clone)]
+pub struct Compose<F, G> {
+  first : F,
+  second: G
+}
+
+
+impl<F, G, I, O> MapFunction<I> for Compose<F, G>
 where
-  P1: ProcessMut + 'static,
-  P2: ProcessMut + 'static
+  F: MapFunction<I>,
+  G: FnOnce(F::Output) -> O
 {
-  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
-    let join_point_1 = Rc::new(JoinPointMut::new(self.process_1, self.process_2, next));
-    let join_point_2 = join_point_1.clone();
-    let join_point_3 = join_point_1.clone();
+  type Output = O;
 
-    join_point_3.p1.take().unwrap().call_mut(runtime, move |runtime: &mut Runtime, (p1, v1): (P1, P1::Value)| {
-      join_point_1.p1.set(Some(p1));
-      let P2_result = join_point_1.P2_result.take();
+  fn apply_once(self, input: I) -> O {
+    (self.second)(self.first.apply_once(input))
+  }
+}
 
-      if P2_result.is_some() {
-        let p1 = join_point_1.p1.take().unwrap();
-        let p2 = join_point_1.p2.take().unwrap();
 
-        let next_input = (v1, P2_result.unwrap());
-        let next = join_point_1.next.take().unwrap();
+impl<F, G, I, O> MapFunctionMut<I> for Compose<F, G>
+where
+  F: MapFunctionMut<I>,
+  G: FnMut(F::Output) -> O
+{
+  fn apply(&mut self, input: I) -> O {
+    (self.second)(self.first.apply(input))
+  }
+}
 
-        next.call(runtime, (p1.join(p2), next_input));
-      }
-      else {
-        join_point_1.P1_result.set(Some(v1));
-      }
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// INSPECT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process calling a function with a reference to its output value, e.g. for debugging.
+#[derive(Clone)]
+pub struct InspectProcess<P, F> {
+  process : P,
+  function: F
+}
+
+impl<P, F, R> Process<R> for InspectProcess<P, F>
+where
+  P: Process<R>,
+  F: FnMut(&P::Value) + 'static,
+  R: 'static
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let mut function = self.function;
+
+    self.process.call(runtime, move |r: &mut R, v: P::Value| {
+      function(&v);
+      next.call(r, v);
     });
+  }
+}
 
-    join_point_3.p2.take().unwrap().call_mut(runtime, move |runtime: &mut Runtime, (p2, v2): (P2, P2::Value)| {
-      join_point_2.p2.set(Some(p2));
-      let P1_result = join_point_2.P1_result.take();
 
-      if P1_result.is_some() {
-        let p1 = join_point_2.p1.take().unwrap();
-        let p2 = join_point_2.p2.take().unwrap();
+impl<P, F, R> ProcessMut<R> for InspectProcess<P, F>
+where
+  P: ProcessMut<R>,
+  F: FnMut(&P::Value) + 'static,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let mut function = self.function;
 
-        let next_input = (P1_result.unwrap(), v2);
-        let next = join_point_2.next.take().unwrap();
-        next.call(runtime, (p1.join(p2), next_input));
-      }
-      else {
-        join_point_2.P2_result.set(Some(v2));
-      }
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, P::Value)| {
+      function(&v);
+      next.call(r, (InspectProcess { process, function }, v));
     });
   }
 }
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// WHILE PROCESS
+// MAP WITH RUNTIME PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Indicates if a loop is finished.
-#[derive(Debug)]
-pub enum LoopStatus<V> { Continue, Exit(V) }
+/// A process applying a function to the runtime and its output value.
+///
+/// It is a mutable process if the function can be called multiple times.
+#[derive(Clone)]
+pub struct MapWithRuntimeProcess<P, F> {
+  process : P,
+  function: F
+}
 
-/// A process calling itself in a *while* loop fashion, until it returns `LoopStatus::Exit<V>`,
-/// which contains the value to give to the `next` continuation.
-pub struct WhileProcess<P>
+impl<P, F, O, R> Process<R> for MapWithRuntimeProcess<P, F>
 where
-  P: Process + 'static
+  P: Process<R>,
+  F: FnOnce(&mut R, P::Value) -> O + 'static,
+  R: 'static
 {
-  process: P
+  type Value = O;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let function = self.function;
+
+    self.process.call(runtime, move |r: &mut R, v: P::Value| {
+      let value = function(r, v);
+      next.call(r, value);
+    });
+  }
 }
 
 
-impl<P, V> Process for WhileProcess<P>
+impl<P, F, O, R> ProcessMut<R> for MapWithRuntimeProcess<P, F>
 where
-  P: ProcessMut<Value = LoopStatus<V>>
+  P: ProcessMut<R>,
+  F: FnMut(&mut R, P::Value) -> O + 'static,
+  R: 'static
 {
-  type Value = V;
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let mut function = self.function;
 
-  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self::Value)> {
-    self.process.call_mut(runtime, |r: &mut Runtime, (p, v): (P, LoopStatus<V>)| {
-      match v {
-        LoopStatus::Continue     => p.while_loop().call(r, next),
-        LoopStatus::Exit(output) => next.call(r, output)
-      };
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, P::Value)| {
+      let value = function(r, v);
+      next.call(r, (MapWithRuntimeProcess { process, function }, value));
     });
   }
 }
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// TESTS
+// SCAN PROCESS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-#[cfg(test)]
-mod tests {
-  use std::rc::Rc;
-  use std::cell::{Cell, RefCell};
+/// A process applying a function to a state and its output value, keeping the state across runs
+/// (see `ProcessMut::scan`).
+#[derive(Clone)]
+pub struct ScanProcess<P, S, F> {
+  process : P,
+  state   : S,
+  function: F
+}
 
-  use super::*;
+impl<P, S, F, O, R> Process<R> for ScanProcess<P, S, F>
+where
+  P: Process<R>,
+  S: 'static,
+  F: FnMut(&mut S, P::Value) -> O + 'static,
+  R: 'static
+{
+  type Value = O;
 
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let mut state    = self.state;
+    let mut function = self.function;
 
-  #[test]
-  fn wait_two_instants () {
-    let mut runtime = Runtime::new();
+    self.process.call(runtime, move |r: &mut R, v: P::Value| {
+      let value = function(&mut state, v);
+      next.call(r, value);
+    });
+  }
+}
 
-    let flag_ref      = Rc::new(RefCell::new(0));
-    let flag_ref_copy = flag_ref.clone();
 
-    runtime.on_current_instant(Box::new((move |_r: &mut Runtime, ()| {
-      *flag_ref_copy.borrow_mut() = 42;
-    }).pause().pause()));
+impl<P, S, F, O, R> ProcessMut<R> for ScanProcess<P, S, F>
+where
+  P: ProcessMut<R>,
+  S: 'static,
+  F: FnMut(&mut S, P::Value) -> O + 'static,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let mut state    = self.state;
+    let mut function = self.function;
 
-    let mut work_remains = runtime.instant();
-    assert_eq!(*flag_ref.borrow_mut(), 0);
-    assert_eq!(work_remains, true);
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, P::Value)| {
+      let value = function(&mut state, v);
+      next.call(r, (ScanProcess { process, state, function }, value));
+    });
+  }
+}
 
-    work_remains = runtime.instant();
-    assert_eq!(*flag_ref.borrow_mut(), 0);
-    assert_eq!(work_remains, true);
 
-    work_remains = runtime.instant();
-    assert_eq!(*flag_ref.borrow_mut(), 42);
-    assert_eq!(work_remains, false);
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FLATTEN PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
 
-    // There should not be any task left
-    work_remains = runtime.instant();
-    assert_eq!(work_remains, false);
-  }
+/// A process calling the process it contains, and giving the resulting value to itself.
+///
+/// It is a mutable process if the process it contains is: each run builds and consumes a new
+/// process, which therefore does not need to be mutable.
+#[derive(Clone)]
+pub struct FlattenProcess<PP> {
+  process: PP
+}
+
+impl<PP, P, R> Process<R> for FlattenProcess<PP>
+where
+  PP: Process<R, Value = P>,
+  P: Process<R>,
+  R: 'static
+{
+  type Value = P::Value;
 
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.process.call(runtime, |runtime: &mut R, value: P| {
+      value.call(runtime, next);
+    });
+  }
+}
 
-  #[test]
-  fn map_to_multiply () {
-    let process = value(21).map(|v| { 2*v });
-    let return_value = execute_process(process);
 
-    assert_eq!(42, return_value);
+impl<PP, P, V, R> ProcessMut<R> for FlattenProcess<PP>
+where
+  PP: ProcessMut<R, Value = P>,
+  P:  Process<R, Value = V>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.process.call_mut(runtime, |runtime: &mut R, (pp, p): (PP, P)| {
+      p.call(runtime, |r: &mut R, v: V| {
+        next.call(r, (pp.flatten(), v));
+      });
+    });
   }
+}
 
 
-  #[test]
-  fn map_and_pause_to_multiply () {
-    let process = value(21).pause().map(|v| { 2*v }).pause();
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// THEN PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
 
-    let return_value = execute_process(process);
-    assert_eq!(42, return_value);
-  }
+/// A process running two processes in sequence, and giving the value of the second one
+/// to its continuation.
+#[derive(Clone)]
+pub struct ThenProcess<P1, P2> {
+  process_1: P1,
+  process_2: P2
+}
 
+impl<P1, P2, R> Process<R> for ThenProcess<P1, P2>
+where
+  P1: Process<R>,
+  P2: Process<R>,
+  R: 'static
+{
+  type Value = P2::Value;
 
-  #[test]
-  fn join_sum_with_delay () {
-    let immediate_process = value(10);
-    let paused_process    = value(32).pause().pause().pause();
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let process_2 = self.process_2;
+    self.process_1.call(runtime, move |r: &mut R, _: P1::Value| process_2.call(r, next));
+  }
+}
 
-    let join_and_pause_process = immediate_process.join(paused_process)
-      .map(|(v1, v2)| { v1 + v2 });
 
-    let return_value = execute_process(join_and_pause_process);
-    assert_eq!(42, return_value);
+impl<P1, P2, R> ProcessMut<R> for ThenProcess<P1, P2>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let process_2 = self.process_2;
+
+    self.process_1.call_mut(runtime, move |r: &mut R, (process_1, _): (P1, P1::Value)| {
+      process_2.call_mut(r, move |r: &mut R, (process_2, v): (P2, P2::Value)| {
+        next.call(r, (ThenProcess { process_1, process_2 }, v));
+      });
+    });
   }
+}
 
-  #[test]
-  fn count_using_while () {
-    let counter_1 = Rc::new(RefCell::new(0));
-    let counter_2 = counter_1.clone();
-    let counter_3 = counter_1.clone();
 
-    let sum = move |v| { *counter_1.borrow_mut() += 1 };
-    let test_loop_end = move |v| {
-      match *counter_2.borrow() {
-        42 => LoopStatus::Exit(42),
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// IF ELSE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running one of two processes, depending on the boolean value of a process.
+#[derive(Clone)]
+pub struct IfElseProcess<P, P1, P2> {
+  condition   : P,
+  then_process: P1,
+  else_process: P2
+}
+
+impl<P, P1, P2, R> Process<R> for IfElseProcess<P, P1, P2>
+where
+  P: Process<R, Value = bool>,
+  P1: Process<R>,
+  P2: Process<R, Value = P1::Value>,
+  R: 'static
+{
+  type Value = P1::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let then_process = self.then_process;
+    let else_process = self.else_process;
+
+    self.condition.call(runtime, move |r: &mut R, condition: bool| {
+      if condition { then_process.call(r, next) } else { else_process.call(r, next) }
+    });
+  }
+}
+
+
+// Mutable process version below: the process which has not been chosen is kept as is
+
+impl<P, P1, P2, R> ProcessMut<R> for IfElseProcess<P, P1, P2>
+where
+  P: ProcessMut<R, Value = bool>,
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R, Value = P1::Value>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let then_process = self.then_process;
+    let else_process = self.else_process;
+
+    self.condition.call_mut(runtime, move |r: &mut R, (condition, c): (P, bool)| {
+      if c {
+        then_process.call_mut(r, move |r: &mut R, (then_process, v): (P1, P1::Value)| {
+          next.call(r, (IfElseProcess { condition, then_process, else_process }, v));
+        });
+      }
+      else {
+        else_process.call_mut(r, move |r: &mut R, (else_process, v): (P2, P2::Value)| {
+          next.call(r, (IfElseProcess { condition, then_process, else_process }, v));
+        });
+      }
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// JOIN PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A helper structure, used by `JoinProcess` to synchronize the call of two processes.
+///
+/// This version is specific to the implementaion of `Process`.
+struct JoinPoint<V1, V2, C> {
+  p1_result: Rc<Cell<Option<V1>>>,
+  p2_result: Rc<Cell<Option<V2>>>,
+  next     : Rc<Cell<Option<C>>>
+}
+
+impl<V1, V2, C> JoinPoint<V1, V2, C> {
+  /// Create a new `JoinPoint` with the given `next` continuation.
+  fn new(next: C) -> JoinPoint<V1, V2, C> {
+    JoinPoint {
+      p1_result: Rc::new(Cell::new(None)),
+      p2_result: Rc::new(Cell::new(None)),
+      next:      Rc::new(Cell::new(Some(next)))
+    }
+  }
+}
+
+
+/// A process calling two sub-processes in a *synchronized* way,
+/// i.e. waiting for both to finnish running before running the given `next` continuation.
+#[derive(Clone)]
+pub struct JoinProcess<P1, P2> {
+  process_1: P1,
+  process_2: P2
+}
+
+
+
+impl<P1, P2, R> Process<R> for JoinProcess<P1, P2>
+where
+  P1: Process<R> + 'static,
+  P2: Process<R> + 'static,
+  R: 'static
+{
+  type Value = (P1::Value, P2::Value);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let join_point_1 = Rc::new(JoinPoint::new(next));
+    let join_point_2 = join_point_1.clone();
+
+    self.process_1.call(runtime, move |runtime: &mut R, p1_result: P1::Value| {
+      if let Some(p2_result) = join_point_1.p2_result.take() {
+        let next_input = (p1_result, p2_result);
+        let next = join_point_1.next.take().unwrap();
+        next.call(runtime, next_input);
+      }
+      else {
+        join_point_1.p1_result.set(Some(p1_result));
+      }
+    });
+
+    self.process_2.call(runtime, move |runtime: &mut R, p2_result: P2::Value| {
+      if let Some(p1_result) = join_point_2.p1_result.take() {
+        let next_input = (p1_result, p2_result);
+        let next = join_point_2.next.take().unwrap();
+        next.call(runtime, next_input);
+      }
+      else {
+        join_point_2.p2_result.set(Some(p2_result));
+      }
+    });
+  }
+}
+
+
+// Mutable process version below
+
+/// A helper structure, used by `JoinProcess` to synchronize the call of two processes.
+///
+/// This version is specific to the implementaion of `ProcessMut`.
+struct JoinPointMut<C, P1, P2, V1, V2> {
+  p1_result: Rc<Cell<Option<V1>>>,
+  p2_result: Rc<Cell<Option<V2>>>,
+  next     : Rc<Cell<Option<C>>>,
+  p1       : Rc<Cell<Option<P1>>>,
+  p2       : Rc<Cell<Option<P2>>>
+}
+
+
+impl<C, P1, P2, V1, V2> JoinPointMut<C, P1, P2, V1, V2> {
+  fn new(p1: P1, p2: P2, next: C) -> JoinPointMut<C, P1, P2, V1, V2> {
+    JoinPointMut {
+      p1_result: Rc::new(Cell::new(None)),
+      p2_result: Rc::new(Cell::new(None)),
+      next:      Rc::new(Cell::new(Some(next))),
+      p1:   Rc::new(Cell::new(Some(p1))),
+      p2:   Rc::new(Cell::new(Some(p2)))
+    }
+  }
+}
+
+
+impl<P1, P2, R> ProcessMut<R> for JoinProcess<P1, P2>
+where
+  P1: ProcessMut<R> + 'static,
+  P2: ProcessMut<R> + 'static,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let join_point_1 = Rc::new(JoinPointMut::new(self.process_1, self.process_2, next));
+    let join_point_2 = join_point_1.clone();
+    let join_point_3 = join_point_1.clone();
+
+    join_point_3.p1.take().unwrap().call_mut(runtime, move |runtime: &mut R, (p1, v1): (P1, P1::Value)| {
+      join_point_1.p1.set(Some(p1));
+      if let Some(p2_result) = join_point_1.p2_result.take() {
+        let p1 = join_point_1.p1.take().unwrap();
+        let p2 = join_point_1.p2.take().unwrap();
+
+        let next_input = (v1, p2_result);
+        let next = join_point_1.next.take().unwrap();
+
+        next.call(runtime, (p1.join(p2), next_input));
+      }
+      else {
+        join_point_1.p1_result.set(Some(v1));
+      }
+    });
+
+    join_point_3.p2.take().unwrap().call_mut(runtime, move |runtime: &mut R, (p2, v2): (P2, P2::Value)| {
+      join_point_2.p2.set(Some(p2));
+      if let Some(p1_result) = join_point_2.p1_result.take() {
+        let p1 = join_point_2.p1.take().unwrap();
+        let p2 = join_point_2.p2.take().unwrap();
+
+        let next_input = (p1_result, v2);
+        let next = join_point_2.next.take().unwrap();
+        next.call(runtime, (p1.join(p2), next_input));
+      }
+      else {
+        join_point_2.p2_result.set(Some(v2));
+      }
+    });
+  }
+}
+
+
+/// A mutable process running two mutable processes in lockstep (see `ProcessMut::zip`):
+/// each run runs one iteration of both processes concurrently, and completes once both have
+/// completed, so that the fastest process never runs ahead of the other one.
+///
+/// A single run behaves like `join`, which it is built on.
+#[derive(Clone)]
+pub struct ZipProcess<P1, P2> {
+  join: JoinProcess<P1, P2>
+}
+
+
+impl<P1, P2, R> Process<R> for ZipProcess<P1, P2>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  R: 'static
+{
+  type Value = (P1::Value, P2::Value);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.join.call(runtime, next);
+  }
+}
+
+
+impl<P1, P2, R> ProcessMut<R> for ZipProcess<P1, P2>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.join.call_mut(runtime, next.map(|(join, values)| (ZipProcess { join }, values)));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// JOIN3 AND JOIN4 PROCESSES
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process joining three processes, built on nested `JoinProcess`es whose values are
+/// flattened into a triple.
+#[derive(Clone)]
+pub struct Join3Process<P1, P2, P3> {
+  join: JoinProcess<P1, JoinProcess<P2, P3>>
+}
+
+
+impl<P1, P2, P3, R> Process<R> for Join3Process<P1, P2, P3>
+where
+  P1: Process<R>,
+  P2: Process<R>,
+  P3: Process<R>,
+  R: 'static
+{
+  type Value = (P1::Value, P2::Value, P3::Value);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.join.call(runtime, next.map(|(v1, (v2, v3))| (v1, v2, v3)));
+  }
+}
+
+
+impl<P1, P2, P3, R> ProcessMut<R> for Join3Process<P1, P2, P3>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  P3: ProcessMut<R>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.join.call_mut(runtime, next.map(|(join, (v1, (v2, v3)))| (Join3Process { join }, (v1, v2, v3))));
+  }
+}
+
+
+/// A process joining four processes, built on nested `JoinProcess`es whose values are
+/// flattened into a quadruple.
+#[derive(Clone)]
+pub struct Join4Process<P1, P2, P3, P4> {
+  join: JoinProcess<P1, JoinProcess<P2, JoinProcess<P3, P4>>>
+}
+
+
+impl<P1, P2, P3, P4, R> Process<R> for Join4Process<P1, P2, P3, P4>
+where
+  P1: Process<R>,
+  P2: Process<R>,
+  P3: Process<R>,
+  P4: Process<R>,
+  R: 'static
+{
+  type Value = (P1::Value, P2::Value, P3::Value, P4::Value);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.join.call(runtime, next.map(|(v1, (v2, (v3, v4)))| (v1, v2, v3, v4)));
+  }
+}
+
+
+impl<P1, P2, P3, P4, R> ProcessMut<R> for Join4Process<P1, P2, P3, P4>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  P3: ProcessMut<R>,
+  P4: ProcessMut<R>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.join.call_mut(runtime, next.map(|(join, (v1, (v2, (v3, v4))))| (Join4Process { join }, (v1, v2, v3, v4))));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// WITH PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process joining a process with a side process, built on a `JoinProcess` whose value is
+/// reduced to the value of the first process.
+#[derive(Clone)]
+pub struct WithProcess<P1, P2> {
+  join: JoinProcess<P1, P2>
+}
+
+
+impl<P1, P2, R> Process<R> for WithProcess<P1, P2>
+where
+  P1: Process<R>,
+  P2: Process<R>,
+  R: 'static
+{
+  type Value = P1::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.join.call(runtime, next.map(|(v1, _)| v1));
+  }
+}
+
+
+impl<P1, P2, R> ProcessMut<R> for WithProcess<P1, P2>
+where
+  P1: ProcessMut<R>,
+  P2: ProcessMut<R>,
+  R: 'static
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    self.join.call_mut(runtime, next.map(|(join, (v1, _))| (WithProcess { join }, v1)));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// JOIN MACRO
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Joins any number of processes (from 2 to 12), and gives the flat tuple of their values to the
+/// continuation, e.g. `(V1, V2, V3)` for `join!(p1, p2, p3)`.
+///
+/// It expands to nested `join` calls, whose nested tuples are flattened by a `map`: the resulting
+/// process is mutable if all the joined processes are, so that it can be used in a loop.
+#[macro_export]
+macro_rules! join {
+  ($($process:expr),+ $(,)*) => {
+    join!(@name [$($process),+] [v1 v2 v3 v4 v5 v6 v7 v8 v9 v10 v11 v12] [] [])
+  };
+
+  // Gives a name to the value of each process
+  (@name [$process:expr $(, $rest:expr)*] [$name:ident $($names:ident)*] [$($named:expr),*] [$($used:ident)*]) => {
+    join!(@name [$($rest),*] [$($names)*] [$($named,)* $process] [$($used)* $name])
+  };
+
+  (@name [] [$($names:ident)*] [$($named:expr),*] [$($used:ident)*]) => {{
+    // The function is bound first, so that it is not only inferred to be callable once
+    let flatten = |join!(@pattern $($used)*)| ($($used),*);
+    $crate::processes::Process::map(join!(@nest $($named),*), flatten)
+  }};
+
+  // Nests the joins of the processes
+  (@nest $process:expr) => { $process };
+  (@nest $process:expr, $($rest:expr),+) => {
+    $crate::processes::Process::join($process, join!(@nest $($rest),+))
+  };
+
+  // Matches the nested tuples of values resulting from nested joins
+  (@pattern $name:ident) => { $name };
+  (@pattern $name:ident $($rest:ident)+) => { ($name, join!(@pattern $($rest)+)) };
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// JOIN ALL PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A helper structure, used by `JoinAllProcess` to synchronize the call of several processes:
+/// it collects their results by index, and counts the processes which have not completed yet.
+struct JoinAllPoint<T, C> {
+  results  : Vec<Option<T>>,
+  remaining: usize,
+  next     : Option<C>
+}
+
+/// Shared pointer to a `JoinAllPoint`.
+type JoinAllPointRef<T, C> = Rc<RefCell<JoinAllPoint<T, C>>>;
+
+impl<T, C> JoinAllPoint<T, C> {
+  /// Create a new `JoinAllPoint` for `count` processes, with the given `next` continuation.
+  fn new(count: usize, next: C) -> JoinAllPointRef<T, C> {
+    let results = (0..count).map(|_| None).collect();
+    Rc::new(RefCell::new(JoinAllPoint { results, remaining: count, next: Some(next) }))
+  }
+
+  /// Records the result of the process of the given index. Once all processes have completed,
+  /// returns the continuation along with the results, in the order of the processes.
+  fn complete(&mut self, index: usize, result: T) -> Option<(C, Vec<T>)> {
+    self.results[index] = Some(result);
+    self.remaining -= 1;
+
+    if self.remaining > 0 {
+      return None;
+    }
+
+    let results = ::std::mem::take(&mut self.results).into_iter().map(Option::unwrap).collect();
+    Some((self.next.take().unwrap(), results))
+  }
+}
+
+
+/// A process calling a vector of sub-processes in a *synchronized* way, i.e. waiting for all
+/// of them to complete before giving their values to the continuation, in the order of the vector.
+#[derive(Clone)]
+pub struct JoinAllProcess<P> {
+  processes: Vec<P>
+}
+
+
+/// Returns a process running all the given processes concurrently, and returning their values
+/// in the order of the vector, whatever the order they complete in.
+pub fn join_all<P>(processes: Vec<P>) -> JoinAllProcess<P> {
+  JoinAllProcess { processes }
+}
+
+
+impl<P, R> Process<R> for JoinAllProcess<P> where P: Process<R>, R: 'static {
+  type Value = Vec<P::Value>;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    if self.processes.is_empty() {
+      next.call(runtime, Vec::new());
+      return;
+    }
+
+    let join_point = JoinAllPoint::new(self.processes.len(), next);
+
+    for (index, process) in self.processes.into_iter().enumerate() {
+      let join_point = join_point.clone();
+
+      process.call(runtime, move |r: &mut R, v: P::Value| {
+        let completed = join_point.borrow_mut().complete(index, v);
+        if let Some((next, values)) = completed {
+          next.call(r, values);
+        }
+      });
+    }
+  }
+}
+
+
+impl<P, R> ProcessMut<R> for JoinAllProcess<P> where P: ProcessMut<R>, R: 'static {
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    if self.processes.is_empty() {
+      next.call(runtime, (self, Vec::new()));
+      return;
+    }
+
+    let join_point = JoinAllPoint::new(self.processes.len(), next);
+
+    for (index, process) in self.processes.into_iter().enumerate() {
+      let join_point = join_point.clone();
+
+      process.call_mut(runtime, move |r: &mut R, result: (P, P::Value)| {
+        let completed = join_point.borrow_mut().complete(index, result);
+        if let Some((next, results)) = completed {
+          let (processes, values) = results.into_iter().unzip();
+          next.call(r, (join_all(processes), values));
+        }
+      });
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SEQUENCE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process calling a vector of sub-processes one after the other, and giving their values to
+/// the continuation, in the order of the vector.
+#[derive(Clone)]
+pub struct SequenceProcess<P> {
+  steps: Vec<P>
+}
+
+
+/// Returns a process running all the given processes in sequence, each one being started once
+/// the previous one has completed, and returning their values in the order of the vector.
+pub fn sequence<P>(steps: Vec<P>) -> SequenceProcess<P> {
+  SequenceProcess { steps }
+}
+
+
+/// Runs the remaining `steps` one after the other, pushing their values into `values`,
+/// then calls `next` with all the values.
+fn sequence_runs<P, R, C>(mut steps: ::std::vec::IntoIter<P>, mut values: Vec<P::Value>, runtime: &mut R, next: C)
+where
+  P: Process<R>,
+  R: 'static,
+  C: Continuation<Vec<P::Value>, R>
+{
+  match steps.next() {
+    None       => next.call(runtime, values),
+    Some(step) => step.call(runtime, move |r: &mut R, v: P::Value| {
+      values.push(v);
+      sequence_runs(steps, values, r, next);
+    })
+  }
+}
+
+
+/// Runs the remaining `steps` one after the other like `sequence_runs`, pushing the processes
+/// which have been ran into `ran`, then calls `next` with these processes and all the values.
+fn sequence_runs_mut<P, R, C>(mut steps: ::std::vec::IntoIter<P>, mut ran: Vec<P>, mut values: Vec<P::Value>,
+                              runtime: &mut R, next: C)
+where
+  P: ProcessMut<R>,
+  R: 'static,
+  C: Continuation<(Vec<P>, Vec<P::Value>), R>
+{
+  match steps.next() {
+    None       => next.call(runtime, (ran, values)),
+    Some(step) => step.call_mut(runtime, move |r: &mut R, (step, v): (P, P::Value)| {
+      ran.push(step);
+      values.push(v);
+      sequence_runs_mut(steps, ran, values, r, next);
+    })
+  }
+}
+
+
+impl<P, R> Process<R> for SequenceProcess<P> where P: Process<R>, R: 'static {
+  type Value = Vec<P::Value>;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let values = Vec::with_capacity(self.steps.len());
+    sequence_runs(self.steps.into_iter(), values, runtime, next);
+  }
+}
+
+
+impl<P, R> ProcessMut<R> for SequenceProcess<P> where P: ProcessMut<R>, R: 'static {
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let count = self.steps.len();
+
+    sequence_runs_mut(self.steps.into_iter(), Vec::with_capacity(count), Vec::with_capacity(count), runtime,
+      move |r: &mut R, (steps, values): (Vec<P>, Vec<P::Value>)| next.call(r, (sequence(steps), values)));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// RACE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running two processes concurrently, and completing with the value of the first
+/// one to complete.
+///
+/// Ties favor the left process: when the right process completes first, its value is only
+/// delivered after the tasks already scheduled for current instant have been ran, so that the
+/// left process still wins if it completes during one of them.
+#[derive(Clone)]
+pub struct RaceProcess<P1, P2> {
+  process_1: P1,
+  process_2: P2
+}
+
+
+impl<P1, P2, R> Process<R> for RaceProcess<P1, P2>
+where
+  P1: Process<R>,
+  P2: Process<R, Value = P1::Value>,
+  R: ReactiveRuntime
+{
+  type Value = P1::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    // Only the first process to complete takes the continuation
+    let next_1 = Rc::new(Cell::new(Some(next)));
+    let next_2 = next_1.clone();
+
+    self.process_1.call(runtime, move |r: &mut R, v: P1::Value| {
+      if let Some(next) = next_1.take() {
+        next.call(r, v);
+      }
+    });
+
+    self.process_2.call(runtime, move |r: &mut R, v: P2::Value| {
+      r.schedule_current(move |r: &mut R, _: ()| {
+        if let Some(next) = next_2.take() {
+          next.call(r, v);
+        }
+      });
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SELECT EITHER PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running two processes concurrently, and completing with the value of the first
+/// one to complete, on the side of the process it comes from.
+///
+/// Ties favor the left process, as in `RaceProcess`.
+#[derive(Clone)]
+pub struct SelectEitherProcess<P1, P2> {
+  process_1: P1,
+  process_2: P2
+}
+
+
+impl<P1, P2, R> Process<R> for SelectEitherProcess<P1, P2>
+where
+  P1: Process<R>,
+  P2: Process<R>,
+  R: ReactiveRuntime
+{
+  type Value = Either<P1::Value, P2::Value>;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let race = RaceProcess {
+      process_1: self.process_1.map(Either::Left),
+      process_2: self.process_2.map(Either::Right)
+    };
+
+    race.call(runtime, next);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SELECT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running a vector of processes concurrently, and completing with the index and the
+/// value of the first one to complete.
+#[derive(Clone)]
+pub struct SelectProcess<P> {
+  processes: Vec<P>
+}
+
+
+/// Returns a process running all the given processes concurrently, and returning the index and
+/// the value of the first one to complete. The values of the other processes are discarded.
+///
+/// Panics if the vector is empty, since the process would never complete.
+pub fn select_any<P>(processes: Vec<P>) -> SelectProcess<P> {
+  assert!(!processes.is_empty(), "cannot select a process among no processes");
+  SelectProcess { processes }
+}
+
+
+impl<P, R> Process<R> for SelectProcess<P> where P: Process<R>, R: 'static {
+  type Value = (usize, P::Value);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    // Only the first process to complete takes the continuation
+    let next = Rc::new(Cell::new(Some(next)));
+
+    for (index, process) in self.processes.into_iter().enumerate() {
+      let next = next.clone();
+
+      process.call(runtime, move |r: &mut R, v: P::Value| {
+        if let Some(next) = next.take() {
+          next.call(r, (index, v));
+        }
+      });
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// WHILE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Indicates if a loop is finished.
+#[derive(Debug)]
+pub enum LoopStatus<V> { Continue, Exit(V) }
+
+
+/// Indicates if a loop threading a state is finished, holding the state of the next iteration
+/// or the value of the loop (see `ProcessMut::while_fold`).
+#[derive(Debug)]
+pub enum LoopStatusWith<S, V> { ContinueWith(S), ExitWith(V) }
+
+
+/// Mutable processes returning a `LoopStatus`, which can be ran in a loop.
+///
+/// It is implemented by any such process: a process returning another type of value cannot be
+/// ran in a loop, and the compiler reports that the bounds of `while_loop` are not satisfied.
+///
+/// ```compile_fail,E0599
+/// use reactrust::processes::*;
+///
+/// // The body of the loop must return a `LoopStatus`
+/// execute_process(value(42).while_loop());
+/// ```
+pub trait LoopProcessMut<T, R = Runtime>: ProcessMut<R, Value = LoopStatus<T>> + Sized {
+  /// Returns a process which runs itself until it returns `LoopStatus::Exit`,
+  /// and returns the value it holds.
+  fn while_loop(self) -> WhileProcess<Self> {
+    WhileProcess { process: self }
+  }
+
+  /// Returns a process which runs itself until it returns `LoopStatus::Exit`, like `while_loop`,
+  /// but at most `max_iterations` times: it returns an error once it has ran that many times
+  /// without exiting, whether the iterations last one or several instants.
+  fn while_loop_max(self, max_iterations: usize) -> WhileMaxProcess<Self> {
+    WhileMaxProcess { process: self, iterations: 0, max_iterations }
+  }
+}
+
+impl<P, T, R> LoopProcessMut<T, R> for P where P: ProcessMut<R, Value = LoopStatus<T>> {}
+
+
+/// A process running a process returning an `Option` in a loop (see `while_some`).
+pub type WhileSomeProcess<P, V> = WhileProcess<MapProcess<P, fn(Option<V>) -> LoopStatus<()>>>;
+
+/// A process running a process returning a `Result` in a loop (see `while_ok`).
+pub type WhileOkProcess<P, V, E> = WhileProcess<MapProcess<P, fn(Result<V, E>) -> LoopStatus<E>>>;
+
+
+/// Mutable processes returning an `Option`, which can be ran in a loop.
+pub trait OptionLoopProcessMut<V, R = Runtime>: ProcessMut<R, Value = Option<V>> + Sized {
+  /// Returns a process which runs itself as long as it returns `Some` (discarding the values it
+  /// holds), and returns `()` once it returns `None`.
+  fn while_some(self) -> WhileSomeProcess<Self, V>
+  where
+    V: 'static
+  {
+    let status: fn(Option<V>) -> LoopStatus<()> = |v| match v {
+      Some(_) => LoopStatus::Continue,
+      None    => LoopStatus::Exit(())
+    };
+
+    WhileProcess { process: self.map(status) }
+  }
+}
+
+impl<P, V, R> OptionLoopProcessMut<V, R> for P where P: ProcessMut<R, Value = Option<V>> {}
+
+
+/// Mutable processes returning a `Result`, which can be ran in a loop.
+pub trait ResultLoopProcessMut<V, E, R = Runtime>: ProcessMut<R, Value = Result<V, E>> + Sized {
+  /// Returns a process which runs itself as long as it returns `Ok` (discarding the values it
+  /// holds), and returns the error it holds once it returns `Err`.
+  fn while_ok(self) -> WhileOkProcess<Self, V, E>
+  where
+    V: 'static,
+    E: 'static
+  {
+    let status: fn(Result<V, E>) -> LoopStatus<E> = |v| match v {
+      Ok(_)  => LoopStatus::Continue,
+      Err(e) => LoopStatus::Exit(e)
+    };
+
+    WhileProcess { process: self.map(status) }
+  }
+}
+
+impl<P, V, E, R> ResultLoopProcessMut<V, E, R> for P where P: ProcessMut<R, Value = Result<V, E>> {}
+
+/// A process calling itself in a *while* loop fashion, until it returns `LoopStatus::Exit<V>`,
+/// which contains the value to give to the `next` continuation.
+#[derive(Clone)]
+pub struct WhileProcess<P> {
+  process: P
+}
+
+
+impl<P, V, R> Process<R> for WhileProcess<P>
+where
+  P: ProcessMut<R, Value = LoopStatus<V>>,
+  R: 'static
+{
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    self.process.call_mut(runtime, |r: &mut R, (p, v): (P, LoopStatus<V>)| {
+      match v {
+        LoopStatus::Continue     => p.while_loop().call(r, next),
+        LoopStatus::Exit(output) => next.call(r, output)
+      };
+    });
+  }
+}
+
+
+/// Error returned by a loop which has not exited after its maximal number of iterations
+/// (see `LoopProcessMut::while_loop_max`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct LoopLimitExceeded {
+  /// Maximal number of iterations of the loop.
+  pub max_iterations: usize
+}
+
+
+impl fmt::Display for LoopLimitExceeded {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "the loop has not exited after {} iterations", self.max_iterations)
+  }
+}
+
+
+/// A process calling itself in a *while* loop fashion, like `WhileProcess`, but stopping with
+/// an error after a maximal number of iterations.
+#[derive(Clone)]
+pub struct WhileMaxProcess<P> {
+  process       : P,
+  iterations    : usize,
+  max_iterations: usize
+}
+
+
+impl<P, V, R> Process<R> for WhileMaxProcess<P>
+where
+  P: ProcessMut<R, Value = LoopStatus<V>>,
+  R: 'static
+{
+  type Value = Result<V, LoopLimitExceeded>;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let (iterations, max_iterations) = (self.iterations, self.max_iterations);
+    if iterations == max_iterations {
+      return next.call(runtime, Err(LoopLimitExceeded { max_iterations }));
+    }
+
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, LoopStatus<V>)| {
+      match v {
+        LoopStatus::Continue     => WhileMaxProcess { process, iterations: iterations + 1, max_iterations }.call(r, next),
+        LoopStatus::Exit(output) => next.call(r, Ok(output))
+      };
+    });
+  }
+}
+
+
+/// A process calling itself in a loop, and threading a state across the iterations, until its
+/// step function returns `LoopStatusWith::ExitWith<V>` (see `ProcessMut::while_fold`).
+#[derive(Clone)]
+pub struct WhileFoldProcess<P, S, F> {
+  process: P,
+  state  : S,
+  step   : F
+}
+
+
+impl<P, S, F, V, R> Process<R> for WhileFoldProcess<P, S, F>
+where
+  P: ProcessMut<R>,
+  S: 'static,
+  F: FnMut(S, P::Value) -> LoopStatusWith<S, V> + 'static,
+  R: 'static
+{
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let state    = self.state;
+    let mut step = self.step;
+
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, P::Value)| {
+      match step(state, v) {
+        LoopStatusWith::ContinueWith(state) => WhileFoldProcess { process, state, step }.call(r, next),
+        LoopStatusWith::ExitWith(output)    => next.call(r, output)
+      };
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// LOOP PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running a mutable process in an infinite loop, discarding its values
+/// (see `ProcessMut::loop_forever`).
+///
+/// Its value is `()`, but it never completes: its continuation is never called.
+#[derive(Clone)]
+pub struct LoopProcess<P> {
+  process: P
+}
+
+
+/// Runs `process`, then runs it again each time it completes.
+fn loop_runs<P, R>(process: P, runtime: &mut R) where P: ProcessMut<R>, R: 'static {
+  process.call_mut(runtime, |r: &mut R, (p, _): (P, P::Value)| loop_runs(p, r));
+}
+
+
+impl<P, R> Process<R> for LoopProcess<P> where P: ProcessMut<R>, R: 'static {
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut R, _: C) where C: Continuation<Self::Value, R> {
+    loop_runs(self.process, runtime);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// BOX PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Object-safe version of `Process`, implemented by any process: its continuation is boxed.
+trait ProcessBox<V> {
+  /// Executes the boxed process in the runtime, calls `next` with the resulting value.
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, next: Box<dyn Continuation<V>>);
+}
+
+
+impl<P> ProcessBox<P::Value> for P where P: Process {
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, next: Box<dyn Continuation<P::Value>>) {
+    (*self).call(runtime, next);
+  }
+}
+
+
+/// A process hiding the type of the process it contains, only exposing the type of its value
+/// (see `Process::boxed`).
+///
+/// Processes of different types can thus be stored in the same collection, as boxed processes.
+pub struct BoxProcess<V> {
+  process: Box<dyn ProcessBox<V>>
+}
+
+
+impl<V> BoxProcess<V> {
+  /// Returns a new `BoxProcess` containing the given process.
+  pub fn new<P>(process: P) -> Self where P: Process<Value = V> {
+    BoxProcess { process: Box::new(process) }
+  }
+}
+
+
+impl<V> Process for BoxProcess<V> where V: 'static {
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    self.process.call_box(runtime, next.boxed());
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// REC PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Function building the body of a recursive process from the process itself.
+type RecFunction<V> = Rc<dyn Fn(BoxProcess<V>) -> BoxProcess<V>>;
+
+/// A process defined recursively, i.e. running a body which can run the process itself
+/// (see `rec`).
+pub struct RecProcess<V> {
+  function: RecFunction<V>
+}
+
+
+/// Returns a process running the body built by `function`, which is given the process itself.
+///
+/// Each time the process is ran (including recursively), `function` is called to build its body.
+/// Recursive runs are scheduled during current instant, so that a deep recursion which never
+/// pauses does not overflow the stack.
+pub fn rec<V, F>(function: F) -> RecProcess<V> where F: Fn(BoxProcess<V>) -> BoxProcess<V> + 'static {
+  RecProcess { function: Rc::new(function) }
+}
+
+
+impl<V> Process for RecProcess<V> where V: 'static {
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let itself = BoxProcess::new(RecCallProcess { function: self.function.clone() });
+    (self.function)(itself).call(runtime, next);
+  }
+}
+
+
+impl<V> Clone for RecProcess<V> {
+  fn clone(&self) -> Self {
+    RecProcess { function: self.function.clone() }
+  }
+}
+
+
+/// A process running a recursive process during current instant, given to its body.
+struct RecCallProcess<V> {
+  function: RecFunction<V>
+}
+
+
+impl<V> Process for RecCallProcess<V> where V: 'static {
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let process = RecProcess { function: self.function };
+    runtime.schedule_current(move |r: &mut Runtime, ()| process.call(r, next));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// RESULT PROCESSES
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Processes returning a `Result`, which can recover from errors, or chain fallible steps.
+pub trait ResultProcess<T, E>: Process<Value = Result<T, E>> + Sized {
+  /// Returns a process which, if it returns an error, runs the fallback process built from
+  /// the error by `function`, and returns its value. Successes are returned untouched,
+  /// without building any fallback process.
+  fn or_else<F, P, E2>(self, function: F) -> OrElseProcess<Self, F>
+  where
+    F: FnOnce(E) -> P + 'static,
+    P: Process<Value = Result<T, E2>>
+  {
+    OrElseProcess { process: self, function }
+  }
+
+  /// Returns a process which returns the value of a success, or `default` on error.
+  fn unwrap_or(self, default: T) -> UnwrapOrProcess<Self, T> {
+    UnwrapOrProcess { process: self, default }
+  }
+
+  /// Returns a process which applies `function` to the error it returns, if any.
+  fn map_err<F, E2>(self, function: F) -> MapErrProcess<Self, F>
+  where
+    F: FnOnce(E) -> E2 + 'static
+  {
+    MapErrProcess { process: self, function }
+  }
+
+  /// Returns a process which applies `function` to the value of the success it returns, if any.
+  fn map_ok<F, T2>(self, function: F) -> MapOkProcess<Self, F>
+  where
+    F: FnOnce(T) -> T2 + 'static
+  {
+    MapOkProcess { process: self, function }
+  }
+
+  /// Returns a process which, if it returns a success, runs the process built from its value by
+  /// `function`, and returns its value. Errors are returned untouched, without building any
+  /// process.
+  fn and_then_ok<F, P, T2>(self, function: F) -> AndThenOkProcess<Self, F>
+  where
+    F: FnOnce(T) -> P + 'static,
+    P: Process<Value = Result<T2, E>>
+  {
+    AndThenOkProcess { process: self, function }
+  }
+}
+
+impl<P, T, E> ResultProcess<T, E> for P where P: Process<Value = Result<T, E>> {}
+
+
+/// A process running a fallback process when another process returns an error.
+#[derive(Clone)]
+pub struct OrElseProcess<P, F> {
+  process : P,
+  function: F
+}
+
+
+impl<P, F, P2, T, E, E2> Process for OrElseProcess<P, F>
+where
+  P: Process<Value = Result<T, E>>,
+  F: FnOnce(E) -> P2 + 'static,
+  P2: Process<Value = Result<T, E2>>
+{
+  type Value = Result<T, E2>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let function = self.function;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: Result<T, E>| {
+      match v {
+        Ok(value)  => next.call(r, Ok(value)),
+        Err(error) => function(error).call(r, next)
+      }
+    });
+  }
+}
+
+
+impl<P, F, P2, T, E, E2> ProcessMut for OrElseProcess<P, F>
+where
+  P: ProcessMut<Value = Result<T, E>>,
+  F: FnMut(E) -> P2 + 'static,
+  P2: Process<Value = Result<T, E2>>
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let mut function = self.function;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, Result<T, E>)| {
+      match v {
+        Ok(value)  => next.call(r, (OrElseProcess { process, function }, Ok(value))),
+        Err(error) => {
+          function(error).call(r, move |r: &mut Runtime, v: Result<T, E2>| {
+            next.call(r, (OrElseProcess { process, function }, v));
+          });
+        }
+      }
+    });
+  }
+}
+
+
+/// A process replacing the error returned by another process by a default value.
+#[derive(Clone)]
+pub struct UnwrapOrProcess<P, T> {
+  process: P,
+  default: T
+}
+
+
+impl<P, T, E> Process for UnwrapOrProcess<P, T>
+where
+  P: Process<Value = Result<T, E>>,
+  T: 'static
+{
+  type Value = T;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let default = self.default;
+    self.process.call(runtime, move |r: &mut Runtime, v: Result<T, E>| next.call(r, v.unwrap_or(default)));
+  }
+}
+
+
+impl<P, T, E> ProcessMut for UnwrapOrProcess<P, T>
+where
+  P: ProcessMut<Value = Result<T, E>>,
+  T: Clone + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let default = self.default;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, Result<T, E>)| {
+      let value = v.unwrap_or_else(|_| default.clone());
+      next.call(r, (UnwrapOrProcess { process, default }, value));
+    });
+  }
+}
+
+
+/// A process applying a function to the error returned by another process.
+#[derive(Clone)]
+pub struct MapErrProcess<P, F> {
+  process : P,
+  function: F
+}
+
+
+impl<P, F, T, E, E2> Process for MapErrProcess<P, F>
+where
+  P: Process<Value = Result<T, E>>,
+  F: FnOnce(E) -> E2 + 'static
+{
+  type Value = Result<T, E2>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let function = self.function;
+    self.process.call(runtime, move |r: &mut Runtime, v: Result<T, E>| next.call(r, v.map_err(function)));
+  }
+}
+
+
+impl<P, F, T, E, E2> ProcessMut for MapErrProcess<P, F>
+where
+  P: ProcessMut<Value = Result<T, E>>,
+  F: FnMut(E) -> E2 + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let mut function = self.function;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, Result<T, E>)| {
+      let value = v.map_err(&mut function);
+      next.call(r, (MapErrProcess { process, function }, value));
+    });
+  }
+}
+
+
+/// A process applying a function to the value of the success returned by another process.
+#[derive(Clone)]
+pub struct MapOkProcess<P, F> {
+  process : P,
+  function: F
+}
+
+
+impl<P, F, T, E, T2> Process for MapOkProcess<P, F>
+where
+  P: Process<Value = Result<T, E>>,
+  F: FnOnce(T) -> T2 + 'static
+{
+  type Value = Result<T2, E>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let function = self.function;
+    self.process.call(runtime, move |r: &mut Runtime, v: Result<T, E>| next.call(r, v.map(function)));
+  }
+}
+
+
+impl<P, F, T, E, T2> ProcessMut for MapOkProcess<P, F>
+where
+  P: ProcessMut<Value = Result<T, E>>,
+  F: FnMut(T) -> T2 + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let mut function = self.function;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, Result<T, E>)| {
+      let value = v.map(&mut function);
+      next.call(r, (MapOkProcess { process, function }, value));
+    });
+  }
+}
+
+
+/// A process running a process built from the value of the success returned by another process.
+#[derive(Clone)]
+pub struct AndThenOkProcess<P, F> {
+  process : P,
+  function: F
+}
+
+
+impl<P, F, P2, T, E, T2> Process for AndThenOkProcess<P, F>
+where
+  P: Process<Value = Result<T, E>>,
+  F: FnOnce(T) -> P2 + 'static,
+  P2: Process<Value = Result<T2, E>>
+{
+  type Value = Result<T2, E>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let function = self.function;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: Result<T, E>| {
+      match v {
+        Ok(value)  => function(value).call(r, next),
+        Err(error) => next.call(r, Err(error))
+      }
+    });
+  }
+}
+
+
+impl<P, F, P2, T, E, T2> ProcessMut for AndThenOkProcess<P, F>
+where
+  P: ProcessMut<Value = Result<T, E>>,
+  F: FnMut(T) -> P2 + 'static,
+  P2: Process<Value = Result<T2, E>>
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let mut function = self.function;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, Result<T, E>)| {
+      match v {
+        Err(error) => next.call(r, (AndThenOkProcess { process, function }, Err(error))),
+        Ok(value)  => {
+          function(value).call(r, move |r: &mut Runtime, v: Result<T2, E>| {
+            next.call(r, (AndThenOkProcess { process, function }, v));
+          });
+        }
+      }
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// GENERATOR
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A handle given to the body of a generator, used to yield values to its consumer.
+///
+/// Internally, it is a rendezvous built on two signals: one carrying the yielded values
+/// (or `None` once the body is done), and one emitted by the consumer once it handled a value.
+pub struct Yielder<V>
+where
+  V: Clone + 'static
+{
+  values : ValueSignal<Option<V>, Option<V>>,
+  handled: PureSignal
+}
+
+impl<V> Clone for Yielder<V>
+where
+  V: Clone + 'static
+{
+  fn clone(&self) -> Self {
+    Yielder { values: self.values.clone(), handled: self.handled.clone() }
+  }
+}
+
+impl<V> Yielder<V>
+where
+  V: Clone + 'static
+{
+  /// Create a new `Yielder`, and its inner signals.
+  fn new() -> Self {
+    Yielder {
+      values : ValueSignal::new_with_gather_function(None, Box::new(|e, v| { *v = e; })),
+      handled: PureSignal::new()
+    }
+  }
+
+  /// Returns a process which yields the given value to the consumer,
+  /// and waits until the consumer has handled it.
+  pub fn yield_value(&self, value: V) -> YieldProcess<V> {
+    YieldProcess { yielder: self.clone(), value }
+  }
+}
+
+
+/// A process yielding a value to the consumer of a generator,
+/// and waiting until the consumer has handled it.
+pub struct YieldProcess<V>
+where
+  V: Clone + 'static
+{
+  yielder: Yielder<V>,
+  value  : V
+}
+
+impl<V> Process for YieldProcess<V>
+where
+  V: Clone + 'static
+{
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    // The consumer can not handle the value before next instant, so the acknowledgement
+    // is only awaited from there: this way, an acknowledgement of the previous value
+    // emitted during current instant can not be mistaken for the one of this value.
+    let handled = self.yielder.handled;
+
+    self.yielder.values.emit_value(Some(self.value))
+      .pause()
+      .and_then(move |_| handled.await_immediate())
+      .call(runtime, next);
+  }
+}
+
+impl<V> ProcessMut for YieldProcess<V>
+where
+  V: Clone + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let yielder = self.yielder.clone();
+    let value   = self.value.clone();
+
+    self.call(runtime, move |r: &mut Runtime, ()| {
+      next.call(r, (YieldProcess { yielder, value }, ()));
+    });
+  }
+}
+
+
+/// A generator, i.e. a body process yielding values through a `Yielder`.
+///
+/// It must be given a consumer (see `for_each_value`) to become a runnable process.
+pub struct Generator<P, V>
+where
+  V: Clone + 'static
+{
+  body   : P,
+  yielder: Yielder<V>
+}
+
+/// Returns a new `Generator`, whose body is built by `body` from the `Yielder` it is given.
+pub fn generator<F, P, V>(body: F) -> Generator<P, V>
+where
+  F: FnOnce(Yielder<V>) -> P,
+  P: Process,
+  V: Clone + 'static
+{
+  let yielder = Yielder::new();
+  Generator { body: body(yielder.clone()), yielder }
+}
+
+impl<P, V> Generator<P, V>
+where
+  P: Process,
+  V: Clone + 'static
+{
+  /// Returns a process running the body of the generator, as well as the process returned by
+  /// `consumer` for each yielded value. The body is only resumed once the consumer process
+  /// of the last yielded value has terminated.
+  ///
+  /// The resulting process terminates with the value of the body.
+  pub fn for_each_value<F, PC>(self, consumer: F) -> ForEachValueProcess<P, V, F>
+  where
+    F: FnMut(V) -> PC + 'static,
+    PC: Process
+  {
+    ForEachValueProcess { generator: self, consumer }
+  }
+}
+
+
+/// A process running a generator, and a consumer process for each value it yields.
+pub struct ForEachValueProcess<P, V, F>
+where
+  V: Clone + 'static
+{
+  generator: Generator<P, V>,
+  consumer : F
+}
+
+impl<P, V, F, PC> Process for ForEachValueProcess<P, V, F>
+where
+  P: Process,
+  V: Clone + 'static,
+  F: FnMut(V) -> PC + 'static,
+  PC: Process
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let yielder = self.generator.yielder;
+    let values  = yielder.values.clone();
+
+    // Once the body has terminated, `None` is sent to stop the consumer
+    let body = self.generator.body.and_then(move |v| values.emit_value(None).map(move |_| v));
+
+    // As the body waits for each of its values to be handled, the consumer of the last value
+    // has always terminated when the body does
+    consume_values(runtime, yielder, self.consumer);
+    body.call(runtime, next);
+  }
+}
+
+/// Await for the next value yielded through `yielder`, and run the process built by `consumer`
+/// from it, before acknowledging the value and starting over (until the generator is done).
+fn consume_values<V, F, PC>(runtime: &mut Runtime, yielder: Yielder<V>, mut consumer: F)
+where
+  V: Clone + 'static,
+  F: FnMut(V) -> PC + 'static,
+  PC: Process
+{
+  yielder.values.clone().await().call(runtime, move |r: &mut Runtime, v: Option<V>| {
+    if let Some(v) = v {
+      consumer(v).call(r, move |r: &mut Runtime, _: PC::Value| {
+        yielder.handled.clone().emit().call(r, move |r: &mut Runtime, ()| {
+          consume_values(r, yielder, consumer);
+        });
+      });
+    }
+  });
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// INSTANT INDEX PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process returning the index of the instant during which it is ran.
+#[derive(Clone)]
+pub struct InstantIndexProcess;
+
+
+/// Returns a new `InstantIndexProcess`.
+pub fn instant_index() -> InstantIndexProcess {
+  InstantIndexProcess
+}
+
+
+impl Process for InstantIndexProcess {
+  type Value = usize;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let instant_index = runtime.instant_index();
+    next.call(runtime, instant_index);
+  }
+}
+
+
+impl ProcessMut for InstantIndexProcess {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let instant_index = runtime.instant_index();
+    next.call(runtime, (self, instant_index));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// INTERLEAVE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A value of one of two possible types.
+#[derive(Debug, Clone, PartialEq)]
+pub enum Either<L, R> { Left(L), Right(R) }
+
+/// A process alternately running two sub-processes, pausing after each run.
+///
+/// Unlike `JoinProcess`, both sub-processes never run during the same instant.
+#[derive(Clone)]
+pub struct InterleaveProcess<P1, P2> {
+  process_1 : P1,
+  process_2 : P2,
+  first_turn: bool
+}
+
+
+impl<P1, P2> Process for InterleaveProcess<P1, P2>
+where
+  P1: Process,
+  P2: Process
+{
+  type Value = Either<P1::Value, P2::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    if self.first_turn {
+      self.process_1.call(runtime, next.pause().map(Either::Left));
+    }
+    else {
+      self.process_2.call(runtime, next.pause().map(Either::Right));
+    }
+  }
+}
+
+
+impl<P1, P2> ProcessMut for InterleaveProcess<P1, P2>
+where
+  P1: ProcessMut,
+  P2: ProcessMut
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let next = next.pause();
+
+    if self.first_turn {
+      let process_2 = self.process_2;
+
+      self.process_1.call_mut(runtime, move |r: &mut Runtime, (p1, v): (P1, P1::Value)| {
+        let interleave = InterleaveProcess { process_1: p1, process_2, first_turn: false };
+        next.call(r, (interleave, Either::Left(v)));
+      });
+    }
+    else {
+      let process_1 = self.process_1;
+
+      self.process_2.call_mut(runtime, move |r: &mut Runtime, (p2, v): (P2, P2::Value)| {
+        let interleave = InterleaveProcess { process_1, process_2: p2, first_turn: true };
+        next.call(r, (interleave, Either::Right(v)));
+      });
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FOR EACH CONCURRENT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process constructing one process per item of a vector, running them concurrently,
+/// and returning their values in the order of the items.
+pub struct ForEachConcurrentProcess<T, F> {
+  items        : Vec<T>,
+  function     : F,
+  max_in_flight: Option<usize>
+}
+
+
+/// Returns a process which calls `function` on each item of `items` (when it is executed),
+/// runs all the resulting processes concurrently, and returns their values in item order.
+pub fn for_each_concurrent<T, F, P>(items: Vec<T>, function: F) -> ForEachConcurrentProcess<T, F>
+where
+  F: FnMut(T) -> P + 'static,
+  P: Process
+{
+  ForEachConcurrentProcess { items, function, max_in_flight: None }
+}
+
+
+impl<T, F> ForEachConcurrentProcess<T, F> {
+  /// Limits the number of processes running at the same time:
+  /// the process of an item is only started once the process of a previous item has completed.
+  pub fn max_in_flight(mut self, max_in_flight: usize) -> Self {
+    assert!(max_in_flight > 0, "at least one process must be allowed to run");
+
+    self.max_in_flight = Some(max_in_flight);
+    self
+  }
+}
+
+
+/// Shared state of an executing `ForEachConcurrentProcess`.
+struct ForEachConcurrentState<T, F, V, C> {
+  items     : ::std::vec::IntoIter<T>,
+  next_index: usize,
+  function  : F,
+  values    : Vec<Option<V>>,
+  running   : usize,
+  next      : Option<C>
+}
+
+/// Shared pointer to the state of an executing `ForEachConcurrentProcess`.
+type ForEachConcurrentStateRef<T, F, V, C> = Rc<RefCell<ForEachConcurrentState<T, F, V, C>>>;
+
+
+impl<T, F, P> Process for ForEachConcurrentProcess<T, F>
+where
+  T: 'static,
+  F: FnMut(T) -> P + 'static,
+  P: Process
+{
+  type Value = Vec<P::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    if self.items.is_empty() {
+      next.call(runtime, Vec::new());
+      return;
+    }
+
+    let max_in_flight = self.max_in_flight.unwrap_or(self.items.len());
+    let state = Rc::new(RefCell::new(ForEachConcurrentState {
+      values    : self.items.iter().map(|_| None).collect(),
+      items     : self.items.into_iter(),
+      next_index: 0,
+      function  : self.function,
+      running   : 0,
+      next      : Some(next)
+    }));
+
+    for _ in 0..max_in_flight {
+      if ! start_next_item(state.clone(), runtime) {
+        break;
+      }
+    }
+  }
+}
+
+
+/// Starts the process of the next item of a `ForEachConcurrentProcess`, if any.
+/// Returns whether a process has been started.
+fn start_next_item<T, F, P, C>(state: ForEachConcurrentStateRef<T, F, P::Value, C>,
+                               runtime: &mut Runtime) -> bool
+where
+  T: 'static,
+  F: FnMut(T) -> P + 'static,
+  P: Process,
+  C: Continuation<Vec<P::Value>>
+{
+  let (index, process) = {
+    let mut state = state.borrow_mut();
+    let item = match state.items.next() {
+      Some(item) => item,
+      None       => return false
+    };
+
+    let index = state.next_index;
+    state.next_index += 1;
+    state.running += 1;
+
+    (index, (state.function)(item))
+  };
+
+  process.call(runtime, move |r: &mut Runtime, value: P::Value| {
+    let all_done = {
+      let mut state = state.borrow_mut();
+      state.values[index] = Some(value);
+      state.running -= 1;
+      state.running == 0 && state.items.len() == 0
+    };
+
+    if all_done {
+      let (values, next) = {
+        let mut state = state.borrow_mut();
+        (::std::mem::take(&mut state.values), state.next.take().unwrap())
+      };
+      next.call(r, values.into_iter().map(Option::unwrap).collect());
+    }
+    else {
+      start_next_item(state, r);
+    }
+  });
+
+  true
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FOR EACH PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process building and running a process for each item of an iterator, one item after the
+/// other (see `for_each` and `for_each_immediate`).
+pub struct ForEachProcess<I, F> {
+  items: I,
+  body : F,
+  pause: bool
+}
+
+
+/// Returns a process which, for each item of `items`, calls `body` on the item and runs the
+/// resulting process, pausing before running the process of the next item.
+/// It returns `()` once the process of the last item has completed.
+///
+/// An item is taken from the iterator as soon as the process of the previous item has completed,
+/// i.e. during the instant before the process of the item is ran.
+pub fn for_each<I, F, P>(items: I, body: F) -> ForEachProcess<I::IntoIter, F>
+where
+  I: IntoIterator,
+  F: FnMut(I::Item) -> P + 'static,
+  P: Process
+{
+  ForEachProcess { items: items.into_iter(), body, pause: true }
+}
+
+
+/// Returns a process like `for_each`, but which runs the process of the next item as soon as the
+/// process of the previous item has completed, without pausing.
+pub fn for_each_immediate<I, F, P>(items: I, body: F) -> ForEachProcess<I::IntoIter, F>
+where
+  I: IntoIterator,
+  F: FnMut(I::Item) -> P + 'static,
+  P: Process
+{
+  ForEachProcess { items: items.into_iter(), body, pause: false }
+}
+
+
+/// Runs the process of `item`, then the processes of the remaining `items`,
+/// and finally calls `next`.
+fn for_each_runs<I, F, P, C>(item: I::Item, mut items: I, mut body: F, pause: bool, runtime: &mut Runtime, next: C)
+where
+  I: Iterator + 'static,
+  F: FnMut(I::Item) -> P + 'static,
+  P: Process,
+  C: Continuation<()>
+{
+  body(item).call(runtime, move |r: &mut Runtime, _: P::Value| {
+    let item = match items.next() {
+      Some(item) => item,
+      None       => return next.call(r, ())
+    };
+
+    // The next item is always ran from a new task, so that items do not nest calls on the stack
+    let run_next = move |r: &mut Runtime, ()| for_each_runs(item, items, body, pause, r, next);
+    if pause { r.schedule_next(run_next) } else { r.schedule_current(run_next) }
+  });
+}
+
+
+impl<I, F, P> Process for ForEachProcess<I, F>
+where
+  I: Iterator + 'static,
+  F: FnMut(I::Item) -> P + 'static,
+  P: Process
+{
+  type Value = ();
+
+  fn call<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    match self.items.next() {
+      Some(item) => for_each_runs(item, self.items, self.body, self.pause, runtime, next),
+      None       => next.call(runtime, ())
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TAKE PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running a mutable process a given number of times, and collecting its values
+/// (see `ProcessMut::take`).
+///
+/// Each run after the first one is scheduled through the runtime, during the instant the previous
+/// run completes, so that running a body which does not pause many times does not grow the stack.
+///
+/// Once the last run is over, the mutable process is not called anymore: e.g. awaiting a signal
+/// only registers a continuation on the signal during the `n` first runs.
+#[derive(Clone)]
+pub struct TakeProcess<P> {
+  process: P,
+  n      : usize
+}
+
+/// A process running a mutable process a given number of times (see `ProcessMut::first_n`).
+pub type FirstNProcess<P> = TakeProcess<P>;
+
+/// A process running a mutable process a given number of times (see `ProcessMut::repeat`).
+pub type RepeatProcess<P> = TakeProcess<P>;
+
+
+/// Runs `process` `remaining` more times, each run being scheduled during current instant,
+/// pushing the values into `values`, then calls `next` with the process and all the values.
+fn repeat_runs<P, R, C>(process: P, remaining: usize, mut values: Vec<P::Value>, runtime: &mut R, next: C)
+where
+  P: ProcessMut<R>,
+  R: ReactiveRuntime,
+  C: Continuation<(P, Vec<P::Value>), R>
+{
+  if remaining == 0 {
+    next.call(runtime, (process, values));
+    return;
+  }
+
+  process.call_mut(runtime, move |r: &mut R, (p, v): (P, P::Value)| {
+    values.push(v);
+    r.schedule_current(move |r: &mut R, _: ()| repeat_runs(p, remaining - 1, values, r, next));
+  });
+}
+
+
+impl<P, R> Process<R> for TakeProcess<P> where P: ProcessMut<R>, R: ReactiveRuntime {
+  type Value = Vec<P::Value>;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    repeat_runs(self.process, self.n, Vec::with_capacity(self.n), runtime,
+      move |r: &mut R, (_, values): (P, Vec<P::Value>)| next.call(r, values));
+  }
+}
+
+
+impl<P, R> ProcessMut<R> for TakeProcess<P> where P: ProcessMut<R>, R: ReactiveRuntime {
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let n = self.n;
+
+    repeat_runs(self.process, n, Vec::with_capacity(n), runtime,
+      move |r: &mut R, (p, values): (P, Vec<P::Value>)| next.call(r, (p.take(n), values)));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FILTER MAP LOOP PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running a mutable process until a function maps its value to `Some`, scheduling
+/// each run after the first one through the runtime (see `ProcessMut::filter_map_loop`).
+#[derive(Clone)]
+pub struct FilterMapLoopProcess<P, F> {
+  process : P,
+  function: F
+}
+
+
+/// Runs `process` until `function` maps its value to `Some`, each run after the first one being
+/// scheduled during current instant, then calls `next` with the process, the function and the
+/// value held by the `Some`.
+fn filter_map_runs<P, F, O, R, C>(process: P, mut function: F, runtime: &mut R, next: C)
+where
+  P: ProcessMut<R>,
+  F: FnMut(P::Value) -> Option<O> + 'static,
+  R: ReactiveRuntime,
+  C: Continuation<(P, F, O), R>
+{
+  process.call_mut(runtime, move |r: &mut R, (p, v): (P, P::Value)| {
+    match function(v) {
+      Some(output) => next.call(r, (p, function, output)),
+      None         => r.schedule_current(move |r: &mut R, _: ()| filter_map_runs(p, function, r, next))
+    }
+  });
+}
+
+
+impl<P, F, O, R> Process<R> for FilterMapLoopProcess<P, F>
+where
+  P: ProcessMut<R>,
+  F: FnMut(P::Value) -> Option<O> + 'static,
+  R: ReactiveRuntime
+{
+  type Value = O;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    filter_map_runs(self.process, self.function, runtime,
+      move |r: &mut R, (_, _, output): (P, F, O)| next.call(r, output));
+  }
+}
+
+
+impl<P, F, O, R> ProcessMut<R> for FilterMapLoopProcess<P, F>
+where
+  P: ProcessMut<R>,
+  F: FnMut(P::Value) -> Option<O> + 'static,
+  R: ReactiveRuntime
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    filter_map_runs(self.process, self.function, runtime,
+      move |r: &mut R, (process, function, output): (P, F, O)| {
+        next.call(r, (FilterMapLoopProcess { process, function }, output));
+      });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SKIP PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A mutable process ignoring the values of the first runs of another mutable process.
+///
+/// Skipped runs are still executed (with their side effects), in a row, during the first run of
+/// the `SkipProcess`, whose value is the value of the first run which is not skipped.
+/// Subsequent runs of the `SkipProcess` are plain runs of the inner process.
+#[derive(Clone)]
+pub struct SkipProcess<P> {
+  process: P,
+  n      : usize
+}
+
+
+/// Runs `process` `remaining` more times, each run being scheduled during current instant,
+/// discarding the values, then calls `next` with it.
+fn skip_runs<P, V, C>(process: P, remaining: usize, runtime: &mut Runtime, next: C)
+where
+  P: ProcessMut<Value = V>,
+  V: 'static,
+  C: Continuation<P>
+{
+  if remaining == 0 {
+    next.call(runtime, process);
+    return;
+  }
+
+  process.call_mut(runtime, move |r: &mut Runtime, (p, _): (P, V)| {
+    r.schedule_current(move |r: &mut Runtime, _: ()| skip_runs(p, remaining - 1, r, next));
+  });
+}
+
+
+impl<P, V> Process for SkipProcess<P>
+where
+  P: ProcessMut<Value = V>,
+  V: 'static
+{
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    skip_runs(self.process, self.n, runtime, move |r: &mut Runtime, p: P| p.call(r, next));
+  }
+}
+
+
+impl<P, V> ProcessMut for SkipProcess<P>
+where
+  P: ProcessMut<Value = V>,
+  V: 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    skip_runs(self.process, self.n, runtime, move |r: &mut Runtime, p: P| {
+      p.call_mut(r, move |r: &mut Runtime, (p, v): (P, V)| next.call(r, (p.skip(0), v)));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// ONE INSTANT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// What a `OneInstantProcess` does when its inner process lasts more than one instant.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum OverrunPolicy {
+  /// Deliver the value as soon as the inner process completes.
+  Clamp,
+  /// Panic in debug builds; behave as `Clamp` in release builds.
+  Panic
+}
+
+
+/// A process normalizing the duration of another process, so that it spans exactly one instant.
+///
+/// A process cannot be shortened: if the inner process completes more than one instant after it
+/// started, the overrun is handled according to the `OverrunPolicy` of the process.
+#[derive(Clone)]
+pub struct OneInstantProcess<P> {
+  process       : P,
+  overrun_policy: OverrunPolicy
+}
+
+
+impl<P> OneInstantProcess<P> {
+  /// Sets the policy to apply when the inner process lasts more than one instant.
+  pub fn on_overrun(mut self, overrun_policy: OverrunPolicy) -> Self {
+    self.overrun_policy = overrun_policy;
+    self
+  }
+}
+
+
+/// Calls `next` with `value` one instant after `start_instant`,
+/// or right away (according to `overrun_policy`) if this instant is already over.
+fn deliver_after_one_instant<V, C>(runtime: &mut Runtime, start_instant: usize,
+                                   overrun_policy: OverrunPolicy, next: C, value: V)
+where
+  V: 'static,
+  C: Continuation<V>
+{
+  let end_instant = runtime.instant_index();
+
+  if end_instant == start_instant {
+    next.pause().call(runtime, value);
+    return;
+  }
+
+  if overrun_policy == OverrunPolicy::Panic {
+    debug_assert!(end_instant == start_instant + 1,
+      "process overran its instant: started at instant {}, completed at instant {}",
+      start_instant, end_instant);
+  }
+
+  next.call(runtime, value);
+}
+
+
+impl<P, V> Process for OneInstantProcess<P>
+where
+  P: Process<Value = V>,
+  V: 'static
+{
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let start_instant  = runtime.instant_index();
+    let overrun_policy = self.overrun_policy;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: V| {
+      deliver_after_one_instant(r, start_instant, overrun_policy, next, v);
+    });
+  }
+}
+
+
+impl<P, V> ProcessMut for OneInstantProcess<P>
+where
+  P: ProcessMut<Value = V>,
+  V: 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let start_instant  = runtime.instant_index();
+    let overrun_policy = self.overrun_policy;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (p, v): (P, V)| {
+      let process = OneInstantProcess { process: p, overrun_policy };
+      deliver_after_one_instant(r, start_instant, overrun_policy, next, (process, v));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// AT INSTANT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process holding another process un-started until a given instant.
+#[derive(Clone)]
+pub struct AtInstantProcess<P> {
+  instant_index: usize,
+  process      : P
+}
+
+
+/// Returns a process which starts `process` during the instant of index `instant_index`,
+/// or right away if this instant is already reached.
+pub fn at_instant<P>(instant_index: usize, process: P) -> AtInstantProcess<P> where P: Process {
+  AtInstantProcess { instant_index, process }
+}
+
+
+impl<P> Process for AtInstantProcess<P> where P: Process {
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    if runtime.instant_index() >= self.instant_index {
+      self.process.call(runtime, next);
+      return;
+    }
+
+    let process = self.process;
+    runtime.on_instant(self.instant_index, Box::new(move |r: &mut Runtime, _: ()| {
+      process.call(r, next);
+    }));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// VALUES ITERATOR
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// An iterator driving a mutable process from non-reactive code.
+///
+/// Each call to `next` starts a run of the process during the next instant of the runtime
+/// (which has not been executed yet), then executes instants until the run completes,
+/// and returns its value. It returns `None` if the runtime runs out of work before.
+pub struct ValuesIter<P> {
+  process: Option<P>,
+  runtime: Runtime
+}
+
+
+impl<P> ValuesIter<P> {
+  /// Returns the runtime driving the process.
+  pub fn runtime(&self) -> &Runtime {
+    &self.runtime
+  }
+}
+
+
+impl<P, V> Iterator for ValuesIter<P>
+where
+  P: ProcessMut<Value = V>,
+  V: 'static
+{
+  type Item = V;
+
+  fn next(&mut self) -> Option<V> {
+    let process = self.process.take()?;
+
+    let result_1 = Rc::new(Cell::new(None));
+    let result_2 = result_1.clone();
+
+    self.runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      process.call_mut(r, move |_: &mut Runtime, result: (P, V)| result_1.set(Some(result)));
+    });
+
+    loop {
+      let remaining_work = self.runtime.instant();
+
+      if let Some((process, value)) = result_2.take() {
+        self.process = Some(process);
+        return Some(value);
+      }
+
+      if ! remaining_work {
+        return None;
+      }
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TRACE TIMING PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A timing record, made of a label, and of the indices of the instants
+/// during which a run of the labelled process started and completed.
+pub type TimingRecord = (&'static str, usize, usize);
+
+/// Statistics about the durations (in instants) of the runs of a labelled process.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TimingStats {
+  /// Number of runs of the process.
+  pub runs: usize,
+  /// Minimal duration of a run, in instants.
+  pub min : usize,
+  /// Maximal duration of a run, in instants.
+  pub max : usize,
+  /// Mean duration of the runs, in instants.
+  pub mean: f64
+}
+
+
+/// A collector of timing records, shared by all its clones.
+#[derive(Clone, Default)]
+pub struct TimingProfile {
+  records: Rc<RefCell<Vec<TimingRecord>>>
+}
+
+
+impl TimingProfile {
+  /// Create a new, empty `TimingProfile`.
+  pub fn new() -> Self {
+    TimingProfile { records: Rc::new(RefCell::new(Vec::new())) }
+  }
+
+  /// Returns all the records of the profile, in order of completion.
+  pub fn records(&self) -> Vec<TimingRecord> {
+    self.records.borrow().clone()
+  }
+
+  /// Returns the statistics about the durations of the runs of each label.
+  pub fn report(&self) -> BTreeMap<&'static str, TimingStats> {
+    let mut report: BTreeMap<&'static str, TimingStats> = BTreeMap::new();
+
+    for &(label, start, end) in self.records.borrow().iter() {
+      let duration = end - start;
+      let stats = report.entry(label).or_insert(TimingStats {
+        runs: 0,
+        min : duration,
+        max : duration,
+        mean: 0.0
+      });
+
+      stats.mean = (stats.mean * stats.runs as f64 + duration as f64) / (stats.runs + 1) as f64;
+      stats.runs += 1;
+      stats.min = stats.min.min(duration);
+      stats.max = stats.max.max(duration);
+    }
+
+    report
+  }
+
+  /// Add a record to the profile.
+  fn record(&self, label: &'static str, start: usize, end: usize) {
+    self.records.borrow_mut().push((label, start, end));
+  }
+}
+
+
+/// A process recording the timing of each run of another process into a `TimingProfile`.
+#[derive(Clone)]
+pub struct TraceTimingProcess<P> {
+  process: P,
+  profile: TimingProfile,
+  label  : &'static str
+}
+
+
+impl<P, V> Process for TraceTimingProcess<P>
+where
+  P: Process<Value = V>,
+  V: 'static
+{
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let start   = runtime.instant_index();
+    let profile = self.profile;
+    let label   = self.label;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: V| {
+      profile.record(label, start, r.instant_index());
+      next.call(r, v);
+    });
+  }
+}
+
+
+impl<P, V> ProcessMut for TraceTimingProcess<P>
+where
+  P: ProcessMut<Value = V>,
+  V: 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let start   = runtime.instant_index();
+    let profile = self.profile;
+    let label   = self.label;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (p, v): (P, V)| {
+      profile.record(label, start, r.instant_index());
+      next.call(r, (p.trace_timing(profile, label), v));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// COUNT INSTANTS PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process counting the instants another process takes to complete, whether it spends them
+/// paused or waiting for signals (see `Process::count_instants`).
+#[derive(Clone)]
+pub struct CountInstantsProcess<P> {
+  process: P
+}
+
+
+impl<P, R> Process<R> for CountInstantsProcess<P>
+where
+  P: Process<R>,
+  R: ReactiveRuntime
+{
+  type Value = (P::Value, u64);
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let start = runtime.instant_index();
+
+    self.process.call(runtime, move |r: &mut R, v: P::Value| {
+      let instants = (r.instant_index() - start) as u64;
+      next.call(r, (v, instants));
+    });
+  }
+}
+
+
+impl<P, R> ProcessMut<R> for CountInstantsProcess<P>
+where
+  P: ProcessMut<R>,
+  R: ReactiveRuntime
+{
+  fn call_mut<C>(self, runtime: &mut R, next: C) where C: Continuation<(Self, Self::Value), R> {
+    let start = runtime.instant_index();
+
+    self.process.call_mut(runtime, move |r: &mut R, (process, v): (P, P::Value)| {
+      let instants = (r.instant_index() - start) as u64;
+      next.call(r, (CountInstantsProcess { process }, (v, instants)));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// UNTIL PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running another process until a signal is emitted.
+#[derive(Clone)]
+pub struct UntilProcess<P, S> {
+  process: P,
+  signal : S
+}
+
+
+impl<P, S> Process for UntilProcess<P, S>
+where
+  P: Process,
+  S: ObservableSignal + 'static
+{
+  type Value = Option<P::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let parent_handle_1 = runtime.cancel_handle();
+    let parent_handle_2 = parent_handle_1.clone();
+    let handle_1        = runtime.new_cancel_handle();
+    let handle_2        = handle_1.clone();
+    let signal_handle_1 = runtime.new_cancel_handle();
+    let signal_handle_2 = signal_handle_1.clone();
+
+    // The continuation is called by whichever completes first: the process, or the signal
+    let next_1 = Rc::new(Cell::new(Some(next)));
+    let next_2 = next_1.clone();
+
+    let process = self.process;
+    let signal  = self.signal;
+    runtime.with_cancel_handle(Some(signal_handle_1), |r| {
+      signal.await_immediate().call(r, move |r: &mut Runtime, _: ()| {
+        if let Some(next) = next_2.take() {
+          handle_2.cancel();
+          r.with_cancel_handle(parent_handle_2, |r| next.call(r, None));
+        }
+      });
+    });
+
+    runtime.with_cancel_handle(Some(handle_1), |r| {
+      process.call(r, move |r: &mut Runtime, v: P::Value| {
+        if let Some(next) = next_1.take() {
+          // The signal is not awaited anymore
+          signal_handle_2.cancel();
+          r.with_cancel_handle(parent_handle_1, |r| next.call(r, Some(v)));
+        }
+      });
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// WHEN PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running another process only during the instants a signal is present.
+///
+/// The process runs within a cancellation context holding a `Gate`, which is open during the
+/// instants the signal is present, and which is released each time the signal is emitted.
+#[derive(Clone)]
+pub struct WhenProcess<P, S> {
+  process: P,
+  signal : S
+}
+
+
+/// Calls `on_present` during each instant `signal` is present, as soon as it is emitted.
+fn watch_presence<S, F>(runtime: &mut Runtime, signal: S, mut on_present: F)
+where
+  S: ObservableSignal + 'static,
+  F: FnMut(&mut Runtime) + 'static
+{
+  let signal_1 = signal.clone();
+
+  signal.await_immediate().call(runtime, move |r: &mut Runtime, ()| {
+    on_present(r);
+
+    // Awaiting the signal (unlike pausing) does not keep the runtime running
+    let signal_2 = signal_1.clone();
+    signal_1.await().call(r, move |r: &mut Runtime, _: S::Value| watch_presence(r, signal_2, on_present));
+  });
+}
+
+
+impl<P, S> Process for WhenProcess<P, S>
+where
+  P: Process,
+  S: ObservableSignal + 'static
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let parent_handle  = runtime.cancel_handle();
+    let watcher_handle = runtime.new_cancel_handle();
+
+    let present_instant   = Rc::new(Cell::new(None));
+    let present_instant_1 = present_instant.clone();
+    let gate = Gate::new(move |r: &Runtime| present_instant_1.get() == Some(r.instant_index()));
+
+    let gate_1     = gate.clone();
+    let on_present = move |r: &mut Runtime| {
+      present_instant.set(Some(r.instant_index()));
+      gate_1.release(r);
+    };
+
+    let signal = self.signal;
+    runtime.with_cancel_handle(Some(watcher_handle.clone()), |r| watch_presence(r, signal, on_present));
+
+    // The process is started from a task, so that its first step is suspended as well
+    let process = self.process;
+    runtime.with_cancel_handle(Some(CancelHandle::gated(parent_handle.clone(), gate)), |r| {
+      r.schedule_current(move |r: &mut Runtime, ()| {
+        process.call(r, move |r: &mut Runtime, v: P::Value| {
+          watcher_handle.cancel();
+          r.with_cancel_handle(parent_handle, |r| next.call(r, v));
+        });
+      });
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// CONTROL PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running another process which can be suspended and resumed by signals.
+///
+/// The process runs within a cancellation context holding a `Gate`, which is closed while the
+/// process is suspended. The signals are checked at the end of each instant.
+#[derive(Clone)]
+pub struct ControlProcess<P, S1, S2> {
+  process: P,
+  suspend: S1,
+  resume : S2
+}
+
+
+impl<P, S1, S2> Process for ControlProcess<P, S1, S2>
+where
+  P: Process,
+  S1: ObservableSignal + 'static,
+  S2: ObservableSignal + 'static
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let parent_handle  = runtime.cancel_handle();
+    let watcher_handle = runtime.new_cancel_handle();
+
+    let suspended   = Rc::new(Cell::new(false));
+    let suspended_1 = suspended.clone();
+    let gate = Gate::new(move |_: &Runtime| !suspended_1.get());
+
+    // Record the last instants during which the signals have been present
+    let suspend_instant   = Rc::new(Cell::new(None));
+    let resume_instant    = Rc::new(Cell::new(None));
+    let suspend_instant_1 = suspend_instant.clone();
+    let resume_instant_1  = resume_instant.clone();
+
+    let (suspend, resume) = (self.suspend, self.resume);
+    runtime.with_cancel_handle(Some(watcher_handle.clone()), |r| {
+      watch_presence(r, suspend, move |r: &mut Runtime| suspend_instant_1.set(Some(r.instant_index())));
+      watch_presence(r, resume, move |r: &mut Runtime| resume_instant_1.set(Some(r.instant_index())));
+    });
+
+    // Resumed continuations are released at the end of the instant, i.e. to run during next instant
+    let gate_1           = gate.clone();
+    let watcher_handle_1 = watcher_handle.clone();
+    runtime.on_end_of_each_instant(Box::new(move |r: &mut Runtime| {
+      let suspend_now = suspend_instant.get() == Some(r.instant_index());
+      let resume_now  = resume_instant.get() == Some(r.instant_index());
+
+      if suspend_now && !resume_now {
+        suspended.set(true);
+      }
+      else if resume_now && !suspend_now {
+        suspended.set(false);
+        gate_1.release(r);
+      }
+
+      !watcher_handle_1.is_cancelled()
+    }));
+
+    let process = self.process;
+    runtime.with_cancel_handle(Some(CancelHandle::gated(parent_handle.clone(), gate)), |r| {
+      process.call(r, move |r: &mut Runtime, v: P::Value| {
+        watcher_handle.cancel();
+        r.with_cancel_handle(parent_handle, |r| next.call(r, v));
+      });
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TIMEOUT PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running another process during at most a given number of instants.
+#[derive(Clone)]
+pub struct TimeoutProcess<P> {
+  process : P,
+  instants: usize
+}
+
+
+/// Runs `process` until `trigger` completes, then calls `next` with the value of the process,
+/// or with `None` if it has been preempted by `trigger`.
+///
+/// The preemption happens at the end of the instant `trigger` completes during, so that the
+/// process can still complete during this instant. Shared by `timeout` and `do_until`.
+pub(crate) fn preempt_at_end_of_instant<P, T, C>(runtime: &mut Runtime, process: P, trigger: T, next: C)
+where
+  P: Process,
+  T: Process<Value = ()>,
+  C: Continuation<Option<P::Value>>
+{
+  let parent_handle_1 = runtime.cancel_handle();
+  let parent_handle_2 = parent_handle_1.clone();
+  let process_handle  = runtime.new_cancel_handle();
+  let trigger_handle  = runtime.new_cancel_handle();
+
+  // The continuation is called by whichever completes first: the process, or the preemption
+  let next_1 = Rc::new(Cell::new(Some(next)));
+  let next_2 = next_1.clone();
+
+  let process_handle_1 = process_handle.clone();
+  let preempt = move |r: &mut Runtime, ()| {
+    r.on_end_of_instant(Box::new(move |r: &mut Runtime, ()| {
+      if let Some(next) = next_2.take() {
+        process_handle_1.cancel();
+        r.with_cancel_handle(parent_handle_2, |r| next.call(r, None));
+      }
+    }));
+  };
+
+  runtime.with_cancel_handle(Some(trigger_handle.clone()), |r| trigger.call(r, preempt));
+
+  runtime.with_cancel_handle(Some(process_handle), |r| {
+    process.call(r, move |r: &mut Runtime, v: P::Value| {
+      if let Some(next) = next_1.take() {
+        trigger_handle.cancel();
+        r.with_cancel_handle(parent_handle_1, |r| next.call(r, Some(v)));
+      }
+    });
+  });
+}
+
+
+impl<P> Process for TimeoutProcess<P> where P: Process {
+  type Value = Option<P::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    // The timer expires at the end of the instant, so that the process can complete before
+    let timer = value(()).pause_n(self.instants);
+    preempt_at_end_of_instant(runtime, self.process, timer, next);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// CATCH PANIC PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process running another process, and catching the panics it raises.
+///
+/// The process runs within a cancellation context holding a panic catcher, so that the panics
+/// raised by its continuations are caught during any instant, not only during the first one.
+#[derive(Clone)]
+pub struct CatchPanicProcess<P> {
+  process: P
+}
+
+
+impl<P> Process for CatchPanicProcess<P> where P: Process {
+  type Value = Result<P::Value, Box<dyn Any + Send>>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let parent_handle_1 = runtime.cancel_handle();
+    let parent_handle_2 = parent_handle_1.clone();
+    let process_handle  = runtime.new_cancel_handle();
+
+    // The continuation is called by whichever comes first: the value, or a panic
+    let next_1 = Rc::new(Cell::new(Some(next)));
+    let next_2 = next_1.clone();
+
+    // Panics raised once the continuation has been called do not belong to the process
+    let process_handle_1 = process_handle.clone();
+    let catcher = move |r: &mut Runtime, payload: Box<dyn Any + Send>| {
+      match next_2.take() {
+        Some(next) => {
+          process_handle_1.cancel();
+          r.with_cancel_handle(parent_handle_2.clone(), |r| next.call(r, Err(payload)));
+        },
+        None => panic::resume_unwind(payload)
+      }
+    };
+
+    // The process is started from a task, so that panics raised by its first step are caught too
+    let process = self.process;
+    runtime.with_cancel_handle(Some(CancelHandle::catching(Some(process_handle), Rc::new(catcher))), |r| {
+      r.schedule_current(move |r: &mut Runtime, ()| {
+        process.call(r, move |r: &mut Runtime, v: P::Value| {
+          if let Some(next) = next_1.take() {
+            r.with_cancel_handle(parent_handle_1, |r| next.call(r, Ok(v)));
+          }
+        });
+      });
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FINALLY PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process calling a cleanup function once another process has completed.
+#[derive(Clone)]
+pub struct FinallyProcess<P, F> {
+  process: P,
+  cleanup: F
+}
+
+
+impl<P, F, R> Process<R> for FinallyProcess<P, F>
+where
+  P: Process<R>,
+  F: FnOnce(&mut R) + 'static,
+  R: 'static
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut R, next: C) where C: Continuation<Self::Value, R> {
+    let cleanup = self.cleanup;
+
+    self.process.call(runtime, move |r: &mut R, v: P::Value| {
+      cleanup(r);
+      next.call(r, v);
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SCOPE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shared state of an executing scope.
+struct ScopeState {
+  cancel_handle: CancelHandle,
+  running      : usize,
+  on_complete  : Option<Box<dyn Continuation<()>>>
+}
+
+
+/// Handle to an executing scope, given to the body of the scope for spawning children
+/// (see `scope`).
+#[derive(Clone)]
+pub struct ScopeHandle {
+  state: Rc<RefCell<ScopeState>>
+}
+
+
+impl ScopeHandle {
+  /// Returns a process which starts `child` in the background of the scope and immediately
+  /// completes, without waiting for `child` to complete.
+  pub fn spawn<P>(&self, child: P) -> SpawnProcess<P> where P: Process {
+    SpawnProcess { scope: self.clone(), child }
+  }
+
+  /// Calls `on_complete` once all the children of the scope have completed.
+  fn on_children_completed<C>(&self, runtime: &mut Runtime, on_complete: C) where C: Continuation<()> {
+    if self.state.borrow().running == 0 {
+      on_complete.call(runtime, ());
+    }
+    else {
+      self.state.borrow_mut().on_complete = Some(Box::new(on_complete));
+    }
+  }
+
+  /// Records the completion of a child of the scope.
+  fn complete_child(&self, runtime: &mut Runtime) {
+    let on_complete = {
+      let mut state = self.state.borrow_mut();
+      state.running -= 1;
+      if state.running == 0 { state.on_complete.take() } else { None }
+    };
+
+    if let Some(on_complete) = on_complete {
+      on_complete.call(runtime, ());
+    }
+  }
+}
+
+
+/// A process running the body of a scope, and completing once the body and all the children
+/// it has spawned have completed.
+pub struct ScopeProcess<F> {
+  body: F
+}
+
+
+/// Returns a process running the process returned by `body`, which may spawn children through
+/// the given `ScopeHandle`. The process completes with the value of the body, once the body and
+/// all the children spawned in the scope have completed.
+///
+/// Preempting the scope (e.g. with `until`) also preempts all its children.
+pub fn scope<F, P>(body: F) -> ScopeProcess<F> where F: FnOnce(ScopeHandle) -> P + 'static, P: Process {
+  ScopeProcess { body }
+}
+
+
+impl<F, P> Process for ScopeProcess<F> where F: FnOnce(ScopeHandle) -> P + 'static, P: Process {
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let parent_handle = runtime.cancel_handle();
+    let cancel_handle = runtime.new_cancel_handle();
+
+    let state = ScopeState { cancel_handle: cancel_handle.clone(), running: 0, on_complete: None };
+    let scope = ScopeHandle { state: Rc::new(RefCell::new(state)) };
+
+    let body = (self.body)(scope.clone());
+    runtime.with_cancel_handle(Some(cancel_handle), |r| {
+      body.call(r, move |r: &mut Runtime, v: P::Value| {
+        scope.on_children_completed(r, move |r: &mut Runtime, _: ()| {
+          r.with_cancel_handle(parent_handle, |r| next.call(r, v));
+        });
+      });
+    });
+  }
+}
+
+
+/// A process starting a child in the background of a scope (see `ScopeHandle::spawn`).
+pub struct SpawnProcess<P> {
+  scope: ScopeHandle,
+  child: P
+}
+
+
+impl<P> Process for SpawnProcess<P> where P: Process {
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let cancel_handle = {
+      let mut state = self.scope.state.borrow_mut();
+      state.running += 1;
+      state.cancel_handle.clone()
+    };
+
+    let scope = self.scope;
+    let child = self.child;
+    runtime.with_cancel_handle(Some(cancel_handle), |r| {
+      child.call(r, move |r: &mut Runtime, _: P::Value| scope.complete_child(r));
+    });
+
+    next.call(runtime, ());
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SHARED PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shared state of a shared process: its value once it has completed,
+/// and the continuations awaiting it until then.
+struct SharedState<V> {
+  value  : Option<V>,
+  waiters: Vec<Box<dyn Continuation<V>>>
+}
+
+
+/// Handle to the value of a shared process (see `Process::shared`).
+pub struct SharedHandle<V> {
+  state: Rc<RefCell<SharedState<V>>>
+}
+
+
+impl<V> Clone for SharedHandle<V> {
+  fn clone(&self) -> Self {
+    SharedHandle { state: self.state.clone() }
+  }
+}
+
+
+impl<V> SharedHandle<V> where V: Clone + 'static {
+  /// Returns a process which awaits the value of the shared process, and returns a clone of it.
+  /// It completes immediately if the shared process has already completed, or during the instant
+  /// it completes otherwise.
+  pub fn await_result(&self) -> AwaitSharedProcess<V> {
+    AwaitSharedProcess { handle: self.clone() }
+  }
+
+  /// Returns the value of the shared process, if it has completed.
+  pub fn try_get(&self) -> Option<V> {
+    self.state.borrow().value.clone()
+  }
+}
+
+
+/// A process running another process, and sharing its value with the processes awaiting it
+/// through a `SharedHandle`.
+pub struct SharedProcess<P, V> {
+  process: P,
+  handle : SharedHandle<V>
+}
+
+
+impl<P> Process for SharedProcess<P, P::Value> where P: Process, P::Value: Clone {
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let state = self.handle.state;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: P::Value| {
+      let waiters = {
+        let mut state = state.borrow_mut();
+        state.value = Some(v.clone());
+        ::std::mem::take(&mut state.waiters)
+      };
+
+      for waiter in waiters {
+        waiter.call(r, v.clone());
+      }
+      next.call(r, v);
+    });
+  }
+}
+
+
+/// A process awaiting the value of a shared process (see `SharedHandle::await_result`).
+pub struct AwaitSharedProcess<V> {
+  handle: SharedHandle<V>
+}
+
+
+impl<V> Process for AwaitSharedProcess<V> where V: Clone + 'static {
+  type Value = V;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let value = self.handle.try_get();
+
+    match value {
+      Some(value) => next.call(runtime, value),
+      None        => {
+        let waiter = runtime.bind_to_cancel_handle(next);
+        self.handle.state.borrow_mut().waiters.push(waiter);
+      }
+    }
+  }
+}
+
+
+impl<V> ProcessMut for AwaitSharedProcess<V> where V: Clone + 'static {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let handle = self.handle.clone();
+    self.call(runtime, move |r: &mut Runtime, v: V| next.call(r, (handle.await_result(), v)));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SPAWN BACKGROUND PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Handle to a process running in the background (see `Process::spawn_background` and `spawn`).
+///
+/// Dropping the handle does not cancel the process.
+pub struct SpawnHandle<V> {
+  shared: SharedHandle<V>
+}
+
+
+impl<V> Clone for SpawnHandle<V> {
+  fn clone(&self) -> Self {
+    SpawnHandle { shared: self.shared.clone() }
+  }
+}
+
+
+impl<V> SpawnHandle<V> where V: Clone + 'static {
+  /// Returns a process which awaits the completion of the background process,
+  /// and returns a clone of its value.
+  pub fn await_done(&self) -> AwaitSharedProcess<V> {
+    self.shared.await_result()
+  }
+
+  /// Indicates whether the background process has completed.
+  pub fn is_done(&self) -> bool {
+    self.shared.state.borrow().value.is_some()
+  }
+}
+
+
+/// Starts `process` in the background of the given runtime, during current instant,
+/// and returns a handle through which its value can be awaited.
+pub fn spawn<P>(runtime: &mut Runtime, process: P) -> SpawnHandle<P::Value>
+where
+  P: Process,
+  P::Value: Clone
+{
+  let (shared, handle) = process.shared();
+  runtime.schedule_current(move |r: &mut Runtime, ()| shared.call(r, |_: &mut Runtime, _: P::Value| {}));
+
+  SpawnHandle { shared: handle }
+}
+
+
+/// A process starting another process in the background, and returning a `SpawnHandle`.
+pub struct SpawnBackgroundProcess<P> {
+  process: P
+}
+
+
+impl<P> Process for SpawnBackgroundProcess<P> where P: Process, P::Value: Clone {
+  type Value = SpawnHandle<P::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let handle = spawn(runtime, self.process);
+    next.call(runtime, handle);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// STATE MACHINE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Transition of a state machine (see `state_machine`), returned by each of its steps.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Transition<S> {
+  /// Moves to the given state, stepped during a later instant (during next instant by default).
+  Next(S),
+  /// Stops the machine, which returns the given state.
+  Stop(S)
+}
+
+
+/// Function registering a continuation to run once a signal has been present.
+type SignalWaker = Box<dyn FnOnce(&mut Runtime, Box<dyn Continuation<()>>)>;
+
+/// Instant of the next step of a state machine.
+enum NextStep {
+  NextInstant,
+  InInstants(usize),
+  AfterSignal(SignalWaker)
+}
+
+
+/// Context given to each step of a state machine, which can be used to sample and emit signals
+/// during the instant of the step, and to choose when the next step happens.
+pub struct StepCtx<'a> {
+  runtime  : &'a mut Runtime,
+  next_step: NextStep
+}
+
+
+impl<'a> StepCtx<'a> {
+  /// Returns the index of the instant of the step.
+  pub fn instant_index(&self) -> usize {
+    self.runtime.instant_index()
+  }
+
+  /// Returns whether the given signal has been present during the instant preceding the step.
+  /// Its presence during the instant of the step cannot be sampled, since it is only known once
+  /// the instant is over.
+  pub fn was_present<S>(&self, signal: &S) -> bool where S: ObservableSignal<Core = SignalRuntimeOf<S>> {
+    let instant_index = self.runtime.instant_index();
+    instant_index > 0 && signal.clone().core().last_emission_instant() == Some(instant_index - 1)
+  }
+
+  /// Emits the given signal with the given element during the instant of the step.
+  pub fn emit<S>(&mut self, signal: &S, element: S::Element) where S: EmittableSignal {
+    signal.clone().core().emit_element(self.runtime, element);
+  }
+
+  /// Requests the next step to happen `instants` instants after the instant of this step,
+  /// instead of during next instant.
+  pub fn pause_for(&mut self, instants: usize) {
+    assert!(instants > 0, "the next step cannot happen during the instant of this step");
+    self.next_step = NextStep::InInstants(instants);
+  }
+
+  /// Requests the next step to happen during the instant following the next instant the given
+  /// signal is present (starting from the instant of this step), instead of during next instant.
+  pub fn wake_after<S>(&mut self, signal: &S) where S: ObservableSignal, S::Core: 'static {
+    let core = signal.clone().core();
+    self.next_step = NextStep::AfterSignal(Box::new(move |r: &mut Runtime, c: Box<dyn Continuation<()>>| {
+      core.register_later_on_present(r, move |r: &mut Runtime, _: S::Value| c.call(r, ()));
+    }));
+  }
+}
+
+
+/// A process running a state machine, from an initial state, until a step stops it.
+#[derive(Clone)]
+pub struct StateMachineProcess<S, F> {
+  state: S,
+  step : F
+}
+
+
+/// Returns a process running a state machine: `step` is called with the current state and a
+/// context (see `StepCtx`) during the instant the process is started, and returns a transition.
+/// It is called again with the new state during next instant (unless the context is used to
+/// choose another instant), until it stops the machine. The process then returns the final state.
+///
+/// Each step happens during a single instant: a step reacting to a signal must either sample
+/// its presence during the previous instant, or wait for its presence before the next step.
+pub fn state_machine<S, F>(initial: S, step: F) -> StateMachineProcess<S, F>
+where
+  S: 'static,
+  F: FnMut(S, &mut StepCtx) -> Transition<S> + 'static
+{
+  StateMachineProcess { state: initial, step }
+}
+
+
+impl<S, F> StateMachineProcess<S, F>
+where
+  S: 'static,
+  F: FnMut(S, &mut StepCtx) -> Transition<S> + 'static
+{
+  /// Runs a step of the machine, and registers the next one if it is not stopped.
+  fn run_step<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<S> {
+    let (transition, next_step) = {
+      let mut ctx = StepCtx { runtime, next_step: NextStep::NextInstant };
+      let transition = (self.step)(self.state, &mut ctx);
+      (transition, ctx.next_step)
+    };
+
+    match transition {
+      Transition::Stop(state) => next.call(runtime, state),
+      Transition::Next(state) => {
+        let machine = StateMachineProcess { state, step: self.step };
+        let resume  = move |r: &mut Runtime, _: ()| machine.run_step(r, next);
+
+        match next_step {
+          NextStep::NextInstant          => runtime.schedule_next(resume),
+          NextStep::InInstants(instants) => runtime.on_instant_in(instants, Box::new(resume)),
+          NextStep::AfterSignal(waker)   => waker(runtime, Box::new(resume))
+        }
+      }
+    }
+  }
+}
+
+
+impl<S, F> Process for StateMachineProcess<S, F>
+where
+  S: 'static,
+  F: FnMut(S, &mut StepCtx) -> Transition<S> + 'static
+{
+  type Value = S;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    self.run_step(runtime, next);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PROCESS LOCAL
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Identifier of the next process-local variable.
+static NEXT_LOCAL_ID: AtomicUsize = AtomicUsize::new(0);
+
+
+/// A variable whose value is stored in the runtime executing the processes using it, so that
+/// it can be shared by several closures without being wrapped in a `Rc<RefCell<_>>`.
+///
+/// The value is initialized when it is first accessed in a runtime, and lives until it is
+/// removed (see `remove` and `owned_by`) or the runtime is torn down. Clones of a variable
+/// share its value.
+pub struct ProcessLocal<T> {
+  id  : usize,
+  init: Rc<dyn Fn() -> T>
+}
+
+
+impl<T> Clone for ProcessLocal<T> {
+  fn clone(&self) -> Self {
+    ProcessLocal { id: self.id, init: self.init.clone() }
+  }
+}
+
+
+impl<T> ProcessLocal<T> where T: 'static {
+  /// Create a new `ProcessLocal`, whose value is initialized by `init` in each runtime.
+  pub fn new<F>(init: F) -> Self where F: Fn() -> T + 'static {
+    ProcessLocal { id: NEXT_LOCAL_ID.fetch_add(1, Ordering::Relaxed), init: Rc::new(init) }
+  }
+
+  /// Calls `f` with the value of the variable in the given runtime, and returns its result.
+  pub fn with<F, O>(&self, runtime: &mut Runtime, f: F) -> O where F: FnOnce(&mut T) -> O {
+    let init  = &self.init;
+    let value = runtime.locals.entry(self.id).or_insert_with(|| Box::new(init()));
+    f(value.downcast_mut::<T>().unwrap())
+  }
+
+  /// Returns a copy of the value of the variable in the given runtime.
+  pub fn get_clone(&self, runtime: &mut Runtime) -> T where T: Clone {
+    self.with(runtime, |value| value.clone())
+  }
+
+  /// Sets the value of the variable in the given runtime.
+  pub fn set(&self, runtime: &mut Runtime, value: T) {
+    runtime.locals.insert(self.id, Box::new(value));
+  }
+
+  /// Replaces the value of the variable in the given runtime by the value `f` returns from it.
+  pub fn update<F>(&self, runtime: &mut Runtime, f: F) where F: FnOnce(&T) -> T {
+    self.with(runtime, |value| *value = f(value));
+  }
+
+  /// Removes the variable from the given runtime, and returns its value if it has one.
+  /// It is initialized again if it is accessed afterwards.
+  pub fn remove(&self, runtime: &mut Runtime) -> Option<T> {
+    runtime.locals.remove(&self.id).map(|value| *value.downcast::<T>().unwrap())
+  }
+
+  /// Returns a process which calls `f` with the value of the variable each time it is ran,
+  /// and returns its result.
+  pub fn access<F, O>(&self, f: F) -> LocalAccessProcess<T, F> where F: FnMut(&mut T) -> O + 'static {
+    LocalAccessProcess { local: self.clone(), function: f }
+  }
+
+  /// Returns a process running `process`, and removing the variable from the runtime
+  /// once `process` has completed, or once it has been preempted (e.g. by `until`).
+  pub fn owned_by<P>(&self, process: P) -> LocalOwnerProcess<T, P> where P: Process {
+    LocalOwnerProcess { local: self.clone(), process }
+  }
+}
+
+
+/// A process accessing the value of a process-local variable (see `ProcessLocal::access`).
+pub struct LocalAccessProcess<T, F> {
+  local   : ProcessLocal<T>,
+  function: F
+}
+
+
+impl<T, F, O> Process for LocalAccessProcess<T, F> where T: 'static, F: FnMut(&mut T) -> O + 'static {
+  type Value = O;
+
+  fn call<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let value = self.local.with(runtime, &mut self.function);
+    next.call(runtime, value);
+  }
+}
+
+
+impl<T, F, O> ProcessMut for LocalAccessProcess<T, F> where T: 'static, F: FnMut(&mut T) -> O + 'static {
+  fn call_mut<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let value = self.local.with(runtime, &mut self.function);
+    next.call(runtime, (self, value));
+  }
+}
+
+
+/// A process removing a process-local variable once another process has completed,
+/// or has been preempted (see `ProcessLocal::owned_by`).
+pub struct LocalOwnerProcess<T, P> {
+  local  : ProcessLocal<T>,
+  process: P
+}
+
+
+impl<T, P> Process for LocalOwnerProcess<T, P> where T: 'static, P: Process {
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let local_1 = self.local;
+    let local_2 = local_1.clone();
+
+    // If the process is preempted (i.e. its cancellation context is cancelled), it never
+    // completes: the variable is then removed at the end of the instant it is preempted during
+    if let Some(handle) = runtime.cancel_handle() {
+      let completed_1 = Rc::new(Cell::new(false));
+      let completed_2 = completed_1.clone();
+
+      runtime.on_end_of_each_instant(Box::new(move |r: &mut Runtime| {
+        if completed_1.get() {
+          return false;
+        }
+        if handle.is_cancelled() {
+          local_2.remove(r);
+          return false;
+        }
+
+        true
+      }));
+
+      self.process.call(runtime, move |r: &mut Runtime, v: P::Value| {
+        completed_2.set(true);
+        local_1.remove(r);
+        next.call(r, v);
+      });
+    }
+    else {
+      self.process.call(runtime, move |r: &mut Runtime, v: P::Value| {
+        local_1.remove(r);
+        next.call(r, v);
+      });
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DESCRIBE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+// Shape of a combinator built from a single process.
+fn describe_inner<P>(name: &'static str, process: &P) -> ProcessShape where P: Describe {
+  ProcessShape::Combinator { name, inner: vec![process.describe()] }
+}
+
+impl<V> Describe for ValueProcess<V> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Value
+  }
+}
+
+impl<F> Describe for FnProcess<F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "FromFn" }
+  }
+}
+
+impl<F, V> Describe for F where F: FnOnce() -> V {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Fn" }
+  }
+}
+
+impl<P> Describe for PauseProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Pause { inner: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P> Describe for PauseNProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("PauseN", &self.process)
+  }
+}
+
+impl<P, F> Describe for MapProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Map { inner: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P, F> Describe for InspectProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Inspect", &self.process)
+  }
+}
+
+impl<PP> Describe for FlattenProcess<PP> where PP: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Flatten { inner: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P1, P2> Describe for JoinProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Join { left: Box::new(self.process_1.describe()), right: Box::new(self.process_2.describe()) }
+  }
+}
+
+impl<P1, P2> Describe for ThenProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Then", inner: vec![self.process_1.describe(), self.process_2.describe()] }
+  }
+}
+
+impl<P, P1, P2> Describe for IfElseProcess<P, P1, P2> where P: Describe, P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator {
+      name : "IfElse",
+      inner: vec![self.condition.describe(), self.then_process.describe(), self.else_process.describe()]
+    }
+  }
+}
+
+impl<P1, P2> Describe for ZipProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Zip", &self.join)
+  }
+}
+
+impl<P1, P2, P3> Describe for Join3Process<P1, P2, P3> where P1: Describe, P2: Describe, P3: Describe {
+  fn describe(&self) -> ProcessShape {
+    self.join.describe()
+  }
+}
+
+impl<P1, P2, P3, P4> Describe for Join4Process<P1, P2, P3, P4>
+where
+  P1: Describe,
+  P2: Describe,
+  P3: Describe,
+  P4: Describe
+{
+  fn describe(&self) -> ProcessShape {
+    self.join.describe()
+  }
+}
+
+impl<P1, P2> Describe for WithProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    self.join.describe()
+  }
+}
+
+impl<P> Describe for JoinAllProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "JoinAll", inner: self.processes.iter().map(Describe::describe).collect() }
+  }
+}
+
+impl<P> Describe for WhileProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Loop { body: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P> Describe for WhileMaxProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Loop { body: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P, F> Describe for OrElseProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("OrElse", &self.process)
+  }
+}
+
+impl<P, T> Describe for UnwrapOrProcess<P, T> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("UnwrapOr", &self.process)
+  }
+}
+
+impl<P, F> Describe for MapErrProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("MapErr", &self.process)
+  }
+}
+
+impl<P, F> Describe for MapOkProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("MapOk", &self.process)
+  }
+}
+
+impl<P, F> Describe for AndThenOkProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("AndThenOk", &self.process)
+  }
+}
+
+impl<P, V> Describe for Generator<P, V> where P: Describe, V: Clone + 'static {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Generator", &self.body)
+  }
+}
+
+impl<V> Describe for YieldProcess<V> where V: Clone + 'static {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Yield" }
+  }
+}
+
+impl Describe for InstantIndexProcess {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "InstantIndex" }
+  }
+}
+
+impl<P> Describe for SequenceProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Sequence", inner: self.steps.iter().map(|step| step.describe()).collect() }
+  }
+}
+
+impl<P, S, F> Describe for ScanProcess<P, S, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Map { inner: Box::new(self.process.describe()) }
+  }
+}
+
+impl<F> Describe for EveryInstantProcess<F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "EveryInstant" }
+  }
+}
+
+impl<P, F> Describe for FilterMapLoopProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "FilterMapLoop", inner: vec![self.process.describe()] }
+  }
+}
+
+impl<V> Describe for BoxProcess<V> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Box" }
+  }
+}
+
+impl<V> Describe for RecProcess<V> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Rec" }
+  }
+}
+
+impl<F> Describe for LazyProcess<F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Lazy" }
+  }
+}
+
+impl<P, F> Describe for MapWithRuntimeProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Map { inner: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P1, P2> Describe for RaceProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Race", inner: vec![self.process_1.describe(), self.process_2.describe()] }
+  }
+}
+
+impl<P1, P2> Describe for SelectEitherProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Select", inner: vec![self.process_1.describe(), self.process_2.describe()] }
+  }
+}
+
+impl<P> Describe for SelectProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Select", inner: self.processes.iter().map(Describe::describe).collect() }
+  }
+}
+
+impl<P1, P2> Describe for InterleaveProcess<P1, P2> where P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "Interleave", inner: vec![self.process_1.describe(), self.process_2.describe()] }
+  }
+}
+
+impl<I, F> Describe for ForEachProcess<I, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: if self.pause { "ForEach" } else { "ForEachImmediate" } }
+  }
+}
+
+impl<T, F> Describe for ForEachConcurrentProcess<T, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "ForEachConcurrent" }
+  }
+}
+
+impl<P> Describe for TakeProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Take", &self.process)
+  }
+}
+
+impl<P, S, F> Describe for WhileFoldProcess<P, S, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Loop { body: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P> Describe for LoopProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Loop { body: Box::new(self.process.describe()) }
+  }
+}
+
+impl<P> Describe for SkipProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Skip", &self.process)
+  }
+}
+
+impl<P> Describe for OneInstantProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("OneInstant", &self.process)
+  }
+}
+
+impl<P> Describe for AtInstantProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("AtInstant", &self.process)
+  }
+}
+
+impl<P> Describe for CountInstantsProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("CountInstants", &self.process)
+  }
+}
+
+impl<P> Describe for TraceTimingProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("TraceTiming", &self.process)
+  }
+}
+
+impl<P, S> Describe for UntilProcess<P, S> where P: Describe, S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Until { inner: Box::new(self.process.describe()), signal_name: self.signal.clone().core().name() }
+  }
+}
+
+impl<P, S> Describe for WhenProcess<P, S> where P: Describe, S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "When", inner: vec![self.process.describe()] }
+  }
+}
+
+impl<P, S1, S2> Describe for ControlProcess<P, S1, S2> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Control", &self.process)
+  }
+}
+
+impl<P> Describe for TimeoutProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Timeout", &self.process)
+  }
+}
+
+impl<P> Describe for CatchPanicProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("CatchPanic", &self.process)
+  }
+}
+
+impl<P, V> Describe for SharedProcess<P, V> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Shared", &self.process)
+  }
+}
+
+impl<V> Describe for AwaitSharedProcess<V> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "AwaitShared" }
+  }
+}
+
+impl<P> Describe for SpawnBackgroundProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("SpawnBackground", &self.process)
+  }
+}
+
+impl<P, F> Describe for FinallyProcess<P, F> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Finally", &self.process)
+  }
+}
+
+impl<F> Describe for ScopeProcess<F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "Scope" }
+  }
+}
+
+impl<P> Describe for SpawnProcess<P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("Spawn", &self.child)
+  }
+}
+
+impl<S, F> Describe for StateMachineProcess<S, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "StateMachine" }
+  }
+}
+
+impl<T, F> Describe for LocalAccessProcess<T, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "LocalAccess" }
+  }
+}
+
+impl<T, P> Describe for LocalOwnerProcess<T, P> where P: Describe {
+  fn describe(&self) -> ProcessShape {
+    describe_inner("LocalOwner", &self.process)
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+  use std::cell::{Cell, RefCell};
+  use std::collections::VecDeque;
+
+  use std::ops::ControlFlow;
+
+  use runtime::InstantSummary;
+  use signals::signals::SignalHandle;
+  use testing::{SignalAssertions, Recorder, Counter};
+  use super::*;
+
+
+  #[test]
+  fn wait_two_instants () {
+    let mut runtime = Runtime::new();
+
+    let flag_ref      = Rc::new(RefCell::new(0));
+    let flag_ref_copy = flag_ref.clone();
+
+    runtime.on_current_instant(Box::new((move |_r: &mut Runtime, ()| {
+      *flag_ref_copy.borrow_mut() = 42;
+    }).pause_n(2)));
+
+    let mut work_remains = runtime.instant();
+    assert_eq!(*flag_ref.borrow_mut(), 0);
+    assert!(work_remains);
+
+    work_remains = runtime.instant();
+    assert_eq!(*flag_ref.borrow_mut(), 0);
+    assert!(work_remains);
+
+    work_remains = runtime.instant();
+    assert_eq!(*flag_ref.borrow_mut(), 42);
+    assert!(!work_remains);
+
+    // There should not be any task left
+    work_remains = runtime.instant();
+    assert!(!work_remains);
+  }
+
+  #[test]
+  fn pause_n_instants () {
+    assert_eq!(execute_process(instant_index().pause_n(0).then(instant_index())), 0);
+    assert_eq!(execute_process(value(()).pause_n(50).then(instant_index())), 50);
+
+    // The number of instants is kept across runs
+    let runs = value(()).pause_n(3).then(instant_index()).take(3);
+    assert_eq!(execute_process(runs), vec![3, 6, 9]);
+  }
+
+
+  #[test]
+  fn from_fn_is_lazy () {
+    let calls    = Counter::new();
+    let mut call = calls.incrementing();
+
+    let process = from_fn(move || { call(); 21 }).map(|v| v * 2);
+    assert_eq!(calls.get(), 0);
+
+    assert_eq!(execute_process(process), 42);
+    assert_eq!(calls.get(), 1);
+  }
+
+  #[test]
+  fn every_instant_one_value_per_instant () {
+    let mut runtime = Runtime::new();
+
+    let values     = Recorder::new();
+    let mut record = values.recording();
+
+    every_instant(|i| i * 10).take(4).call(&mut runtime, move |r: &mut Runtime, v: Vec<u64>| record((v, r.instant_index())));
+
+    // The fourth value is returned during instant 3, and no instant is executed after
+    let mut instants = 1;
+    while runtime.instant() {
+      instants += 1;
+    }
+
+    assert_eq!(instants, 4);
+    assert_eq!(values.values(), vec![(vec![0, 10, 20, 30], 3)]);
+
+    let squares = every_instant(|i| i).zip(every_instant(|i| i * i)).take(3);
+    assert_eq!(execute_process(squares.then(instant_index())), 2);
+
+    let squares = every_instant(|i| i).zip(every_instant(|i| i * i)).take(3);
+    assert_eq!(execute_process(squares), vec![(0, 0), (1, 1), (2, 4)]);
+  }
+
+  #[test]
+  fn every_instant_emit_output () {
+    let signal    = ValueSignal::new();
+    let recording = signal.record();
+
+    execute_process(every_instant(|i| i + 1).emit_output(signal.clone()).take(3));
+    assert_eq!(recording.into_vec(), vec![(0, Some(vec![1])), (1, Some(vec![2])), (2, Some(vec![3]))]);
+  }
+
+  #[test]
+  fn lazy_builds_taken_branch () {
+    let builds      = Counter::new();
+    let mut build_1 = builds.incrementing();
+    let mut build_2 = builds.incrementing();
+
+    // The else branch is only built once the signal is known to be absent
+    let signal  = PureSignal::new();
+    let process = signal.present(value(1), lazy(move || { build_1(); value(2) }));
+    assert_eq!(builds.get(), 0);
+
+    assert_eq!(execute_process(process), 2);
+    assert_eq!(builds.get(), 1);
+
+    let signal  = PureSignal::new();
+    let process = signal.clone().present(value(1), lazy(move || { build_2(); value(2) }));
+    assert_eq!(execute_process(process.join(signal.emit())).0, 1);
+    assert_eq!(builds.get(), 1);
+  }
+
+  #[test]
+  fn lazy_in_loop () {
+    let mut counter = 0;
+    let build = move || { counter += 1; value(counter).pause() };
+
+    assert_eq!(execute_process(lazy(build).take(3).then(instant_index())), 3);
+    assert_eq!(execute_process(lazy(build).take(3)), vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn closures_as_processes () {
+    let signal  = PureSignal::new();
+    let process = signal.clone().present(|| 1, || 2);
+    assert_eq!(execute_process(process.join(signal.emit())).0, 1);
+
+    let signal  = PureSignal::new();
+    let process = signal.present(|| 1, || 2).and_then(|v| (move || v * 10).pause());
+    assert_eq!(execute_process(process.join(|| "joined")), (20, "joined"));
+
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+    assert_eq!(execute_process(count.pause().take(3)), vec![1, 2, 3]);
+    assert_eq!(count.map(|v| v * 2).describe().to_string(), "Map\n  Fn");
+  }
+
+  #[test]
+  fn from_fn_in_loop () {
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+
+    assert_eq!(execute_process(from_fn(count).pause().take(3)), vec![1, 2, 3]);
+  }
+
+
+  #[test]
+  fn and_then_one_shot_process_in_loop () {
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+
+    // Each iteration builds a process which can only be ran once, since it moves its name out
+    let build = |i: usize| {
+      let name = format!("run {}", i);
+      from_fn(move || name).pause()
+    };
+
+    let runs = from_fn(count).and_then(build).take(3).then(instant_index());
+    assert_eq!(execute_process(runs), 3);
+
+    let runs = from_fn(count).and_then(build).take(3);
+    assert_eq!(execute_process(runs), vec!["run 1", "run 2", "run 3"]);
+  }
+
+  #[test]
+  fn inspect_forwards_value () {
+    let seen       = Recorder::new();
+    let mut record = seen.recording();
+
+    let process = value(vec![1, 2]).inspect(move |v: &Vec<u32>| record(v.len())).pause();
+    assert_eq!(execute_process(process), vec![1, 2]);
+    assert_eq!(seen.values(), vec![2]);
+  }
+
+  #[test]
+  fn inspect_in_loop () {
+    let seen       = Recorder::new();
+    let mut record = seen.recording();
+
+    let loop_status = |i: usize| if i < 3 { LoopStatus::Continue } else { LoopStatus::Exit(i) };
+    let iteration   = instant_index().inspect(move |i| record(*i)).pause().map(loop_status);
+
+    assert_eq!(execute_process(iteration.while_loop()), 3);
+    assert_eq!(seen.values(), vec![0, 1, 2, 3]);
+  }
+
+
+  #[test]
+  fn map_with_runtime_registers_task () {
+    let instants   = Recorder::new();
+    let mut record = instants.recording();
+
+    let register = move |r: &mut Runtime, v: usize| {
+      r.on_next_instant(Box::new(move |r: &mut Runtime, ()| record(r.instant_index())));
+      v * 2
+    };
+
+    assert_eq!(execute_process(value(21).map_with_runtime(register)), 42);
+    assert_eq!(instants.values(), vec![1]);
+  }
+
+  #[test]
+  fn map_with_runtime_emits_signal () {
+    let signal   = PureSignal::new();
+    let signal_1 = signal.clone();
+
+    // The signal is emitted imperatively during the second iteration, i.e. during instant 2
+    let mut counter = 0;
+    let emit_once = move |r: &mut Runtime, _| {
+      counter += 1;
+      if counter == 2 { signal_1.clone().core().emit_element(r, ()); }
+      if counter < 3 { LoopStatus::Continue } else { LoopStatus::Exit(counter) }
+    };
+
+    let emitter = value(()).pause().map_with_runtime(emit_once).while_loop();
+    let awaiter = signal.await_immediate().then(instant_index());
+    assert_eq!(execute_process(emitter.join(awaiter)), (3, 2));
+  }
+
+  #[test]
+  fn iterate_counter () {
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+
+    let values: Vec<_> = iterate(from_fn(count).pause()).take(4).collect();
+    assert_eq!(values, vec![1, 2, 3, 4]);
+
+    // The runtime is kept across the runs, each run starting during the instant after the last one
+    let instants: Vec<_> = iterate(instant_index().pause()).take(3).collect();
+    assert_eq!(instants, vec![0, 2, 4]);
+  }
+
+  #[test]
+  fn iterate_stuck_process () {
+    let signal = PureSignal::new();
+    let mut iterations = iterate(value(()).pause().then(signal.await_immediate()));
+
+    assert_eq!(iterations.next(), None);
+    assert_eq!(iterations.next(), None);
+  }
+
+  #[test]
+  fn scan_running_sum_of_signal_values () {
+    let signal: ValueSignal<Vec<usize>, usize> = ValueSignal::new();
+
+    // Values 1 to 5 are emitted during instants 0 to 4, and awaited during instants 1 to 5
+    let next_value  = |i: usize| i + 1;
+    let emit_values = instant_index().map(next_value).emit_output(signal.clone()).pause().take(5);
+    let running_sum = signal.await().scan(0, |sum, values: Vec<usize>| { *sum += values.iter().sum::<usize>(); *sum });
+
+    assert_eq!(execute_process(emit_values.join(running_sum.take(5))).1, vec![1, 3, 6, 10, 15]);
+  }
+
+  #[test]
+  fn map_to_multiply () {
+    let process = value(21).map(|v| { 2*v });
+    let return_value = execute_process(process);
+
+    assert_eq!(42, return_value);
+  }
+
+
+  #[test]
+  fn map_and_pause_to_multiply () {
+    let process = value(21).pause().map(|v| { 2*v }).pause();
+
+    let return_value = execute_process(process);
+    assert_eq!(42, return_value);
+  }
+
+
+  #[test]
+  fn join_sum_with_delay () {
+    let immediate_process = value(10);
+    let paused_process    = value(32).pause().pause().pause();
+
+    let join_and_pause_process = immediate_process.join(paused_process)
+      .map(|(v1, v2)| { v1 + v2 });
+
+    let return_value = execute_process(join_and_pause_process);
+    assert_eq!(42, return_value);
+  }
+
+  #[test]
+  fn then_runs_in_sequence () {
+    let log          = Recorder::new();
+    let mut record_1 = log.recording();
+    let mut record_2 = log.recording();
+
+    let first  = instant_index().map(move |i| record_1(("first", i))).pause();
+    let second = instant_index().map(move |i| { record_2(("second", i)); 42 });
+
+    assert_eq!(execute_process(first.then(second)), 42);
+    assert_eq!(log.values(), vec![("first", 0), ("second", 1)]);
+  }
+
+  #[test]
+  fn then_in_loop () {
+    let counter       = Counter::new();
+    let mut increment = counter.incrementing();
+
+    let count = move |_| {
+      let count = increment();
+      if count == 5 { LoopStatus::Exit(count) } else { LoopStatus::Continue }
+    };
+
+    // Each iteration pauses once: the loop completes during instant 5
+    let iteration = value(()).pause().then(instant_index()).map(count);
+    assert_eq!(execute_process(iteration.while_loop().then(instant_index())), 5);
+    assert_eq!(counter.get(), 5);
+  }
+
+  #[test]
+  fn clone_composed_process () {
+    let runs    = Counter::new();
+    let mut run = runs.incrementing();
+
+    let template = value(20).pause().map(move |v| { run(); v + 1 }).join(value(1));
+    let copy     = template.clone();
+
+    assert_eq!(execute_process(template.map(|(v1, v2)| v1 + v2)), 22);
+    assert_eq!(execute_process(copy.then(instant_index())), 1);
+    assert_eq!(runs.get(), 2);
+  }
+
+  #[test]
+  fn clone_branches_of_present () {
+    let branch = value(1).pause().map(|v| v * 10);
+
+    let signal_1 = PureSignal::new();
+    let signal_2 = PureSignal::new();
+    let present_1 = signal_1.clone().present(branch.clone(), value(0));
+    let present_2 = signal_2.present(value(0), branch);
+
+    assert_eq!(execute_process(present_1.join(present_2).join(signal_1.emit())).0, (10, 10));
+
+    // Both copies of a loop run their own iterations
+    let loop_status = |i: usize| if i < 2 { LoopStatus::Continue } else { LoopStatus::Exit(i) };
+    let counter = instant_index().pause().map(loop_status).while_loop();
+    assert_eq!(execute_process(counter.clone().join(counter)), (2, 2));
+  }
+
+  #[test]
+  fn if_else_runs_chosen_process () {
+    assert_eq!(execute_process(value(true).if_else(value(1), value(2).pause())), 1);
+    assert_eq!(execute_process(value(false).if_else(value(1), value(2).pause())), 2);
+  }
+
+  #[test]
+  fn if_else_in_loop () {
+    let is_even = |i: usize| i.is_multiple_of(2);
+
+    // Both processes are kept across iterations, the condition alternates from an instant to the next
+    let iteration = instant_index().map(is_even).if_else(value("even"), value("odd")).pause();
+    assert_eq!(execute_process(iteration.take(4)), vec!["even", "odd", "even", "odd"]);
+  }
+
+  #[test]
+  fn join3_and_join4_flatten_values () {
+    // The processes complete in various orders, the values keep the order of the processes
+    let process_3 = value(1).pause().pause().join3(value("two"), value(3.0).pause());
+    assert_eq!(execute_process(process_3), (1, "two", 3.0));
+
+    let process_4 = value(1).join4(value(2).pause().pause(), value(3).pause(), value(4).pause().pause().pause());
+    assert_eq!(execute_process(process_4), (1, 2, 3, 4));
+  }
+
+  #[test]
+  fn join3_in_loop () {
+    let mut sum = 0;
+    let count = move |(a, b, c): (usize, usize, usize)| {
+      sum += a + b + c;
+      if sum >= 9 { LoopStatus::Exit(sum) } else { LoopStatus::Continue }
+    };
+
+    let process = instant_index().join3(instant_index().pause(), instant_index()).map(count).while_loop();
+    assert_eq!(execute_process(process), 9);
+  }
+
+  #[test]
+  fn with_side_process () {
+    let signal  = PureSignal::new();
+    let log     = signal.attach();
+    let awaited = PureSignal::new();
+
+    // The value is the one of the awaiter, once both processes have completed
+    let awaiter = awaited.clone().await_immediate().then(value(42));
+    let process = awaiter.with(signal.clone().emit().pause());
+    let value: usize = execute_process(process.join(awaited.emit())).0;
+
+    assert_eq!(value, 42);
+    log.assert_present_at(&[0]);
+
+    // Each iteration of a loop body emits the signal
+    let signal    = PureSignal::new();
+    let log       = signal.attach();
+    let iteration = instant_index().with(signal.emit()).pause();
+
+    assert_eq!(execute_process(iteration.take(3)), vec![0, 1, 2]);
+    log.assert_present_at(&[0, 1, 2]);
+  }
+
+  #[test]
+  fn join_macro_flattens_values () {
+    assert_eq!(execute_process(join!(value(1), value("two").pause())), (1, "two"));
+    assert_eq!(execute_process(join!(value(1).pause(), value(2), value(3).pause().pause())), (1, 2, 3));
+
+    let process = join!(value(1), value(2).pause(), value(3), value(4).pause().pause(), value(5));
+    assert_eq!(execute_process(process), (1, 2, 3, 4, 5));
+  }
+
+  #[test]
+  fn join_macro_in_loop () {
+    let mut sum = 0;
+    let count = move |(a, b, c, d, e): (usize, usize, usize, usize, usize)| {
+      sum += a + b + c + d + e;
+      if sum >= 15 { LoopStatus::Exit(sum) } else { LoopStatus::Continue }
+    };
+
+    // Iterations start during instants 0, 1 and 2
+    let process = join!(instant_index(), instant_index().pause(), instant_index(), instant_index(), instant_index());
+    assert_eq!(execute_process(process.map(count).while_loop()), 15);
+  }
+
+  #[test]
+  fn race_returns_first_value () {
+    let process = value(1).race(value(2).pause()).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (1, 0));
+
+    let process = value(1).pause().pause().race(value(2).pause());
+    assert_eq!(execute_process(process), 2);
+  }
+
+  #[test]
+  fn race_ties_favor_left () {
+    assert_eq!(execute_process(value(1).race(value(2))), 1);
+    assert_eq!(execute_process(value(1).pause().race(value(2).pause())), 1);
+
+    // The right process completes first during instant 1, but the left one still wins
+    let signal  = PureSignal::new();
+    let process = signal.clone().await().map(|_| 1).race(value(2).pause()).join(signal.emit());
+    assert_eq!(execute_process(process).0, 1);
+  }
+
+  #[test]
+  fn select_either_winner () {
+    let timeout = value(()).pause().pause();
+
+    // A value signal emitted before the timeout
+    let signal  = ValueSignal::new();
+    let process = signal.clone().await().select(timeout).join(signal.emit_value(1).pause());
+    assert_eq!(execute_process(process).0, Either::Left(vec![1]));
+
+    // A value signal never emitted: the timeout wins, and the signal is never awaited again
+    let signal: ValueSignal<Vec<usize>, usize> = ValueSignal::new();
+    let process = signal.await().select(value(()).pause().pause()).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (Either::Right(()), 2));
+  }
+
+  #[test]
+  fn select_either_ties_favor_left () {
+    assert_eq!(execute_process(value(1).select(value("right"))), Either::Left(1));
+    assert_eq!(execute_process(value(1).pause().select(value("right").pause())), Either::Left(1));
+
+    // The losing process still completes, without delivering its value
+    let completions  = Counter::new();
+    let mut complete = completions.incrementing();
+
+    let right   = value(()).pause().map(move |_| { complete(); });
+    let process = value(1).pause().select(right).then(value(()).pause());
+
+    assert_eq!(execute_process(process), ());
+    assert_eq!(completions.get(), 1);
+  }
+
+  #[test]
+  fn select_first_completed_process () {
+    let never_emitted = PureSignal::new();
+    let winner        = PureSignal::new();
+    let late          = PureSignal::new();
+
+    // The winner is emitted after a pause, and awaited until the instant after: the late signal
+    // completes its process afterwards, while the program keeps running
+    let select  = select_any(vec![never_emitted.await(), late.clone().await(), winner.clone().await()])
+      .and_then(|(index, _)| instant_index().map(move |i| (index, i)));
+    let emitter = value(()).pause().then(winner.emit()).then(at_instant(5, late.emit()));
+
+    assert_eq!(execute_process(select.join(emitter)).0, (2, 2));
+  }
+
+  #[test]
+  fn take_collects_values () {
+    assert_eq!(execute_process(value(1).take(5)), vec![1; 5]);
+    assert_eq!(execute_process(value(1).take(0)), vec![]);
+    assert_eq!(execute_process(value(1).first_n(2)), vec![1; 2]);
+    assert_eq!(execute_process(value(1).repeat(2)), vec![1; 2]);
+
+    // Runs which do not pause are scheduled through the runtime, instead of nesting calls
+    assert_eq!(execute_process(value(()).take(100_000)).len(), 100_000);
+  }
+
+  #[test]
+  fn take_emissions () {
+    let signal = PureSignal::new();
+
+    // The signal is emitted during instants 0 to 2, and each emission is awaited
+    let emitter = signal.clone().emit().pause().take(3);
+    let awaiter = signal.clone().await().then(instant_index()).take(3);
+
+    assert_eq!(execute_process(emitter.join(awaiter)).1, vec![1, 2, 3]);
+  }
+
+  #[test]
+  fn filter_map_loop_skips_iterations () {
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+    let every_third = |i: usize| if i.is_multiple_of(3) { Some(i / 3) } else { None };
+
+    // Skipped iterations run during the same instant
+    let process = from_fn(count).filter_map_loop(every_third).take(3).then(instant_index());
+    assert_eq!(execute_process(process), 0);
+
+    let process = from_fn(count).filter_map_loop(every_third).take(3);
+    assert_eq!(execute_process(process), vec![1, 2, 3]);
+
+    // Pausing iterations are not merged into a single instant
+    let process = from_fn(count).pause().filter_map_loop(every_third).take(3).then(instant_index());
+    assert_eq!(execute_process(process), 9);
+  }
+
+  #[test]
+  fn boxed_processes_in_sequence () {
+    let signal = PureSignal::new();
+    let log    = Recorder::new();
+    let record = log.recording();
+
+    let mut record_awaited = log.recording();
+    let steps: Vec<BoxProcess<()>> = vec![
+      value(21).map(|v| v * 2).map(record.clone()).boxed(),
+      signal.clone().await_immediate().map(move |_| record_awaited(0)).boxed(),
+      instant_index().pause().map(record).boxed()
+    ];
+
+    execute_process(sequence(steps).join(signal.emit()));
+    assert_eq!(log.values(), vec![42, 0, 0]);
+  }
+
+  #[test]
+  fn boxed_process_chosen_at_runtime () {
+    let choose = |pause: bool| if pause { value(1).pause().boxed() } else { value(2).boxed() };
+
+    assert_eq!(execute_process(value(true).and_then(choose).then(instant_index())), 1);
+    assert_eq!(execute_process(value(false).and_then(choose)), 2);
+  }
+
+  #[test]
+  fn rec_countdown () {
+    // Counts down from the given number to zero, pausing once per level of recursion
+    let countdown = |from: usize| {
+      let remaining = Rc::new(Cell::new(from));
+
+      rec(move |itself: BoxProcess<usize>| {
+        let remaining = remaining.clone();
+
+        BoxProcess::new(lazy(move || match remaining.get() {
+          0 => BoxProcess::new(instant_index()),
+          n => { remaining.set(n - 1); BoxProcess::new(value(()).pause().then(itself)) }
+        }))
+      })
+    };
+
+    assert_eq!(execute_process(countdown(0)), 0);
+    assert_eq!(execute_process(countdown(5)), 5);
+  }
+
+  #[test]
+  fn rec_without_pause () {
+    let remaining = Rc::new(Cell::new(100_000));
+
+    // Recursive runs are scheduled through the runtime, and do not overflow the stack
+    let countdown = rec(move |itself: BoxProcess<usize>| {
+      let remaining = remaining.clone();
+
+      BoxProcess::new(lazy(move || match remaining.get() {
+        0 => BoxProcess::new(instant_index()),
+        n => { remaining.set(n - 1); itself }
+      }))
+    });
+
+    assert_eq!(execute_process(countdown), 0);
+  }
+
+  #[test]
+  fn loop_forever_joined_with_finite_process () {
+    let mut runtime = Runtime::new();
+    let signal      = PureSignal::new();
+
+    let completions  = Counter::new();
+    let mut complete = completions.incrementing();
+
+    // The signal is emitted during each instant, and awaited three times (until instant 3)
+    let emitter = signal.clone().emit().pause().loop_forever();
+    let awaiter = signal.clone().await().take(3).map(move |_| { complete(); });
+
+    emitter.join(awaiter).call(&mut runtime, |_: &mut Runtime, _: ((), ())| {
+      panic!("A process running in an infinite loop completed");
+    });
+
+    for _ in 0..3 {
+      assert!(runtime.instant());
+      assert_eq!(completions.get(), 0);
+    }
+
+    assert!(runtime.instant());
+    assert_eq!(completions.get(), 1);
+
+    // The emitter is still running
+    assert!(runtime.instant());
+  }
+
+  #[test]
+  fn zip_in_lockstep () {
+    let (mut counter_1, mut counter_2) = (0, 0);
+    let count_1 = move || { counter_1 += 1; counter_1 };
+    let count_2 = move || { counter_2 += 1; counter_2 };
+
+    // The immediate counter waits for the paused one during each round
+    let rounds = from_fn(count_1).zip(from_fn(count_2).pause()).take(3).then(instant_index());
+    assert_eq!(execute_process(rounds), 3);
+
+    let rounds = from_fn(count_1).zip(from_fn(count_2).pause()).take(3);
+    assert_eq!(execute_process(rounds), vec![(1, 1), (2, 2), (3, 3)]);
+
+    // Each round lasts as long as the slowest process, which the fastest one does not outrun
+    let rounds = instant_index().zip(instant_index().pause_n(2)).take(3);
+    assert_eq!(execute_process(rounds), vec![(0, 0), (2, 2), (4, 4)]);
+  }
+
+  #[test]
+  fn join_all_keeps_order () {
+    // The processes complete in another order than the order of the vector
+    let processes = vec![3, 0, 2, 1].into_iter().map(|i| at_instant(i, value(i)).pause()).collect();
+    let process   = join_all(processes).and_then(|values| instant_index().map(move |i| (values, i)));
+
+    assert_eq!(execute_process(process), (vec![3, 0, 2, 1], 4));
+    assert_eq!(execute_process(join_all(Vec::<ValueProcess<()>>::new())), vec![]);
+  }
+
+  #[test]
+  fn sequence_keeps_order () {
+    // Each step samples the instant index when it starts: pauses delay the next steps
+    let steps = vec![
+      instant_index().pause_n(0),
+      instant_index().pause_n(2),
+      instant_index().pause_n(0),
+      instant_index().pause_n(1)
+    ];
+
+    let process = sequence(steps).and_then(|values| instant_index().map(move |i| (values, i)));
+    assert_eq!(execute_process(process), (vec![0, 0, 2, 2], 3));
+
+    let empty: Vec<ValueProcess<()>> = vec![];
+    assert_eq!(execute_process(sequence(empty)), vec![]);
+  }
+
+  #[test]
+  fn sequence_in_loop () {
+    // The pipeline lasts two instants, and is ran twice
+    let steps = vec![value(1).pause_n(0), value(2).pause_n(2), value(3).pause_n(0)];
+    let runs  = sequence(steps).then(instant_index()).take(2);
+    assert_eq!(execute_process(runs), vec![2, 4]);
+  }
+
+  #[test]
+  fn join_all_in_loop () {
+    let iterations = Recorder::new();
+    let mut record_iteration = iterations.recording();
+    let mut runs = 0;
+
+    let record = move |values: Vec<usize>| {
+      record_iteration(values);
+      runs += 1;
+      if runs == 3 { LoopStatus::Exit(()) } else { LoopStatus::Continue }
+    };
+
+    // Each iteration samples the instant index twice, and lasts an instant
+    let processes = vec![instant_index().pause(), instant_index().pause()];
+    execute_process(join_all(processes).map(record).while_loop());
+
+    assert_eq!(iterations.values(), vec![vec![0, 0], vec![1, 1], vec![2, 2]]);
+  }
+
+  #[test]
+  fn count_using_while () {
+    let counter = ProcessLocal::new(|| 0);
+    let total   = ProcessLocal::new(|| 0);
+    let total_1 = total.clone();
+
+    let sum = |counter: &mut u32| { *counter += 1; *counter };
+    let test_loop_end = |counter: u32| {
+      match counter {
+        42 => LoopStatus::Exit(42),
         _  => LoopStatus::Continue
       }
     };
 
-    let sum = value(()).map(sum).map(test_loop_end).while_loop();
+    let sum = counter.owned_by(counter.access(sum).map(test_loop_end).while_loop());
+
+    let mut runtime = Runtime::new();
+    sum.call(&mut runtime, move |r: &mut Runtime, v: u32| total_1.set(r, v));
+    runtime.execute();
+
+    // The counter is freed once the loop has completed, only the total remains
+    assert_eq!(42, total.get_clone(&mut runtime));
+    assert_eq!(runtime.live_locals(), 1);
+  }
+
+  /// Returns a process counting the instants in a process-local variable, and never completing.
+  fn owned_counter(counter: ProcessLocal<u32>) -> impl Process<Value = ()> {
+    let increment = |counter: &mut u32| { *counter += 1; LoopStatus::Continue };
+    counter.owned_by(counter.access(increment).pause().while_loop())
+  }
+
+  #[test]
+  fn preempted_owners_free_their_locals () {
+    let counter = ProcessLocal::new(|| 0);
+    let signal   = PureSignal::new();
+    let signal_1 = signal.clone();
+    let emitter  = value(()).pause().pause().and_then(move |_| signal_1.emit());
+
+    let mut runtime = Runtime::new();
+    let process     = owned_counter(counter.clone()).until(signal.clone()).join(emitter.clone());
+    process.call(&mut runtime, |_: &mut Runtime, _| ());
+    runtime.execute();
+    assert_eq!(runtime.live_locals(), 0);
+
+    let mut runtime = Runtime::new();
+    let process     = owned_counter(counter.clone()).do_until(signal.clone()).join(emitter);
+    process.call(&mut runtime, |_: &mut Runtime, _| ());
+    runtime.execute();
+    assert_eq!(runtime.live_locals(), 0);
+
+    let mut runtime = Runtime::new();
+    owned_counter(counter.clone()).timeout(3).call(&mut runtime, |_: &mut Runtime, _| ());
+    runtime.execute();
+    assert_eq!(runtime.live_locals(), 0);
+  }
+
+  #[test]
+  fn while_loop_max_stops_infinite_loops () {
+    let never_exit = |_| -> LoopStatus<()> { LoopStatus::Continue };
+    let error      = Err(LoopLimitExceeded { max_iterations: 1000 });
+
+    // Iterations are counted within a single instant as well as across instants
+    assert_eq!(execute_process(value(()).map(never_exit).while_loop_max(1000)), error);
+    let process = value(()).pause().map(never_exit).while_loop_max(1000).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (error, 1000));
+
+    let mut counter = 0;
+    let count = move |_| { counter += 1; if counter == 10 { LoopStatus::Exit(counter) } else { LoopStatus::Continue } };
+    assert_eq!(execute_process(value(()).map(count).while_loop_max(10)), Ok(10));
+  }
+
+  #[test]
+  fn count_using_while_fold () {
+    let count = |counter: u32, _| match counter + 1 {
+      42      => LoopStatusWith::ExitWith(42),
+      counter => LoopStatusWith::ContinueWith(counter)
+    };
+
+    assert_eq!(execute_process(value(()).while_fold(0, count)), 42);
+
+    // The state is threaded across instants, the loop exits once the index of instant 5 is read
+    let count_instants = |counter: usize, i: usize| match i {
+      5 => LoopStatusWith::ExitWith(counter),
+      _ => LoopStatusWith::ContinueWith(counter + 1)
+    };
+
+    assert_eq!(execute_process(instant_index().pause().while_fold(0, count_instants)), 5);
+  }
+
+  #[test]
+  fn count_using_while_some () {
+    let counter       = Counter::new();
+    let mut increment = counter.incrementing();
+
+    let count = move |_| {
+      let count = increment();
+      if count < 42 { Some(count) } else { None }
+    };
+
+    execute_process(value(()).map(count).while_some());
+    assert_eq!(counter.get(), 42);
+  }
+
+  #[test]
+  fn while_ok_returns_first_error () {
+    let counter       = Counter::new();
+    let mut increment = counter.incrementing();
+
+    let check = move |_| {
+      let count = increment();
+      if !count.is_multiple_of(5) { Ok(count) } else { Err(format!("{} is a multiple of 5", count)) }
+    };
+
+    let error = execute_process(instant_index().pause().map(check).while_ok());
+    assert_eq!(error, "5 is a multiple of 5");
+    assert_eq!(counter.get(), 5);
+  }
+
+  #[test]
+  fn and_then_ok_chains_fallible_steps () {
+    let parse  = |s: &'static str| value(s.parse::<u32>().map_err(|_| "not a number")).pause();
+    let halve  = |n: u32| value(if n.is_multiple_of(2) { Ok(n / 2) } else { Err("odd number") });
+    let double = |n: u32| n * 2;
+
+    // Both steps succeed, the second one running after a pause
+    let process = parse("42").and_then_ok(halve).map_ok(double).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (Ok(42), 1));
+
+    // The first step fails, the second one is never built
+    let builds    = Counter::new();
+    let mut build = builds.incrementing();
+    let halve_checked = move |n: u32| { build(); halve(n) };
+
+    let process = parse("forty-two").and_then_ok(halve_checked).map_ok(double).map_err(|e| e.len());
+    assert_eq!(execute_process(process), Err(12));
+    assert_eq!(builds.get(), 0);
+  }
+
+  #[test]
+  fn and_then_ok_in_loop () {
+    let check = |i: usize| value(if i < 3 { Ok(i) } else { Err(i) });
+    let scale = |i: usize| i * 10;
+    let runs  = instant_index().map(Ok).and_then_ok(check).map_ok(scale).pause().take(5);
+    assert_eq!(execute_process(runs), vec![Ok(0), Ok(10), Ok(20), Err(3), Err(4)]);
+  }
+
+  #[test]
+  fn recover_using_paused_fallback () {
+    let fallback = |error: String| value(Ok(error.len())).pause().pause();
+    let stage    = value(Err::<usize, String>(String::from("boom"))).or_else(fallback);
+
+    let result = execute_process(stage.and_then(|v| instant_index().map(move |i| (v, i))));
+    assert_eq!(result, (Ok::<usize, ()>(4), 2));
+  }
+
+  #[test]
+  fn fallback_never_built_on_success () {
+    let fallback_calls    = Counter::new();
+    let mut call_fallback = fallback_calls.incrementing();
+
+    let fallback = move |_: String| {
+      call_fallback();
+      value(Err::<usize, String>(String::from("fallback")))
+    };
+
+    let stage = value(Ok::<usize, String>(42)).or_else(fallback);
+    assert_eq!(execute_process(stage), Ok(42));
+    assert_eq!(fallback_calls.get(), 0);
+  }
+
+  #[test]
+  fn recover_odd_instants () {
+    let fallbacks         = Counter::new();
+    let mut call_fallback = fallbacks.incrementing();
+
+    let check    = |i: usize| if i.is_multiple_of(2) { Ok(i) } else { Err(i) };
+    let fallback = move |i: usize| {
+      call_fallback();
+      value(Ok::<usize, ()>(100 + i))
+    };
+
+    let runs = instant_index().map(check).or_else(fallback).pause().take(4);
+    assert_eq!(execute_process(runs), vec![Ok(0), Ok(101), Ok(2), Ok(103)]);
+    assert_eq!(fallbacks.get(), 2);
+
+    let errors = value(Err::<(), usize>(3));
+    assert_eq!(execute_process(errors.map_err(|e| e * 2)), Err(6));
+    assert_eq!(execute_process(value(Err::<usize, ()>(())).unwrap_or(7)), 7);
+  }
+
+
+  // Build a generator body yielding the values from 1 to `last`, logging each of them
+  fn count_up_to(yielder: Yielder<u32>, last: u32, log: Recorder<String>) -> impl Process<Value = u32> {
+    let counter       = Counter::new();
+    let mut increment = counter.incrementing();
+
+    let yield_next = move |_| {
+      let count = increment() as u32;
+      log.record(format!("yield {}", count));
+      yielder.yield_value(count)
+    };
+    let test_loop_end = move |_| {
+      match counter.get() as u32 {
+        c if c == last => LoopStatus::Exit(c),
+        _              => LoopStatus::Continue
+      }
+    };
+
+    value(()).and_then(yield_next).map(test_loop_end).while_loop()
+  }
+
+  #[test]
+  fn generator_sum () {
+    let values     = Recorder::new();
+    let mut record = values.recording();
+
+    let process = generator(|yielder| count_up_to(yielder, 5, Recorder::new()))
+      .for_each_value(move |v| {
+        record(v);
+        value(())
+      });
+
+    assert_eq!(execute_process(process), 5);
+    assert_eq!(values.values().iter().sum::<u32>(), 15);
+  }
+
+  #[test]
+  fn generator_backpressure () {
+    let log     = Recorder::new();
+    let yielded = log.clone();
+    let handled = log.clone();
+
+    // Each value takes two instants to be handled
+    let process = generator(|yielder| count_up_to(yielder, 3, yielded))
+      .for_each_value(move |v| {
+        let mut record = handled.recording();
+        value(()).pause().pause().map(move |_| record(format!("handle {}", v)))
+      });
+
+    execute_process(process);
+    assert_eq!(log.values(), vec!["yield 1", "handle 1", "yield 2", "handle 2", "yield 3", "handle 3"]);
+  }
+
+
+  /// Runs `process` under a `while_loop` until it has ran `runs` times,
+  /// and returns the values of all the runs.
+  fn collect_interleaved<P>(process: P, runs: usize) -> Vec<Either<usize, usize>>
+  where
+    P: ProcessMut<Value = Either<usize, usize>> + 'static
+  {
+    let values     = Recorder::new();
+    let mut record = values.recording();
+    let mut count  = 0;
+
+    let record_value = move |v| {
+      record(v);
+      count += 1;
+      if count == runs { LoopStatus::Exit(()) } else { LoopStatus::Continue }
+    };
+
+    execute_process(process.map(record_value).while_loop());
+    values.values()
+  }
+
+  #[test]
+  fn interleave_alternates () {
+    let values = collect_interleaved(instant_index().interleave(instant_index()), 6);
+
+    assert_eq!(values, vec![
+      Either::Left(0), Either::Right(1), Either::Left(2),
+      Either::Right(3), Either::Left(4), Either::Right(5)
+    ]);
+  }
+
+  #[test]
+  fn interleave_with_pause () {
+    let probe     = |_| instant_index();
+    let process_1 = value(()).pause().and_then(probe);
+    let values    = collect_interleaved(process_1.interleave(instant_index()), 6);
+
+    assert_eq!(values, vec![
+      Either::Left(1), Either::Right(2), Either::Left(4),
+      Either::Right(5), Either::Left(7), Either::Right(8)
+    ]);
+  }
+
+
+  /// Returns a process waiting for `instants` instants (at least one).
+  fn wait_instants(instants: usize) -> impl Process<Value = ()> {
+    let remaining = Rc::new(Cell::new(instants));
+    let count_down = move |_| {
+      remaining.set(remaining.get().saturating_sub(1));
+      match remaining.get() {
+        0 => LoopStatus::Exit(()),
+        _ => LoopStatus::Continue
+      }
+    };
+
+    value(()).pause().map(count_down).while_loop()
+  }
+
+  #[test]
+  fn for_each_one_item_per_instant () {
+    let signal    = ValueSignal::new();
+    let recording = signal.record();
+
+    let signal_1 = signal.clone();
+    execute_process(for_each(vec![1, 2, 3], move |i| signal_1.clone().emit_value(i)));
+
+    assert_eq!(recording.into_vec(), vec![(0, Some(vec![1])), (1, Some(vec![2])), (2, Some(vec![3]))]);
+  }
+
+  #[test]
+  fn for_each_immediate_in_one_instant () {
+    let signal    = ValueSignal::new();
+    let recording = signal.record();
+
+    let signal_1 = signal.clone();
+    execute_process(for_each_immediate(1..4, move |i| signal_1.clone().emit_value(i)));
+
+    assert_eq!(recording.into_vec(), vec![(0, Some(vec![1, 2, 3]))]);
+
+    // Items are ran from new tasks, instead of nesting calls
+    execute_process(for_each_immediate(0..100_000, value));
+  }
+
+  #[test]
+  fn for_each_concurrent_in_order () {
+    let items: Vec<usize> = (0..10).collect();
+
+    let process = for_each_concurrent(items, |i| {
+      wait_instants((10 - i) % 4 + 1).map(move |_| i * i)
+    });
+
+    assert_eq!(execute_process(process), vec![0, 1, 4, 9, 16, 25, 36, 49, 64, 81]);
+  }
+
+  #[test]
+  fn for_each_concurrent_max_in_flight () {
+    // Each process records +1 when it starts, and -1 when it ends
+    let deltas           = Recorder::new();
+    let mut record_start = deltas.recording();
+    let record_end       = deltas.recording();
+
+    let process = for_each_concurrent(vec![3, 1, 2, 1, 2], move |depth| {
+      let mut record_end = record_end.clone();
+
+      record_start(1);
+      wait_instants(depth).map(move |_| { record_end(-1); depth })
+    }).max_in_flight(2);
+
+    assert_eq!(execute_process(process), vec![3, 1, 2, 1, 2]);
+
+    let max_active = deltas.values().iter().scan(0, |active, delta| { *active += delta; Some(*active) }).max();
+    assert_eq!(max_active, Some(2));
+  }
+
+
+  #[test]
+  fn take_awaited_values () {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: usize, v: &mut usize| { *v = e; }));
+
+    // The signal is emitted during instants 0 to 4, but only the first 3 emissions are awaited
+    let signal_1 = signal.clone();
+    let emitter  = for_each(0..5, move |i| signal_1.clone().emit_value(i));
+    let awaiter  = signal.clone().await().take(3);
+
+    assert_eq!(execute_process(emitter.join(awaiter)).1, vec![0, 1, 2]);
+
+    // Taking no value does not run the process at all
+    let runs    = Counter::new();
+    let mut run = runs.incrementing();
+    let count_run = move |_| { run(); };
+
+    assert_eq!(execute_process(value(()).map(count_run).take(0)), vec![]);
+    assert_eq!(runs.get(), 0);
+  }
+
+  #[test]
+  fn take_awaited_values_once () {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: usize, v: &mut usize| { *v = e; }));
+
+    let wakes    = Counter::new();
+    let mut wake = wakes.incrementing();
+
+    let count_wake = move |v| { wake(); v };
+    let first_values = signal.clone().await().map(count_wake).take(3)
+      .and_then(|values| instant_index().map(move |i| (i, values)));
+
+    // The signal is emitted during instants 0 to 5
+    let emit_index = move |i| signal.clone().emit_value(i).join(value(i));
+    let test_loop_end = |(_, i)| {
+      match i {
+        5 => LoopStatus::Exit(()),
+        _ => LoopStatus::Continue
+      }
+    };
+    let emit_loop = instant_index().and_then(emit_index).pause().map(test_loop_end).while_loop();
+
+    let (first_values, _) = execute_process(first_values.join(emit_loop));
+    assert_eq!(first_values, (3, vec![0, 1, 2]));
+    assert_eq!(wakes.get(), 3);
+  }
+
+
+  #[test]
+  fn skip_first_values () {
+    let counter       = Counter::new();
+    let mut increment = counter.incrementing();
+
+    let count = move |_| increment();
+    let process = value(()).pause().map(count).skip(2).take(3);
+
+    assert_eq!(execute_process(process), vec![3, 4, 5]);
+    assert_eq!(counter.get(), 5);
+
+    // Skipped runs which do not pause are scheduled through the runtime, instead of nesting calls
+    assert_eq!(execute_process(value(1).skip(100_000)), 1);
+  }
+
+
+  #[test]
+  fn one_instant_delays_instantaneous_process () {
+    let process = instant_index().one_instant().and_then(|start| instant_index().map(move |end| (start, end)));
+    assert_eq!(execute_process(process), (0, 1));
+
+    let process = instant_index().pause().one_instant().and_then(|start| instant_index().map(move |end| (start, end)));
+    assert_eq!(execute_process(process), (0, 1));
+  }
+
+  #[test]
+  #[should_panic(expected = "process overran its instant")]
+  fn one_instant_reports_overrun () {
+    execute_process(value(()).pause().pause().one_instant());
+  }
+
+  #[test]
+  fn one_instant_clamps_overrun () {
+    let process = value(()).pause().pause().one_instant().on_overrun(OverrunPolicy::Clamp);
+    assert_eq!(execute_process(process.and_then(|_| instant_index())), 2);
+  }
+
+
+  #[test]
+  fn at_instant_scripted_starts () {
+    let late_start = |_| at_instant(1, instant_index());
+    let process = at_instant(5, instant_index())
+      .join(at_instant(12, instant_index()))
+      .join(value(()).pause().pause().pause().and_then(late_start));
+
+    assert_eq!(execute_process(process), ((5, 12), 3));
+  }
+
+
+  #[test]
+  fn values_iter_counter () {
+    let mut counter = 0;
+    let count = move |_| { counter += 1; counter };
+
+    let mut values = value(()).pause().map(count).into_values(Runtime::new());
+
+    for i in 1..6 {
+      assert_eq!(values.next(), Some(i));
+      assert_eq!(values.runtime().instant_index(), 2 * i);
+    }
+  }
+
+  #[test]
+  fn values_iter_drained_runtime () {
+    let signal = PureSignal::new();
+    let mut values = signal.await().into_values(Runtime::new());
+
+    assert_eq!(values.next(), None);
+    assert_eq!(values.next(), None);
+  }
+
+
+  #[test]
+  fn count_instants_of_processes () {
+    assert_eq!(execute_process(value(1).count_instants()), (1, 0));
+    assert_eq!(execute_process(value(1).pause().pause().count_instants()), (1, 2));
+
+    // Instants spent waiting for a signal are counted as well: the signal is emitted during
+    // instant 4, and awaited until instant 5
+    let signal  = PureSignal::new();
+    let process = signal.clone().await().count_instants();
+    assert_eq!(execute_process(process.join(at_instant(4, signal.emit()))).0, ((), 5));
+
+    let instants  = |(_, instants): ((), u64)| instants;
+    let iteration = value(()).pause().count_instants().map(instants);
+    assert_eq!(execute_process(iteration.take(3)), vec![1, 1, 1]);
+  }
+
+  #[test]
+  fn trace_timing_pipeline () {
+    let profile_1 = TimingProfile::new();
+    let profile_2 = profile_1.clone();
+    let profile_3 = profile_1.clone();
+    let profile_4 = profile_1.clone();
+
+    let counter = Rc::new(Cell::new(0));
+
+    let stage_b = move |_| value(()).pause().pause().trace_timing(profile_2.clone(), "b");
+    let stage_c = move |_| instant_index().trace_timing(profile_3.clone(), "c");
+    let test_loop_end = move |_| {
+      counter.set(counter.get() + 1);
+      match counter.get() {
+        3 => LoopStatus::Exit(()),
+        _ => LoopStatus::Continue
+      }
+    };
+
+    let pipeline = value(()).pause().trace_timing(profile_1, "a")
+      .and_then(stage_b)
+      .and_then(stage_c)
+      .map(test_loop_end)
+      .while_loop();
+
+    execute_process(pipeline);
+
+    let report = profile_4.report();
+    assert_eq!(report["a"], TimingStats { runs: 3, min: 1, max: 1, mean: 1.0 });
+    assert_eq!(report["b"], TimingStats { runs: 3, min: 2, max: 2, mean: 2.0 });
+    assert_eq!(report["c"], TimingStats { runs: 3, min: 0, max: 0, mean: 0.0 });
+    assert_eq!(&profile_4.records()[..3], &[("a", 0, 1), ("b", 1, 3), ("c", 3, 3)]);
+  }
+
+
+  #[test]
+  fn execute_interactive_host_inputs () {
+    let input_1 = PureSignal::new();
+    let input_2 = input_1.clone();
+
+    let inputs     = Recorder::new();
+    let mut record = inputs.recording();
+
+    let record_input = move |i| { record(i); LoopStatus::Continue::<()> };
+    let probe = |_| instant_index();
+    let react_to_inputs = input_1.await_immediate()
+      .and_then(probe)
+      .map(record_input)
+      .pause()
+      .while_loop();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| react_to_inputs.call(r, |_: &mut Runtime, _: ()| {}));
+
+    let instants           = Recorder::new();
+    let mut record_instant = instants.recording();
+
+    runtime.execute_interactive(move |r: &mut Runtime, summary: InstantSummary| {
+      record_instant(summary.instant_index);
+
+      match summary.instant_index {
+        2 | 5 => { input_2.clone().emit_from_host(r, ()); ControlFlow::Continue(()) },
+        10    => ControlFlow::Break(()),
+        _     => ControlFlow::Continue(())
+      }
+    });
+
+    assert_eq!(inputs.values(), vec![3, 6]);
+    assert_eq!(instants.values().last(), Some(&10));
+  }
+
+
+  // A process poisoning the runtime it runs in.
+  struct PoisonProcess;
+
+  impl Process for PoisonProcess {
+    type Value = ();
+
+    fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+      runtime.poison();
+      next.call(runtime, ());
+    }
+  }
+
+  #[test]
+  fn try_execute_poisoning_process () {
+    assert_eq!(try_execute_process(value(21).map(|v| v * 2)), Ok(42));
+    assert_eq!(try_execute_process(value(()).pause().and_then(|_| PoisonProcess)), Err(RuntimeError::Poisoned));
+  }
+
+
+  // A process stopping the runtime it runs in.
+  struct StopProcess;
+
+  impl Process for StopProcess {
+    type Value = ();
+
+    fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+      runtime.stop();
+      next.call(runtime, ());
+    }
+  }
+
+  #[test]
+  fn stop_gracefully () {
+    let mut runtime = Runtime::new();
+
+    let emissions      = Counter::new();
+    let mut count_emit = emissions.incrementing();
+    let cleanups       = Counter::new();
+    let mut clean_up   = cleanups.incrementing();
+    let results        = Recorder::new();
+    let mut record     = results.recording();
+
+    let signal = PureSignal::new();
+
+    // An infinite emitter
+    let emit = move |_: ()| {
+      count_emit();
+      signal.clone().emit().pause()
+    };
+    let continue_loop = |_: ()| LoopStatus::Continue::<()>;
+    let emitter = value(()).and_then(emit).map(continue_loop).while_loop();
+
+    // A resource which is only released when the runtime shuts down
+    let resource = value(())
+      .pause()
+      .map(continue_loop)
+      .while_loop()
+      .until(runtime.shutdown_signal())
+      .finally(move |_| { clean_up(); })
+      .map(move |v| record(v.is_none()));
+
+    let stopper = value(()).pause().pause().pause().and_then(|_| StopProcess);
+
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      emitter.join(resource).join(stopper).call(r, |_: &mut Runtime, _| ());
+    });
+    runtime.execute();
+
+    assert_eq!(cleanups.get(), 1);
+    assert_eq!(results.values(), vec![true]);
+    assert_eq!(emissions.get(), 5);
+    assert_eq!(runtime.try_execute(), Err(RuntimeError::Stopped));
+  }
+
+  #[test]
+  fn until_in_loop_drops_its_waiters () {
+    let mut runtime = Runtime::new();
+
+    let waiters    = Recorder::new();
+    let record     = waiters.recording();
+    let iterations = Counter::new();
+    let count      = iterations.incrementing();
+
+    let signal   = PureSignal::new();
+    let signal_1 = signal.clone();
+    let iteration = move |_: ()| {
+      let signal_2   = signal_1.clone();
+      let mut record = record.clone();
+      let mut count  = count.clone();
+      value(()).pause().until(signal_1.clone()).map(move |_| {
+        record(signal_2.clone().runtime().awaiting_presence());
+        if count() < 100 { LoopStatus::Continue } else { LoopStatus::Exit(()) }
+      })
+    };
+
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      value(()).and_then(iteration).while_loop().call(r, |_: &mut Runtime, _| ());
+    });
+    runtime.execute();
+
+    assert_eq!(iterations.get(), 100);
+    assert!(waiters.values().iter().all(|&n| n <= 1));
+    assert!(signal.runtime().awaiting_presence() <= 1);
+  }
+
+  /// Minimal runtime, counting the tasks registered to it.
+  #[derive(Default)]
+  struct CountingRuntime {
+    current_instant_tasks: VecDeque<Box<dyn Continuation<(), CountingRuntime>>>,
+    next_instant_tasks   : VecDeque<Box<dyn Continuation<(), CountingRuntime>>>,
+    end_of_instant_tasks : VecDeque<Box<dyn Continuation<(), CountingRuntime>>>,
+
+    instant_index  : usize,
+    tasks_scheduled: usize
+  }
+
+  impl CountingRuntime {
+    fn execute(&mut self) {
+      loop {
+        while let Some(task) = self.current_instant_tasks.pop_front() {
+          task.call_box(self, ());
+        }
+        while let Some(task) = self.end_of_instant_tasks.pop_front() {
+          task.call_box(self, ());
+        }
+
+        if self.next_instant_tasks.is_empty() {
+          return;
+        }
+
+        self.current_instant_tasks = ::std::mem::take(&mut self.next_instant_tasks);
+        self.instant_index += 1;
+      }
+    }
+  }
+
+  impl ReactiveRuntime for CountingRuntime {
+    fn on_current_instant(&mut self, c: Box<dyn Continuation<(), Self>>) {
+      self.tasks_scheduled += 1;
+      self.current_instant_tasks.push_back(c);
+    }
+
+    fn on_next_instant(&mut self, c: Box<dyn Continuation<(), Self>>) {
+      self.tasks_scheduled += 1;
+      self.next_instant_tasks.push_back(c);
+    }
+
+    fn on_end_of_instant(&mut self, c: Box<dyn Continuation<(), Self>>) {
+      self.tasks_scheduled += 1;
+      self.end_of_instant_tasks.push_back(c);
+    }
+
+    fn instant_index(&self) -> usize {
+      self.instant_index
+    }
+  }
+
+  /// Process returning the index of the instant it runs during, on a `CountingRuntime`.
+  struct CountedInstantIndex;
+
+  impl Process<CountingRuntime> for CountedInstantIndex {
+    type Value = usize;
+
+    fn call<C>(self, runtime: &mut CountingRuntime, next: C) where C: Continuation<usize, CountingRuntime> {
+      let instant_index = runtime.instant_index();
+      next.call(runtime, instant_index);
+    }
+  }
+
+  impl ProcessMut<CountingRuntime> for CountedInstantIndex {
+    fn call_mut<C>(self, runtime: &mut CountingRuntime, next: C)
+    where
+      C: Continuation<(Self, usize), CountingRuntime>
+    {
+      let instant_index = runtime.instant_index();
+      next.call(runtime, (self, instant_index));
+    }
+  }
+
+  #[test]
+  fn combinators_on_alternative_runtime () {
+    let cleanups           = Recorder::new();
+    let mut record_cleanup = cleanups.recording();
+    let results            = Recorder::new();
+    let mut record_result  = results.recording();
+
+    let loop_status = |i: usize| if i < 3 { LoopStatus::Continue } else { LoopStatus::Exit(i) };
+    let counter     = CountedInstantIndex.pause().map(loop_status).while_loop();
+
+    let process = CountedInstantIndex.pause().map(|i| i * 10)
+      .join(counter)
+      .and_then(|(a, b)| CountedInstantIndex.map(move |c| (a, b, c)))
+      .finally(move |r: &mut CountingRuntime| record_cleanup(r.instant_index()));
+
+    let mut runtime = CountingRuntime::default();
+    process.call(&mut runtime, move |_: &mut CountingRuntime, v: (usize, usize, usize)| record_result(v));
+    runtime.execute();
+
+    // Paused processes run before the pause, the counter loops during instants 0 to 3
+    assert_eq!(results.values(), vec![(0, 3, 4)]);
+    assert_eq!(cleanups.values(), vec![4]);
+    assert_eq!(runtime.tasks_scheduled, 5);
+  }
+
+  #[test]
+  fn step_state_machine () {
+    let signal   = PureSignal::new();
+    let signal_1 = signal.clone();
+
+    // The machine records the instants of its steps, and chooses the instant of the next one
+    let step = move |mut steps: Vec<usize>, ctx: &mut StepCtx| {
+      steps.push(ctx.instant_index());
+      match steps.len() {
+        1 => ctx.pause_for(3),
+        2 => ctx.wake_after(&signal_1),
+        3 => {},
+        _ => return Transition::Stop(steps)
+      }
+
+      Transition::Next(steps)
+    };
+
+    let machine = state_machine(Vec::new(), step);
+    let emitter = at_instant(5, signal.emit());
+
+    assert_eq!(execute_process(machine.join(emitter)).0, vec![0, 3, 6, 7]);
+  }
+
+  #[test]
+  fn when_counts_present_instants () {
+    let clock   = PureSignal::new();
+    let clock_1 = clock.clone();
+
+    // The clock ticks during instants 0, 2, 3 and 6
+    let ticks   = for_each_concurrent(vec![0, 2, 3, 6], move |i| at_instant(i, clock_1.clone().emit()));
+    let counter = instant_index().pause().take(3).when(clock).and_then(|v| instant_index().map(move |i| (v, i)));
+
+    // The last pause of the counter only completes during the next tick
+    assert_eq!(execute_process(counter.join(ticks)).0, (vec![0, 2, 3], 6));
+  }
+
+  #[test]
+  fn when_never_present () {
+    let clock = PureSignal::new();
+
+    // The process is suspended forever, but does not keep the runtime running
+    let mut runtime = Runtime::new();
+    value(()).when(clock).call(&mut runtime, |_: &mut Runtime, ()| panic!("The process has ran"));
+    assert!(!runtime.instant());
+  }
+
+  #[test]
+  fn control_suspends_and_resumes () {
+    let suspend = PureSignal::new();
+    let resume  = PureSignal::new();
+
+    // The counter is suspended during instants 2 and 3
+    let counter  = instant_index().pause().take(6).control(suspend.clone(), resume.clone());
+    let controls = at_instant(1, suspend.emit()).join(at_instant(3, resume.emit()));
+    assert_eq!(execute_process(counter.join(controls)).0, vec![0, 1, 4, 5, 6, 7]);
+  }
+
+  #[test]
+  fn control_suspend_and_resume_together () {
+    let suspend = PureSignal::new();
+    let resume  = PureSignal::new();
+
+    let counter  = instant_index().pause().take(4).control(suspend.clone(), resume.clone());
+    let controls = at_instant(1, suspend.emit()).join(at_instant(1, resume.emit()));
+    assert_eq!(execute_process(counter.join(controls)).0, vec![0, 1, 2, 3]);
+  }
+
+  #[test]
+  fn timeout_expires () {
+    let signal = PureSignal::new();
+
+    // The signal is never emitted, the timeout expires at the end of instant 3
+    let process = signal.await_immediate().timeout(3).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (None, 3));
+  }
+
+  #[test]
+  fn timeout_process_completes_first () {
+    assert_eq!(execute_process(value(42).pause().timeout(3)), Some(42));
+
+    // A process completing during the instant the timeout expires wins
+    assert_eq!(execute_process(value(42).pause_n(3).timeout(3)), Some(42));
+    assert_eq!(execute_process(value(42).pause_n(4).timeout(3)), None);
+
+    // Once the process has completed, the timer does not keep the runtime running
+    assert_eq!(execute_process(value(()).pause().timeout(100).then(instant_index())), 1);
+  }
+
+  #[test]
+  fn catch_panic_after_pause () {
+    let explode = |_: ()| -> u32 { panic!("explosion") };
+
+    // The sibling of the panicking process keeps running
+    let process = value(()).pause().map(explode).catch_panic();
+    let sibling = instant_index().pause().take(3);
+
+    let (result, instants) = execute_process(process.join(sibling));
+    assert_eq!(result.unwrap_err().downcast_ref::<&str>(), Some(&"explosion"));
+    assert_eq!(instants, vec![0, 1, 2]);
+  }
+
+  #[test]
+  fn catch_panic_returns_value () {
+    assert_eq!(execute_process(value(42).pause().catch_panic()).ok(), Some(42));
+  }
+
+  #[test]
+  fn shared_value_awaited_by_consumers () {
+    let (producer, handle) = value(vec![4, 2]).pause().pause().shared();
+
+    // Consumers started before the value is produced complete during the same instant
+    let consume = |handle: &SharedHandle<Vec<u32>>| handle.await_result().and_then(|v| instant_index().map(move |i| (v, i)));
+    let consumers = consume(&handle).join3(consume(&handle), consume(&handle));
+
+    // A consumer started once the value is produced completes immediately
+    let late_consumer = at_instant(4, consume(&handle));
+
+    let ((produced, consumed), late) = execute_process(producer.join(consumers).join(late_consumer));
+    assert_eq!(produced, vec![4, 2]);
+    assert_eq!(consumed, ((vec![4, 2], 2), (vec![4, 2], 2), (vec![4, 2], 2)));
+    assert_eq!(late, (vec![4, 2], 4));
+  }
+
+  #[test]
+  fn spawn_background_worker () {
+    let worker = value(42).pause_n(3).spawn_background();
+
+    // Other work is done while the worker runs, then the worker is awaited
+    let process = worker.and_then(|handle| {
+      value(()).pause().map(move |_| handle)
+        .and_then(|handle| { assert!(!handle.is_done()); handle.await_done() })
+        .and_then(|v| instant_index().map(move |i| (v, i)))
+    });
+
+    assert_eq!(execute_process(process), (42, 3));
+  }
+
+  #[test]
+  fn spawned_process_outlives_handle () {
+    let completions  = Counter::new();
+    let mut complete = completions.incrementing();
+
+    let mut runtime = Runtime::new();
+    drop(spawn(&mut runtime, value(()).pause().map(move |_| { complete(); })));
+    runtime.execute();
+
+    assert_eq!(completions.get(), 1);
+  }
+
+  #[test]
+  fn scope_waits_for_slowest_child () {
+    let completions = Recorder::new();
+    let record      = completions.recording();
+
+    // Each child completes during the given instant, and records it
+    let child = move |instant: usize| at_instant(instant, instant_index().map(record.clone()));
+
+    let body = move |scope: ScopeHandle| {
+      scope.spawn(child(2)).join(scope.spawn(child(5))).join(scope.spawn(child(3))).map(|_| 42)
+    };
+
+    let process = scope(body).and_then(|v| instant_index().map(move |i| (v, i)));
+    assert_eq!(execute_process(process), (42, 5));
+    assert_eq!(completions.values(), vec![2, 3, 5]);
+  }
+
+  #[test]
+  fn preempted_scope_stops_children () {
+    let stop      = PureSignal::new();
+    let effects    = Recorder::new();
+    let mut effect = effects.recording();
+
+    // The child records each instant it runs during, and never completes
+    let record = move |i: usize| { effect(i); LoopStatus::Continue::<()> };
+    let child  = instant_index().map(record).pause().while_loop();
+
+    let scoped  = scope(move |scope: ScopeHandle| scope.spawn(child)).until(stop.clone());
+    let process = scoped.join(at_instant(3, stop.emit())).join(at_instant(10, instant_index()));
 
-    execute_process(sum);
-    assert_eq!(42, *counter_3.borrow());
+    let ((preempted, _), last_instant) = execute_process(process);
+    assert_eq!(preempted, None);
+    assert_eq!(last_instant, 10);
+    assert_eq!(effects.values(), vec![0, 1, 2, 3]);
   }
 }
@@ -1,5 +1,126 @@
-use continuations::Continuation;
-use signals::runtime::SignalRuntimeRef;
+use std::fmt;
+use std::rc::Rc;
+use std::cell::RefCell;
+use std::ops::ControlFlow;
+use std::panic::{self, AssertUnwindSafe};
+use std::any::{Any, TypeId};
+use std::collections::{BTreeMap, HashMap, VecDeque};
+use std::time::Duration;
+
+use continuations::{Continuation, CancelHandle};
+use clock::{Clock, SystemClock};
+use signals::signals::{SignalHandle, SignalRuntimeOf, EmittableSignal};
+use signals::pure_signal::PureSignal;
+
+
+/// A queue of tasks, executed in registration order.
+type TaskQueue = VecDeque<Box<dyn Continuation<()>>>;
+
+/// A hook called at the end of each instant (see `Runtime::on_end_of_each_instant`).
+pub type InstantHook = Box<dyn FnMut(&mut Runtime) -> bool>;
+
+/// A function describing the state of a part of the program which may block the runtime
+/// (e.g. a signal with awaiting continuations), or returning `None` if there is nothing to report.
+pub type StateReporter = Box<dyn Fn() -> Option<String>>;
+
+/// A cleanup action called when a runtime is torn down (see `Runtime::register_guard`).
+pub type Guard = Box<dyn FnOnce(&mut Runtime)>;
+
+/// Phase of the execution of a runtime (see `Runtime::phase`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Phase {
+  /// The runtime is running the tasks of current instant.
+  CurrentInstant,
+  /// The runtime is running the end-of-instant tasks or the hooks of current instant.
+  EndOfInstant,
+  /// The runtime is not running any instant (e.g. from the callback of `execute_interactive`).
+  BetweenInstants
+}
+
+/// Error returned when a runtime cannot accept or execute tasks.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RuntimeError {
+  /// The runtime has been poisoned (see `Runtime::poison`).
+  Poisoned,
+  /// The runtime has been stopped.
+  Stopped,
+  /// The runtime has accepted as many tasks as its budget allows (see `Runtime::set_task_budget`).
+  BudgetExceeded,
+  /// The pool of tasks is full (see `Runtime::set_queue_capacity`).
+  QueueFull
+}
+
+
+impl fmt::Display for RuntimeError {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    match *self {
+      RuntimeError::Poisoned       => write!(f, "the runtime is poisoned"),
+      RuntimeError::Stopped        => write!(f, "the runtime is stopped"),
+      RuntimeError::BudgetExceeded => write!(f, "the task budget of the runtime is exceeded"),
+      RuntimeError::QueueFull      => write!(f, "the task pool of the runtime is full")
+    }
+  }
+}
+
+/// Counters about the work done by a runtime (see `Runtime::stats`).
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+pub struct RuntimeStats {
+  /// Number of continuations registered as tasks (individually or in batches).
+  pub tasks_scheduled: usize,
+  /// Number of batches of tasks registered at once.
+  pub batches_scheduled: usize,
+  /// Number of tasks executed.
+  pub tasks_executed: usize,
+  /// Number of instants actually executed (skipped instants are not counted, see `set_fast_forward`).
+  pub instants_executed: usize
+}
+
+/// Summary of an executed instant (see `Runtime::execute_interactive`).
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct InstantSummary {
+  /// Index of the executed instant.
+  pub instant_index: usize,
+  /// Number of tasks executed during the instant.
+  pub tasks_executed: usize,
+  /// Whether tasks are already registered for later instants.
+  pub remaining_work: bool
+}
+
+/// Error returned when an execution exceeds its deadline (see `Runtime::execute_with_deadline`).
+#[derive(Debug, Clone, PartialEq)]
+pub struct ExecutionTimeout {
+  /// Index of the instant during which the execution has been aborted.
+  pub instant_index: usize,
+  /// Number of tasks executed since the beginning of the execution.
+  pub tasks_executed: usize,
+  /// Wall-clock time elapsed since the beginning of the execution.
+  pub elapsed: Duration
+}
+
+
+impl fmt::Display for ExecutionTimeout {
+  fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+    write!(f, "execution aborted after {:?}, during instant {} ({} tasks executed)",
+      self.elapsed, self.instant_index, self.tasks_executed)
+  }
+}
+
+
+/// Progress of a graceful stop of a runtime (see `Runtime::stop`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum StopState { Running, Requested, ShutdownEmitted }
+
+
+/// Deadline of an execution, and state of the runtime when the execution started.
+struct Deadline {
+  start         : Duration,
+  limit         : Duration,
+  tasks_executed: usize
+}
+
+/// Identifier of a guard registered in a runtime.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct GuardId(usize);
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -8,10 +129,59 @@ use signals::runtime::SignalRuntimeRef;
 
 /// Runtime for executing reactive continuations.
 pub struct Runtime {
-  // Pools of continuations to execute at different points in time
-  current_instant_tasks: Vec<Box<Continuation<()>>>,
-  next_instant_tasks   : Vec<Box<Continuation<()>>>,
-  end_of_instant_tasks : Vec<Box<Continuation<()>>>
+  // Queues of continuations to execute at different points in time, in registration order
+  current_instant_tasks: TaskQueue,
+  next_instant_tasks   : TaskQueue,
+  end_of_instant_tasks : TaskQueue,
+
+  // Continuations to execute at the end of next instant (registered at the end of current instant)
+  next_end_of_instant_tasks: TaskQueue,
+
+  // Phase of the execution the runtime currently is in
+  phase: Phase,
+
+  // States in which the runtime refuses new tasks, and limits on the number of tasks
+  poisoned      : bool,
+  stopped       : bool,
+  stop_state    : StopState,
+  shutdown      : PureSignal,
+  task_budget   : Option<usize>,
+  queue_capacity: Option<usize>,
+
+  // Continuations to execute during later instants, indexed by instant
+  timed_tasks: BTreeMap<usize, Vec<Box<dyn Continuation<()>>>>,
+
+  // Hooks to call at the end of every instant
+  instant_hooks: Vec<InstantHook>,
+
+  // Reporters of the parts of the program which may be blocked (see `dump_state`)
+  state_reporters: Vec<StateReporter>,
+
+  // Index of the current instant, starting from 0, whether empty instants can be skipped when
+  // only timed tasks remain, and whether the runtime is executing all its work at once
+  instant_index        : usize,
+  fast_forward         : bool,
+  running_to_completion: bool,
+
+  // Counters about the work done by the runtime
+  stats: RuntimeStats,
+
+  // Source of wall-clock time, and number of tasks between two deadline checks within an instant
+  clock                  : Box<dyn Clock>,
+  deadline_check_interval: Option<usize>,
+
+  // Cleanup guards, in registration order, and identifier of the next guard
+  guards       : Vec<(GuardId, Guard)>,
+  next_guard_id: usize,
+
+  // Values of the process-local variables, by identifier of variable (see `ProcessLocal`)
+  pub(crate) locals: HashMap<usize, Box<dyn Any>>,
+
+  // Cancellation context of the running continuation (see `CancelHandle`)
+  cancel_handle: Option<CancelHandle>,
+
+  // Recycled boxes of the continuations boxed by the runtime itself
+  continuation_pool: RefCell<ContinuationPool>
 }
 
 
@@ -19,23 +189,301 @@ impl Runtime {
   /// Creates a new `Runtime`.
   pub fn new() -> Self {
     Self {
-      current_instant_tasks: Vec::new(),
-      next_instant_tasks   : Vec::new(),
-      end_of_instant_tasks : Vec::new()
+      current_instant_tasks: VecDeque::new(),
+      next_instant_tasks   : VecDeque::new(),
+      end_of_instant_tasks : VecDeque::new(),
+
+      next_end_of_instant_tasks: VecDeque::new(),
+
+      phase: Phase::BetweenInstants,
+
+      poisoned      : false,
+      stopped       : false,
+      stop_state    : StopState::Running,
+      shutdown      : PureSignal::new(),
+      task_budget   : None,
+      queue_capacity: None,
+
+      timed_tasks: BTreeMap::new(),
+
+      instant_hooks: Vec::new(),
+
+      state_reporters: Vec::new(),
+
+      instant_index        : 0,
+      fast_forward         : true,
+      running_to_completion: false,
+
+      stats: RuntimeStats::default(),
+
+      clock                  : Box::new(SystemClock::new()),
+      deadline_check_interval: Some(1000),
+
+      guards       : Vec::new(),
+      next_guard_id: 0,
+
+      locals: HashMap::new(),
+
+      cancel_handle: None,
+
+      continuation_pool: RefCell::new(ContinuationPool::default())
     }
   }
 
   /// Executes instants until all work is completed.
+  ///
+  /// Panics if the runtime is poisoned or stopped (see `try_execute`).
   pub fn execute(&mut self) {
-    let mut remaining_work = true;
+    if let Err(error) = self.try_execute() {
+      panic!("cannot execute the runtime: {}", error);
+    }
+  }
+
+  /// Executes instants until all work is completed, unless the runtime is poisoned or stopped.
+  /// Returns an error if it is, or if it gets poisoned during the execution.
+  pub fn try_execute(&mut self) -> Result<(), RuntimeError> {
+    self.check_schedulable(0, 0)?;
 
-    while remaining_work {
-      remaining_work = self.instant();
+    self.run_to_completion(|runtime: &mut Runtime| {
+      let mut remaining_work = true;
+      while remaining_work {
+        remaining_work = runtime.instant();
+
+        if runtime.poisoned {
+          return Err(RuntimeError::Poisoned);
+        }
+      }
+
+      Ok(())
+    })
+  }
+
+  /// Calls `f` with the runtime, allowing empty instants to be skipped in the meantime
+  /// (see `set_fast_forward`).
+  fn run_to_completion<F, T>(&mut self, f: F) -> T where F: FnOnce(&mut Runtime) -> T {
+    let running_to_completion = self.running_to_completion;
+    self.running_to_completion = true;
+
+    let result = f(self);
+    self.running_to_completion = running_to_completion;
+
+    result
+  }
+
+  /// Stops the runtime gracefully: current instant runs to completion, then the shutdown signal
+  /// (see `shutdown_signal`) is emitted during one final instant, so that processes can release
+  /// their resources. Then, all remaining work is dropped, guards are run (see `register_guard`),
+  /// and the runtime is stopped: it refuses new tasks and executions.
+  pub fn stop(&mut self) {
+    if self.stop_state == StopState::Running {
+      self.stop_state = StopState::Requested;
+    }
+  }
+
+  /// Returns the signal emitted during the final instant of the runtime, after `stop` is called.
+  pub fn shutdown_signal(&self) -> PureSignal {
+    self.shutdown.clone()
+  }
+
+  /// Moves a graceful stop one instant forward, if one has been requested.
+  /// Returns whether more work remains to be done.
+  fn advance_stop(&mut self, remaining_work: bool) -> bool {
+    match self.stop_state {
+      StopState::Running => remaining_work,
+
+      StopState::Requested => {
+        self.stop_state = StopState::ShutdownEmitted;
+        self.shutdown.clone().emit_from_host(self, ());
+        true
+      },
+
+      StopState::ShutdownEmitted => {
+        self.current_instant_tasks.clear();
+        self.next_instant_tasks.clear();
+        self.end_of_instant_tasks.clear();
+        self.next_end_of_instant_tasks.clear();
+        self.timed_tasks.clear();
+
+        self.stopped = true;
+        self.stop_state = StopState::Running;
+        self.run_guards();
+        false
+      }
+    }
+  }
+
+  /// Marks the runtime as poisoned, e.g. because a task panicked and left the program
+  /// in an inconsistent state: the runtime then refuses new tasks and executions.
+  pub fn poison(&mut self) {
+    self.poisoned = true;
+  }
+
+  /// Indicates whether the runtime is poisoned (see `poison`).
+  pub fn is_poisoned(&self) -> bool {
+    self.poisoned
+  }
+
+  /// Sets the maximum number of tasks the runtime accepts over its lifetime (unlimited by default).
+  pub fn set_task_budget(&mut self, task_budget: Option<usize>) {
+    self.task_budget = task_budget;
+  }
+
+  /// Sets the maximum number of tasks of each pool of tasks (unlimited by default).
+  pub fn set_queue_capacity(&mut self, queue_capacity: Option<usize>) {
+    self.queue_capacity = queue_capacity;
+  }
+
+  /// Returns an error if `tasks` new tasks cannot be added to a pool of `queue_length` tasks.
+  fn check_schedulable(&self, queue_length: usize, tasks: usize) -> Result<(), RuntimeError> {
+    if self.poisoned {
+      return Err(RuntimeError::Poisoned);
+    }
+    if self.stopped {
+      return Err(RuntimeError::Stopped);
+    }
+    if self.task_budget.is_some_and(|budget| self.stats.tasks_scheduled + tasks > budget) {
+      return Err(RuntimeError::BudgetExceeded);
+    }
+    if self.queue_capacity.is_some_and(|capacity| queue_length + tasks > capacity) {
+      return Err(RuntimeError::QueueFull);
+    }
+
+    Ok(())
+  }
+
+  /// Panics if `tasks` new tasks cannot be added to a pool of `queue_length` tasks.
+  fn expect_schedulable(&self, queue_length: usize, tasks: usize) {
+    if let Err(error) = self.check_schedulable(queue_length, tasks) {
+      panic!("cannot schedule a task: {}", error);
+    }
+  }
+
+  /// Tears the runtime down, and makes it ready for a new execution: runs all registered
+  /// guards, drops all pending tasks and hooks, and goes back to the first instant.
+  pub fn reset(&mut self) {
+    self.run_guards();
+
+    self.current_instant_tasks.clear();
+    self.next_instant_tasks.clear();
+    self.end_of_instant_tasks.clear();
+    self.next_end_of_instant_tasks.clear();
+    self.timed_tasks.clear();
+    self.instant_hooks.clear();
+    self.state_reporters.clear();
+    self.locals.clear();
+
+    self.instant_index = 0;
+    self.phase         = Phase::BetweenInstants;
+    self.stop_state    = StopState::Running;
+    self.cancel_handle = None;
+  }
+
+  /// Executes instants one by one, giving control back to the host after each of them:
+  /// `f` is called between two instants with the summary of the executed instant.
+  ///
+  /// The callback may register new tasks or emit signals (see `Signal::emit_from_host`),
+  /// which then happens during next instant. Instants are executed until it returns
+  /// `ControlFlow::Break`, even if the runtime has no work to do.
+  pub fn execute_interactive<F>(&mut self, mut f: F)
+  where
+    F: FnMut(&mut Runtime, InstantSummary) -> ControlFlow<()>
+  {
+    loop {
+      let instant_index  = self.instant_index;
+      let tasks_executed = self.stats.tasks_executed;
+      let remaining_work = self.instant();
+
+      let summary = InstantSummary {
+        instant_index,
+        tasks_executed: self.stats.tasks_executed - tasks_executed,
+        remaining_work
+      };
+
+      if f(self, summary).is_break() {
+        return;
+      }
+    }
+  }
+
+  /// Executes instants until all work is completed, or until the given wall-clock duration
+  /// (measured by the clock of the runtime) is exceeded.
+  ///
+  /// The deadline is checked at the end of each instant, as well as every few tasks within an
+  /// instant (see `set_deadline_check_interval`), so that livelocked instants are also aborted.
+  /// Work which is pending when the execution is aborted stays registered.
+  pub fn execute_with_deadline(&mut self, deadline: Duration) -> Result<(), ExecutionTimeout> {
+    let deadline = Deadline {
+      start         : self.clock.now(),
+      limit         : deadline,
+      tasks_executed: self.stats.tasks_executed
+    };
+
+    self.run_to_completion(|runtime: &mut Runtime| {
+      while runtime.run_instant(Some(&deadline))? {
+        runtime.check_deadline(&deadline)?;
+      }
+
+      Ok(())
+    })
+  }
+
+  /// Sets the number of tasks to execute between two deadline checks within an instant,
+  /// or disables those checks if `None` is given (the default is 1000 tasks).
+  pub fn set_deadline_check_interval(&mut self, interval: Option<usize>) {
+    self.deadline_check_interval = interval;
+  }
+
+  /// Replaces the clock used by the runtime to measure wall-clock time.
+  pub fn set_clock(&mut self, clock: Box<dyn Clock>) {
+    self.clock = clock;
+  }
+
+  /// Returns the clock used by the runtime to measure wall-clock time.
+  pub fn clock(&self) -> &dyn Clock {
+    &*self.clock
+  }
+
+  /// Returns an error if the given deadline is exceeded.
+  fn check_deadline(&self, deadline: &Deadline) -> Result<(), ExecutionTimeout> {
+    let elapsed = self.clock.now() - deadline.start;
+
+    if elapsed < deadline.limit {
+      return Ok(());
+    }
+
+    Err(ExecutionTimeout {
+      instant_index : self.instant_index,
+      tasks_executed: self.stats.tasks_executed - deadline.tasks_executed,
+      elapsed
+    })
+  }
+
+  /// Checks the given deadline (if any) if enough tasks have been executed since last check.
+  fn poll_deadline(&self, deadline: Option<&Deadline>) -> Result<(), ExecutionTimeout> {
+    let (deadline, interval) = match (deadline, self.deadline_check_interval) {
+      (Some(deadline), Some(interval)) => (deadline, interval),
+      _                                => return Ok(())
+    };
+
+    if (self.stats.tasks_executed - deadline.tasks_executed).is_multiple_of(interval) {
+      self.check_deadline(deadline)
+    }
+    else {
+      Ok(())
     }
   }
 
   /// Executes a single instant to completion. Indicates if more work remains to be done.
   pub fn instant(&mut self) -> bool {
+    match self.run_instant(None) {
+      Ok(remaining_work) => remaining_work,
+      Err(_)             => unreachable!()
+    }
+  }
+
+  /// Executes a single instant to completion, unless the given deadline is exceeded in between.
+  /// Indicates if more work remains to be done.
+  fn run_instant(&mut self, deadline: Option<&Deadline>) -> Result<bool, ExecutionTimeout> {
     /*
     println!("Running instant (cur: {}, endof: {}, next: {})",
       self.current_instant_tasks.len(),
@@ -44,12 +492,44 @@ impl Runtime {
     );
     */
 
+    self.stats.instants_executed += 1;
+
     // Run tasks belonging to the current instant, then tasks belonging to the end of current instant
-    while self.current_instant() {}
-    while self.end_of_instant() {}
+    self.phase = Phase::CurrentInstant;
+    let mut result = self.run_current_instant(deadline);
+
+    if result.is_ok() {
+      // Tasks registered from now on are delayed to next instant (see `on_current_instant`)
+      self.phase = Phase::EndOfInstant;
+      result = self.run_end_of_instant(deadline);
+    }
+
+    self.phase = Phase::BetweenInstants;
+    result?;
 
     // Move to the next instant and return whether there are new tasks to run or not
-    return self.move_to_next_instant();
+    let remaining_work = self.move_to_next_instant();
+    Ok(self.advance_stop(remaining_work))
+  }
+
+  /// Runs the current instant tasks, unless the given deadline is exceeded in between.
+  fn run_current_instant(&mut self, deadline: Option<&Deadline>) -> Result<(), ExecutionTimeout> {
+    while self.current_instant() {
+      self.poll_deadline(deadline)?;
+    }
+
+    Ok(())
+  }
+
+  /// Runs the end-of-instant tasks, then the instant hooks,
+  /// unless the given deadline is exceeded in between.
+  fn run_end_of_instant(&mut self, deadline: Option<&Deadline>) -> Result<(), ExecutionTimeout> {
+    while self.end_of_instant() {
+      self.poll_deadline(deadline)?;
+    }
+    self.run_instant_hooks();
+
+    Ok(())
   }
 
   /// Prepare the runtime for moving to the next instant, and update its state accordingly.
@@ -63,8 +543,37 @@ impl Runtime {
 
     // Next instant tasks now are current instant tasks
     self.current_instant_tasks.append(&mut self.next_instant_tasks);
+    self.end_of_instant_tasks.append(&mut self.next_end_of_instant_tasks);
+    self.instant_index += 1;
+
+    // Skip the instants during which nothing can happen, if any
+    let no_tasks = self.current_instant_tasks.is_empty() && self.end_of_instant_tasks.is_empty();
+    let stopping = self.stop_state != StopState::Running;
+    if self.fast_forward && self.running_to_completion && no_tasks && self.instant_hooks.is_empty() && !stopping {
+      if let Some(&instant_index) = self.timed_tasks.keys().next() {
+        self.instant_index = instant_index;
+      }
+    }
 
-    return !self.current_instant_tasks.is_empty();
+    // Add the tasks which were waiting for this instant
+    if let Some(tasks) = self.timed_tasks.remove(&self.instant_index) {
+      self.current_instant_tasks.extend(tasks);
+    }
+
+    !self.current_instant_tasks.is_empty()
+      || !self.end_of_instant_tasks.is_empty()
+      || !self.timed_tasks.is_empty()
+  }
+
+  /// Call all hooks registered to run at the end of each instant,
+  /// and unregister those which returned `false`.
+  fn run_instant_hooks(&mut self) {
+    let mut hooks = ::std::mem::take(&mut self.instant_hooks);
+    hooks.retain_mut(|hook| hook(self));
+
+    // Hooks may have been registered by the hooks themselves
+    hooks.append(&mut self.instant_hooks);
+    self.instant_hooks = hooks;
   }
 
   /// Execute a single task registered as a current instant task
@@ -74,11 +583,12 @@ impl Runtime {
       return false;
     }
 
-    if let Some(continuation) = self.current_instant_tasks.pop() {
+    if let Some(continuation) = self.current_instant_tasks.pop_front() {
+      self.stats.tasks_executed += 1;
       continuation.call_box(self, ());
     }
 
-    return !self.current_instant_tasks.is_empty();
+    !self.current_instant_tasks.is_empty()
   }
 
   /// Execute a single task registered as an end-of-instant task.
@@ -88,26 +598,1058 @@ impl Runtime {
       return false;
     }
 
-    if let Some(continuation) = self.end_of_instant_tasks.pop() {
+    if let Some(continuation) = self.end_of_instant_tasks.pop_front() {
+      self.stats.tasks_executed += 1;
       continuation.call_box(self, ())
     }
 
-    return !self.end_of_instant_tasks.is_empty();
+    !self.end_of_instant_tasks.is_empty()
   }
 
   /// Registers a continuation to execute on the current instant.
-  pub fn on_current_instant(&mut self, c: Box<Continuation<()>>) {
-    self.current_instant_tasks.push(c);
+  ///
+  /// If current instant is already over (i.e. during the end of current instant, or from an
+  /// instant hook), the continuation is executed during next instant instead.
+  ///
+  /// Panics if the runtime cannot accept the task (see `try_on_current_instant`).
+  pub fn on_current_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    if let Err(error) = self.try_on_current_instant(c) {
+      panic!("cannot schedule a task: {}", error);
+    }
+  }
+
+  /// Registers a continuation to execute on the current instant (see `on_current_instant`),
+  /// or returns an error if the runtime cannot accept it.
+  pub fn try_on_current_instant(&mut self, c: Box<dyn Continuation<()>>) -> Result<(), RuntimeError> {
+    if self.phase == Phase::EndOfInstant {
+      return self.try_on_next_instant(c);
+    }
+
+    self.check_schedulable(self.current_instant_tasks.len(), 1)?;
+    self.stats.tasks_scheduled += 1;
+    let c = self.bind_task(c);
+    self.current_instant_tasks.push_back(c);
+
+    Ok(())
   }
 
   /// Registers a continuation to execute on the next instant.
-  pub fn on_next_instant(&mut self, c: Box<Continuation<()>>) {
-    self.next_instant_tasks.push(c);
+  ///
+  /// Panics if the runtime cannot accept the task (see `try_on_next_instant`).
+  pub fn on_next_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    if let Err(error) = self.try_on_next_instant(c) {
+      panic!("cannot schedule a task: {}", error);
+    }
+  }
+
+  /// Registers a continuation to execute on the next instant,
+  /// or returns an error if the runtime cannot accept it.
+  pub fn try_on_next_instant(&mut self, c: Box<dyn Continuation<()>>) -> Result<(), RuntimeError> {
+    self.check_schedulable(self.next_instant_tasks.len(), 1)?;
+    self.stats.tasks_scheduled += 1;
+    let c = self.bind_task(c);
+    self.next_instant_tasks.push_back(c);
+
+    Ok(())
   }
 
   /// Registers a continuation to execute at the end of current instant.
   /// Runtime calls for `c` behave as if they where executed during the next instant.
-  pub fn on_end_of_instant(&mut self, c: Box<Continuation<()>>) {
-    self.end_of_instant_tasks.push(c);
+  ///
+  /// If the end of current instant is already running (or over), the continuation is executed
+  /// at the end of next instant instead: an end-of-instant task never reschedules itself
+  /// during the same end of instant.
+  pub fn on_end_of_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    let in_end_of_instant = self.phase == Phase::EndOfInstant;
+    let tasks = if in_end_of_instant { &self.next_end_of_instant_tasks } else { &self.end_of_instant_tasks };
+    self.expect_schedulable(tasks.len(), 1);
+
+    self.stats.tasks_scheduled += 1;
+    let c = self.bind_task(c);
+
+    if in_end_of_instant {
+      self.next_end_of_instant_tasks.push_back(c);
+    }
+    else {
+      self.end_of_instant_tasks.push_back(c);
+    }
+  }
+
+  /// Registers several continuations at once to execute on the current instant.
+  /// They are executed in the order of the vector, after the current instant tasks registered so far.
+  /// Like `on_current_instant`, they are delayed to next instant if current instant is over.
+  pub fn on_current_instant_batch(&mut self, cs: Vec<Box<dyn Continuation<()>>>) {
+    if self.phase == Phase::EndOfInstant {
+      return self.on_next_instant_batch(cs);
+    }
+
+    self.expect_schedulable(self.current_instant_tasks.len(), cs.len());
+    let cs = self.bind_batch(cs);
+    self.current_instant_tasks.extend(cs);
+  }
+
+  /// Registers several continuations at once to execute on the next instant.
+  /// They are executed in the order of the vector, after the next instant tasks registered so far.
+  pub fn on_next_instant_batch(&mut self, cs: Vec<Box<dyn Continuation<()>>>) {
+    self.expect_schedulable(self.next_instant_tasks.len(), cs.len());
+    let cs = self.bind_batch(cs);
+    self.next_instant_tasks.extend(cs);
+  }
+
+  /// Binds a batch of tasks to the current cancellation context, if any, and counts it.
+  fn bind_batch(&mut self, cs: Vec<Box<dyn Continuation<()>>>) -> Vec<Box<dyn Continuation<()>>> {
+    self.stats.tasks_scheduled   += cs.len();
+    self.stats.batches_scheduled += 1;
+
+    match self.cancel_handle {
+      None    => cs,
+      Some(_) => cs.into_iter().map(|c| self.bind_task(c)).collect()
+    }
+  }
+
+  /// Registers a continuation to execute on the current instant.
+  ///
+  /// Unlike `on_current_instant`, the continuation is boxed by the runtime itself.
+  pub fn schedule_current<C>(&mut self, c: C) where C: Continuation<()> {
+    let c = self.pooled(c);
+    self.on_current_instant(c);
+  }
+
+  /// Registers a continuation to execute on the next instant.
+  ///
+  /// Unlike `on_next_instant`, the continuation is boxed by the runtime itself.
+  pub fn schedule_next<C>(&mut self, c: C) where C: Continuation<()> {
+    let c = self.pooled(c);
+    self.on_next_instant(c);
+  }
+
+  /// Registers a continuation to execute at the end of current instant.
+  ///
+  /// Unlike `on_end_of_instant`, the continuation is boxed by the runtime itself.
+  pub fn schedule_end_of_instant<C>(&mut self, c: C) where C: Continuation<()> {
+    let c = self.pooled(c);
+    self.on_end_of_instant(c);
+  }
+
+  /// Registers a continuation to execute during the instant of the given index,
+  /// or during current instant if this instant is already reached.
+  ///
+  /// The continuation does not occupy the runtime until then: no intermediate task is created.
+  pub fn on_instant(&mut self, instant_index: usize, c: Box<dyn Continuation<()>>) {
+    if instant_index <= self.instant_index {
+      self.on_current_instant(c);
+    }
+    else if instant_index == self.instant_index + 1 {
+      self.on_next_instant(c);
+    }
+    else {
+      let tasks = self.timed_tasks.get(&instant_index).map_or(0, |tasks| tasks.len());
+      self.expect_schedulable(tasks, 1);
+
+      self.stats.tasks_scheduled += 1;
+      let c = self.bind_task(c);
+      self.timed_tasks.entry(instant_index).or_default().push(c);
+    }
+  }
+
+  /// Registers a continuation to execute `instants` instants after current instant.
+  pub fn on_instant_in(&mut self, instants: usize, c: Box<dyn Continuation<()>>) {
+    let instant_index = self.instant_index + instants;
+    self.on_instant(instant_index, c);
+  }
+
+  /// Enables or disables fast-forwarding (enabled by default).
+  ///
+  /// When fast-forwarding is enabled and only tasks registered for later instants remain
+  /// (see `on_instant`), the runtime directly moves to the first of those instants instead of
+  /// executing all the empty instants in between. This is not observable by the program:
+  /// it is only done when no hook is registered (see `on_end_of_each_instant`).
+  ///
+  /// Instants are only skipped while the runtime executes all its work at once (see `execute`
+  /// and `execute_with_deadline`): a host executing instants one by one (see `instant` and
+  /// `execute_interactive`) sees every instant index.
+  pub fn set_fast_forward(&mut self, fast_forward: bool) {
+    self.fast_forward = fast_forward;
+  }
+
+  /// Registers a hook to call at the end of each instant, once all end-of-instant tasks have
+  /// been executed. The hook is called until it returns `false`.
+  ///
+  /// Since the current instant is over when it is called, a hook must not register tasks
+  /// to run during current instant (or at its end), but only during next instant.
+  pub fn on_end_of_each_instant(&mut self, hook: InstantHook) {
+    self.instant_hooks.push(hook);
+  }
+
+  /// Registers a guard, i.e. a cleanup action to run when the runtime is torn down
+  /// (dropped, reset, or stopped: see `stop`). Guards run exactly once, in reverse registration
+  /// order.
+  pub fn register_guard(&mut self, guard: Guard) -> GuardId {
+    let guard_id = GuardId(self.next_guard_id);
+    self.next_guard_id += 1;
+    self.guards.push((guard_id, guard));
+
+    guard_id
+  }
+
+  /// Unregisters a guard without running it. Returns whether the guard was still registered.
+  pub fn release_guard(&mut self, guard_id: GuardId) -> bool {
+    let guards_count = self.guards.len();
+    self.guards.retain(|&(id, _)| id != guard_id);
+
+    self.guards.len() != guards_count
+  }
+
+  /// Runs and unregisters all guards, in reverse registration order
+  /// (including guards registered by the guards themselves).
+  fn run_guards(&mut self) {
+    while let Some((_, guard)) = self.guards.pop() {
+      guard(self);
+    }
+  }
+
+  /// Returns the number of process-local variables which currently hold a value in the runtime
+  /// (see `ProcessLocal`).
+  pub fn live_locals(&self) -> usize {
+    self.locals.len()
+  }
+
+  /// Registers a reporter, describing the state of a part of the program in `dump_state`.
+  pub fn register_state_reporter(&mut self, reporter: StateReporter) {
+    self.state_reporters.push(reporter);
+  }
+
+  /// Returns a human-readable snapshot of the state of the runtime, for debugging purposes:
+  /// current instant, pending tasks (with the labels of named continuations),
+  /// tasks registered for later instants, hooks, and blocked parts of the program.
+  pub fn dump_state(&self) -> String {
+    fn describe_tasks<'a, I>(tasks: I) -> String where I: ExactSizeIterator<Item = &'a Box<dyn Continuation<()>>> {
+      let count = tasks.len();
+      let labels: Vec<&str> = tasks.filter_map(|task| task.label()).collect();
+
+      if labels.is_empty() {
+        format!("{}", count)
+      }
+      else {
+        format!("{} [{}]", count, labels.join(", "))
+      }
+    }
+
+    let mut dump = String::new();
+
+    dump += &format!("instant {}\n", self.instant_index);
+    dump += &format!("current instant tasks: {}\n", describe_tasks(self.current_instant_tasks.iter()));
+    dump += &format!("end of instant tasks: {}\n", describe_tasks(self.end_of_instant_tasks.iter()));
+    dump += &format!("next instant tasks: {}\n", describe_tasks(self.next_instant_tasks.iter()));
+    dump += &format!("end of next instant tasks: {}\n", describe_tasks(self.next_end_of_instant_tasks.iter()));
+
+    dump += "timed tasks:\n";
+    for (instant_index, tasks) in &self.timed_tasks {
+      dump += &format!("  instant {}: {}\n", instant_index, describe_tasks(tasks.iter()));
+    }
+
+    dump += &format!("instant hooks: {}\n", self.instant_hooks.len());
+
+    dump += "blocked:\n";
+    for report in self.state_reporters.iter().filter_map(|reporter| reporter()) {
+      dump += &format!("  {}\n", report);
+    }
+
+    dump
+  }
+
+  /// Returns the counters about the work done by the runtime so far.
+  pub fn stats(&self) -> RuntimeStats {
+    self.stats
+  }
+
+  /// Returns the phase of the execution the runtime currently is in.
+  pub fn phase(&self) -> Phase {
+    self.phase
+  }
+
+  /// Returns the index of the current instant (the first instant has index 0).
+  pub fn instant_index(&self) -> usize {
+    self.instant_index
+  }
+
+  /// Binds a task to the current cancellation context, if any.
+  fn bind_task(&self, c: Box<dyn Continuation<()>>) -> Box<dyn Continuation<()>> {
+    match self.cancel_handle {
+      None    => c,
+      Some(_) => self.bind_to_cancel_handle(c)
+    }
+  }
+
+  /// Returns the handle of the current cancellation context, if any.
+  pub fn cancel_handle(&self) -> Option<CancelHandle> {
+    self.cancel_handle.clone()
+  }
+
+  /// Creates a new `CancelHandle`, child of the handle of the current cancellation context.
+  pub fn new_cancel_handle(&self) -> CancelHandle {
+    CancelHandle::new(self.cancel_handle())
+  }
+
+  /// Runs `f` within the cancellation context of the given handle
+  /// (or outside of any context if `handle` is `None`).
+  pub fn with_cancel_handle<F>(&mut self, handle: Option<CancelHandle>, f: F)
+  where
+    F: FnOnce(&mut Runtime)
+  {
+    let previous_handle = ::std::mem::replace(&mut self.cancel_handle, handle);
+    f(self);
+    self.cancel_handle = previous_handle;
+  }
+
+  /// Binds a continuation to the current cancellation context: the resulting continuation
+  /// runs `c` within this context, unless its handle has been cancelled in between.
+  /// It is parked instead if a gate of the context is closed (see `Gate`).
+  pub fn bind_to_cancel_handle<V, C>(&self, c: C) -> Box<dyn Continuation<V>>
+  where
+    C: Continuation<V>,
+    V: 'static
+  {
+    match self.cancel_handle() {
+      None => self.pooled(c),
+      Some(handle) => self.pooled(move |r: &mut Runtime, v: V| run_bound(r, handle, c, v))
+    }
+  }
+
+  /// Boxes a continuation, reusing a box recycled by a previously called continuation
+  /// of the same type if possible (see `ContinuationPool`).
+  fn pooled<V, C>(&self, c: C) -> Box<dyn Continuation<V>>
+  where
+    C: Continuation<V>,
+    V: 'static
+  {
+    let slot = self.continuation_pool.borrow_mut().take::<C>();
+    match slot {
+      Some(mut slot) => {
+        slot.continuation = Some(c);
+        slot
+      },
+      None => Box::new(PooledContinuation { continuation: Some(c) })
+    }
+  }
+}
+
+
+/// Calls `c` with `v` within the cancellation context of `handle`, unless the handle has been
+/// cancelled, or parks it on the first closed gate of the context, if any.
+///
+/// If the context has a panic catcher, a panic raised by `c` is caught and given to the catcher.
+fn run_bound<V, C>(runtime: &mut Runtime, handle: CancelHandle, c: C, v: V)
+where
+  C: Continuation<V>,
+  V: 'static
+{
+  if handle.is_cancelled() {
+    return;
+  }
+
+  if let Some(gate) = handle.closed_gate(runtime) {
+    gate.park(Box::new(move |r: &mut Runtime, ()| run_bound(r, handle, c, v)));
+    return;
+  }
+
+  match handle.panic_catcher() {
+    None => runtime.with_cancel_handle(Some(handle), |r| c.call(r, v)),
+    Some(catcher) => {
+      // The context must be restored even if the continuation unwinds
+      let previous_handle = runtime.cancel_handle.replace(handle);
+      let result = panic::catch_unwind(AssertUnwindSafe(|| c.call(runtime, v)));
+      runtime.cancel_handle = previous_handle;
+
+      if let Err(payload) = result {
+        catcher(runtime, payload);
+      }
+    }
+  }
+}
+
+
+impl Default for Runtime {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+impl Drop for Runtime {
+  fn drop(&mut self) {
+    self.run_guards();
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// REACTIVE RUNTIME
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Scheduling surface of a runtime, required by continuations and processes which are generic
+/// over the runtime executing them (see `Continuation` and `Process`).
+///
+/// It is implemented by `Runtime`, but other runtimes may implement it as well, e.g. runtimes
+/// instrumenting the execution, or exploring several orderings of the tasks of an instant.
+/// As for `Runtime`, tasks registered for the same point in time must run in registration order.
+pub trait ReactiveRuntime: Sized + 'static {
+  /// Registers a continuation to execute on the current instant.
+  fn on_current_instant(&mut self, c: Box<dyn Continuation<(), Self>>);
+
+  /// Registers a continuation to execute on the next instant.
+  fn on_next_instant(&mut self, c: Box<dyn Continuation<(), Self>>);
+
+  /// Registers a continuation to execute at the end of current instant.
+  fn on_end_of_instant(&mut self, c: Box<dyn Continuation<(), Self>>);
+
+  /// Returns the index of the current instant (the first instant has index 0).
+  fn instant_index(&self) -> usize;
+
+  /// Registers a continuation to execute on the current instant, boxing it.
+  fn schedule_current<C>(&mut self, c: C) where C: Continuation<(), Self> {
+    self.on_current_instant(Box::new(c));
+  }
+
+  /// Registers a continuation to execute on the next instant, boxing it.
+  fn schedule_next<C>(&mut self, c: C) where C: Continuation<(), Self> {
+    self.on_next_instant(Box::new(c));
+  }
+
+  /// Registers a continuation to execute at the end of current instant, boxing it.
+  fn schedule_end_of_instant<C>(&mut self, c: C) where C: Continuation<(), Self> {
+    self.on_end_of_instant(Box::new(c));
+  }
+}
+
+
+/// The runtime of this crate boxes continuations using its pool (see `ContinuationPool`).
+impl ReactiveRuntime for Runtime {
+  fn on_current_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    Runtime::on_current_instant(self, c);
+  }
+
+  fn on_next_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    Runtime::on_next_instant(self, c);
+  }
+
+  fn on_end_of_instant(&mut self, c: Box<dyn Continuation<()>>) {
+    Runtime::on_end_of_instant(self, c);
+  }
+
+  fn instant_index(&self) -> usize {
+    Runtime::instant_index(self)
+  }
+
+  fn schedule_current<C>(&mut self, c: C) where C: Continuation<()> {
+    Runtime::schedule_current(self, c);
+  }
+
+  fn schedule_next<C>(&mut self, c: C) where C: Continuation<()> {
+    Runtime::schedule_next(self, c);
+  }
+
+  fn schedule_end_of_instant<C>(&mut self, c: C) where C: Continuation<()> {
+    Runtime::schedule_end_of_instant(self, c);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// CONTINUATION POOL
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Maximum number of free slots kept by a continuation pool for each type of continuation.
+const POOL_SLOTS_PER_TYPE: usize = 256;
+
+
+/// Free-list of the boxes used by the continuations boxed by a runtime, indexed by type.
+///
+/// A box is recycled once the continuation it holds has been moved out of it to be called,
+/// so that the wrappers created at each instant by pauses and signals reuse the same slots.
+#[derive(Default)]
+struct ContinuationPool {
+  free_slots: HashMap<TypeId, Vec<Box<dyn Any>>>
+}
+
+
+impl ContinuationPool {
+  /// Returns a free slot for continuations of type `C`, if any.
+  fn take<C>(&mut self) -> Option<Box<PooledContinuation<C>>> where C: 'static {
+    let slot = self.free_slots.get_mut(&TypeId::of::<C>())?.pop()?;
+    slot.downcast().ok()
+  }
+
+  /// Makes an empty slot available again, unless enough slots of its type are already available.
+  fn recycle<C>(&mut self, slot: Box<PooledContinuation<C>>) where C: 'static {
+    let slots = self.free_slots.entry(TypeId::of::<C>()).or_default();
+    if slots.len() < POOL_SLOTS_PER_TYPE {
+      slots.push(slot);
+    }
+  }
+}
+
+
+/// A continuation stored in a slot of a continuation pool.
+struct PooledContinuation<C> {
+  continuation: Option<C>
+}
+
+
+impl<C, V> Continuation<V> for PooledContinuation<C>
+where
+  C: Continuation<V>
+{
+  fn call(mut self, runtime: &mut Runtime, value: V) {
+    if let Some(continuation) = self.continuation.take() {
+      continuation.call(runtime, value);
+    }
+  }
+
+  fn call_box(mut self: Box<Self>, runtime: &mut Runtime, value: V) {
+    if let Some(continuation) = self.continuation.take() {
+      runtime.continuation_pool.borrow_mut().recycle(self);
+      continuation.call(runtime, value);
+    }
+  }
+
+  fn label(&self) -> Option<&'static str> {
+    self.continuation.as_ref().and_then(|c| c.label())
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// LOCKSTEP DRIVER
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A transfer between runtimes driven by a `LockstepDriver`, called after each step of each
+/// runtime with all the runtimes. It returns whether something has been transferred.
+pub type Bridge = Box<dyn FnMut(&mut [&mut Runtime]) -> bool>;
+
+
+/// A driver advancing several runtimes in a coordinated way, one instant at a time.
+pub struct LockstepDriver<'a, 'b: 'a> {
+  runtimes  : &'a mut [&'b mut Runtime],
+  bridges   : Vec<Bridge>,
+  max_rounds: Option<usize>
+}
+
+
+/// Returns a new `LockstepDriver`, driving the given runtimes in this order.
+pub fn lockstep<'a, 'b>(runtimes: &'a mut [&'b mut Runtime]) -> LockstepDriver<'a, 'b> {
+  LockstepDriver { runtimes, bridges: Vec::new(), max_rounds: None }
+}
+
+
+impl<'a, 'b> LockstepDriver<'a, 'b> {
+  /// Registers a bridge, i.e. a transfer between runtimes, called after each step of each runtime.
+  pub fn add_bridge(&mut self, bridge: Bridge) {
+    self.bridges.push(bridge);
+  }
+
+  /// Registers a bridge emitting `target` in the runtime of index `target_runtime`
+  /// with the value of `source` each time `source` is emitted (in any other runtime).
+  ///
+  /// Since a runtime cannot emit a signal during an instant which is already over, `target`
+  /// is emitted during the next instant executed by its runtime.
+  pub fn bridge_signal<S1, S2>(&mut self, source: &S1, target_runtime: usize, target: S2)
+  where
+    S1: SignalHandle<Core = SignalRuntimeOf<S1>>,
+    S2: EmittableSignal<Element = S1::Value> + 'static
+  {
+    let values_1 = Rc::new(RefCell::new(Vec::new()));
+    let values_2 = values_1.clone();
+
+    source.clone().runtime().on_each_instant(move |_: &mut Runtime, value: Option<&S1::Value>| {
+      if let Some(value) = value {
+        values_1.borrow_mut().push(value.clone());
+      }
+    });
+
+    self.add_bridge(Box::new(move |runtimes: &mut [&mut Runtime]| {
+      let values = values_2.replace(Vec::new());
+      let transferred = !values.is_empty();
+
+      for value in values {
+        target.clone().emit_from_host(runtimes[target_runtime], value);
+      }
+
+      transferred
+    }));
+  }
+
+  /// Sets the maximum number of rounds executed by `execute_all`.
+  pub fn set_max_rounds(&mut self, max_rounds: Option<usize>) {
+    self.max_rounds = max_rounds;
+  }
+
+  /// Executes a round, i.e. exactly one instant of each runtime, in order, calling the bridges
+  /// after each step. Returns whether work remains to be done in some runtime.
+  pub fn step_all(&mut self) -> bool {
+    let mut remaining_work = false;
+
+    for index in 0..self.runtimes.len() {
+      remaining_work |= self.runtimes[index].instant();
+
+      for bridge in self.bridges.iter_mut() {
+        remaining_work |= bridge(&mut *self.runtimes);
+      }
+    }
+
+    remaining_work
+  }
+
+  /// Executes rounds until every runtime is idle, and returns the number of executed rounds,
+  /// or `None` if the maximum number of rounds (see `set_max_rounds`) has been reached before.
+  pub fn execute_all(&mut self) -> Option<usize> {
+    let mut rounds = 0;
+
+    loop {
+      if self.max_rounds == Some(rounds) {
+        return None;
+      }
+
+      rounds += 1;
+      if ! self.step_all() {
+        return Some(rounds);
+      }
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use std::rc::Rc;
+  use std::cell::RefCell;
+
+  use clock::MockClock;
+  use processes::*;
+  use signals::signals::ObservableSignal;
+  use signals::value_signal::ValueSignal;
+  use signals::pure_signal::PureSignal;
+  use testing::{XorShift, Recorder, Counter};
+  use super::*;
+
+
+  #[test]
+  fn guard_runs_on_drop () {
+    let guard_runs    = Counter::new();
+    let mut run_guard = guard_runs.incrementing();
+
+    let mut runtime = Runtime::new();
+    runtime.register_guard(Box::new(move |_: &mut Runtime| { run_guard(); }));
+
+    // Work remains pending
+    runtime.on_next_instant(Box::new(|_: &mut Runtime, _: ()| {}));
+    runtime.on_next_instant(Box::new(|_: &mut Runtime, _: ()| {}));
+    assert!(runtime.instant());
+    assert_eq!(guard_runs.get(), 0);
+
+    drop(runtime);
+    assert_eq!(guard_runs.get(), 1);
+  }
+
+  #[test]
+  fn guards_run_once_in_reverse_order () {
+    let log = Recorder::new();
+    let mut record_1 = log.recording();
+    let mut record_2 = log.recording();
+    let mut record_3 = log.recording();
+
+    let mut runtime = Runtime::new();
+    runtime.register_guard(Box::new(move |_: &mut Runtime| record_1(1)));
+    let guard_id = runtime.register_guard(Box::new(move |_: &mut Runtime| record_2(2)));
+    runtime.register_guard(Box::new(move |_: &mut Runtime| record_3(3)));
+
+    assert!(runtime.release_guard(guard_id));
+    assert!(!runtime.release_guard(guard_id));
+
+    runtime.reset();
+    drop(runtime);
+    assert_eq!(log.values(), vec![3, 1]);
+  }
+
+
+  #[test]
+  fn generic_scheduling_phases () {
+    let log      = Recorder::new();
+    let recorder = log.clone();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      let mut record_next = recorder.recording();
+      let mut record_end  = recorder.recording();
+
+      r.schedule_next(move |r: &mut Runtime, _: ()| record_next(("next", r.instant_index())));
+      r.schedule_end_of_instant(move |r: &mut Runtime, _: ()| record_end(("end", r.instant_index())));
+      recorder.record(("current", r.instant_index()));
+    });
+
+    runtime.execute();
+    assert_eq!(log.values(), vec![("current", 0), ("end", 0), ("next", 1)]);
+  }
+
+
+  // Register a task which registers itself again during current instant, forever,
+  // advancing the given clock by one millisecond each time.
+  fn livelock(runtime: &mut Runtime, clock: MockClock) {
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      clock.advance(Duration::from_millis(1));
+      livelock(r, clock);
+    });
+  }
+
+  #[test]
+  fn deadline_aborts_livelock () {
+    let clock = MockClock::new();
+
+    let mut runtime = Runtime::new();
+    runtime.set_clock(Box::new(clock.clone()));
+    runtime.set_deadline_check_interval(Some(10));
+    runtime.instant();
+
+    livelock(&mut runtime, clock);
+    assert_eq!(runtime.execute_with_deadline(Duration::from_millis(100)), Err(ExecutionTimeout {
+      instant_index : 1,
+      tasks_executed: 100,
+      elapsed       : Duration::from_millis(100)
+    }));
+  }
+
+  #[test]
+  fn deadline_not_reached () {
+    let mut runtime = Runtime::new();
+    runtime.set_clock(Box::new(MockClock::new()));
+    runtime.schedule_current(|r: &mut Runtime, _: ()| r.schedule_next(|_: &mut Runtime, _: ()| {}));
+
+    assert_eq!(runtime.execute_with_deadline(Duration::from_secs(1)), Ok(()));
+    assert_eq!(runtime.instant_index(), 2);
+  }
+
+
+  // Emissions of signals (name of the signal, instant, value).
+  type EmissionLog = Recorder<(&'static str, usize, u32)>;
+
+  // Returns a signal of integers, and records its emissions into `log`.
+  fn recorded_signal(name: &'static str, log: &EmissionLog) -> ValueSignal<u32, u32> {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let log    = log.clone();
+
+    signal.clone().runtime().on_each_instant(move |r: &mut Runtime, value: Option<&u32>| {
+      if let Some(&value) = value {
+        log.record((name, r.instant_index(), value));
+      }
+    });
+
+    signal
+  }
+
+  // Returns a process awaiting `input` and emitting `output` with the received value plus one,
+  // until it emits a value greater or equal to `last`.
+  fn reply_until(input: ValueSignal<u32, u32>, output: ValueSignal<u32, u32>, last: u32)
+    -> impl Process<Value = ()>
+  {
+    let reply = move |v: u32| value(v + 1).join(output.clone().emit_value(v + 1));
+    let test_loop_end = move |(v, _): (u32, ())| {
+      if v >= last { LoopStatus::Exit(()) } else { LoopStatus::Continue }
+    };
+
+    input.await().and_then(reply).map(test_loop_end).while_loop()
+  }
+
+  #[test]
+  fn lockstep_ping_pong () {
+    let log = Recorder::new();
+
+    let ping    = recorded_signal("ping", &log);
+    let pong    = recorded_signal("pong", &log);
+    let ping_in = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let pong_in = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+
+    let (ping_1, ping_in_1, pong_1, pong_in_1) = (ping.clone(), ping_in.clone(), pong.clone(), pong_in.clone());
+    let process_a = ping_1.clone().emit_value(0).and_then(move |_| reply_until(pong_in_1, ping_1, 18));
+    let process_b = reply_until(ping_in_1, pong_1, 19);
+
+    let mut runtime_a = Runtime::new();
+    let mut runtime_b = Runtime::new();
+    runtime_a.schedule_current(move |r: &mut Runtime, _: ()| process_a.call(r, |_: &mut Runtime, _: ()| {}));
+    runtime_b.schedule_current(move |r: &mut Runtime, _: ()| process_b.call(r, |_: &mut Runtime, _: ()| {}));
+
+    {
+      let mut runtimes = [&mut runtime_a, &mut runtime_b];
+      let mut driver = lockstep(&mut runtimes);
+      driver.bridge_signal(&ping, 1, ping_in);
+      driver.bridge_signal(&pong, 0, pong_in);
+      driver.set_max_rounds(Some(100));
+
+      assert_eq!(driver.execute_all(), Some(30));
+    }
+
+    let expected_log: Vec<_> = (0..10)
+      .flat_map(|k| vec![("ping", 3 * k, 2 * k as u32), ("pong", 3 * k + 1, 2 * k as u32 + 1)])
+      .collect();
+    assert_eq!(log.values(), expected_log);
+    assert_eq!(runtime_a.instant_index(), runtime_b.instant_index());
+  }
+
+
+  // Execute a runtime with a single task registered `instants` instants later,
+  // and returns the index of the instant during which the task ran.
+  fn run_late_task(runtime: &mut Runtime, instants: usize) -> usize {
+    let instant_indices    = Recorder::new();
+    let mut record_instant = instant_indices.recording();
+
+    runtime.on_instant_in(instants, Box::new(move |r: &mut Runtime, _: ()| record_instant(r.instant_index())));
+    runtime.execute();
+
+    instant_indices.values()[0]
+  }
+
+  #[test]
+  fn fast_forward_to_timed_task () {
+    let mut runtime = Runtime::new();
+
+    assert_eq!(run_late_task(&mut runtime, 100_000), 100_000);
+    assert_eq!(runtime.stats().instants_executed, 2);
+  }
+
+  #[test]
+  fn no_fast_forward_to_timed_task () {
+    let mut runtime = Runtime::new();
+    runtime.set_fast_forward(false);
+
+    assert_eq!(run_late_task(&mut runtime, 1000), 1000);
+    assert_eq!(runtime.stats().instants_executed, 1001);
+  }
+
+  #[test]
+  fn no_fast_forward_in_interactive_execution () {
+    let mut runtime = Runtime::new();
+    let task_runs   = Recorder::new();
+    let mut record  = task_runs.recording();
+
+    runtime.on_instant_in(100, Box::new(move |r: &mut Runtime, _: ()| record(r.instant_index())));
+
+    // The host sees every instant, up to the one of the timed task
+    let summaries = Recorder::new();
+    let mut record_summary = summaries.recording();
+    runtime.execute_interactive(move |_: &mut Runtime, summary: InstantSummary| {
+      record_summary(summary.instant_index);
+      if summary.remaining_work { ControlFlow::Continue(()) } else { ControlFlow::Break(()) }
+    });
+
+    assert_eq!(task_runs.values(), vec![100]);
+    assert_eq!(summaries.values(), (0..101).collect::<Vec<_>>());
+    assert_eq!(runtime.stats().instants_executed, 101);
+  }
+
+
+  #[test]
+  fn end_of_instant_rescheduled_at_end_of_next_instant () {
+    let log             = Recorder::new();
+    let mut record_end  = log.recording();
+    let mut record_next = log.recording();
+    let recorder        = log.clone();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+      record_end(("end", r.instant_index()));
+      r.schedule_end_of_instant(move |r: &mut Runtime, _: ()| recorder.record(("end again", r.instant_index())));
+    });
+    runtime.schedule_next(move |r: &mut Runtime, _: ()| record_next(("next", r.instant_index())));
+
+    runtime.execute();
+    assert_eq!(log.values(), vec![("end", 0), ("next", 1), ("end again", 1)]);
+  }
+
+  #[test]
+  fn current_instant_from_end_of_instant_delayed_to_next_instant () {
+    let log        = Recorder::new();
+    let mut record = log.recording();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+      r.schedule_current(move |r: &mut Runtime, _: ()| record(r.instant_index()));
+    });
+
+    assert!(runtime.instant());
+    assert!(!runtime.instant());
+    assert_eq!(log.values(), vec![1]);
+  }
+
+
+  #[test]
+  fn phases () {
+    let phases = Recorder::new();
+    let mut record_1 = phases.recording();
+    let mut record_2 = phases.recording();
+    let mut record_3 = phases.recording();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| record_1(r.phase()));
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| record_2(r.phase()));
+    runtime.on_end_of_each_instant(Box::new(move |r: &mut Runtime| { record_3(r.phase()); false }));
+
+    assert_eq!(runtime.phase(), Phase::BetweenInstants);
+    runtime.instant();
+    assert_eq!(runtime.phase(), Phase::BetweenInstants);
+    assert_eq!(phases.values(), vec![Phase::CurrentInstant, Phase::EndOfInstant, Phase::EndOfInstant]);
+  }
+
+
+  #[test]
+  fn poisoned_runtime_refuses_work () {
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(|r: &mut Runtime, _: ()| r.poison());
+
+    assert_eq!(runtime.try_execute(), Err(RuntimeError::Poisoned));
+    assert_eq!(runtime.try_on_next_instant(Box::new(|_: &mut Runtime, _: ()| {})), Err(RuntimeError::Poisoned));
+    assert_eq!(runtime.try_execute(), Err(RuntimeError::Poisoned));
+  }
+
+  #[test]
+  #[should_panic(expected = "cannot execute the runtime: the runtime is poisoned")]
+  fn poisoned_runtime_does_not_execute () {
+    let mut runtime = Runtime::new();
+    runtime.poison();
+    runtime.execute();
+  }
+
+  #[test]
+  fn bounded_runtime () {
+    let mut runtime = Runtime::new();
+    runtime.set_queue_capacity(Some(1));
+    runtime.set_task_budget(Some(2));
+
+    assert_eq!(runtime.try_on_next_instant(Box::new(|_: &mut Runtime, _: ()| {})), Ok(()));
+    assert_eq!(runtime.try_on_next_instant(Box::new(|_: &mut Runtime, _: ()| {})), Err(RuntimeError::QueueFull));
+    assert_eq!(runtime.try_on_current_instant(Box::new(|_: &mut Runtime, _: ()| {})), Ok(()));
+    assert_eq!(runtime.try_on_current_instant(Box::new(|_: &mut Runtime, _: ()| {})), Err(RuntimeError::BudgetExceeded));
+  }
+
+
+  /// State shared by the instrumented tasks of a fuzzed schedule.
+  struct Schedule {
+    rng   : XorShift,
+    signal: PureSignal,
+
+    // Earliest instant each task can run during, indexed by task id,
+    // and task ids with the instant they ran during, in execution order
+    earliest_instants: Vec<usize>,
+    executions       : Vec<(usize, usize)>
+  }
+
+  type ScheduleRef = Rc<RefCell<Schedule>>;
+
+  /// Maximal depth of the tasks spawned by other tasks.
+  const MAX_SPAWN_DEPTH: usize = 4;
+
+  /// Task recording its execution, and spawning a few other tasks in turn.
+  struct InstrumentedTask {
+    id      : usize,
+    depth   : usize,
+    schedule: ScheduleRef
+  }
+
+  impl Continuation<()> for InstrumentedTask {
+    fn call(self, runtime: &mut Runtime, _: ()) {
+      let children = {
+        let mut schedule = self.schedule.borrow_mut();
+        schedule.executions.push((self.id, runtime.instant_index()));
+        if self.depth < MAX_SPAWN_DEPTH { schedule.rng.below(3) } else { 0 }
+      };
+
+      for _ in 0..children {
+        spawn(runtime, &self.schedule, self.depth + 1);
+      }
+    }
+
+    fn call_box(self: Box<Self>, runtime: &mut Runtime, value: ()) {
+      (*self).call(runtime, value);
+    }
+  }
+
+  // Register a new instrumented task, using a randomly chosen way of scheduling it.
+  fn spawn(runtime: &mut Runtime, schedule: &ScheduleRef, depth: usize) {
+    let instant = runtime.instant_index();
+    let (kind, delay, signal) = {
+      let mut schedule = schedule.borrow_mut();
+      (schedule.rng.below(8), 1 + schedule.rng.below(3) as usize, schedule.signal.clone())
+    };
+
+    let earliest_instant = match kind {
+      1 | 4 | 6 => instant + 1,
+      5         => instant + delay,
+      _         => instant
+    };
+
+    let id = {
+      let mut schedule = schedule.borrow_mut();
+      schedule.earliest_instants.push(earliest_instant);
+      schedule.earliest_instants.len() - 1
+    };
+
+    let task = InstrumentedTask { id, depth, schedule: schedule.clone() };
+    let emitter = signal.clone();
+    let emit = move |r: &mut Runtime, _: ()| emitter.runtime().emit(r, ());
+
+    match kind {
+      0 => runtime.on_current_instant(Box::new(task)),
+      1 => runtime.on_next_instant(Box::new(task)),
+      2 => runtime.on_end_of_instant(Box::new(task)),
+      3 => runtime.on_current_instant_batch(vec![Box::new(task)]),
+      4 => runtime.on_next_instant_batch(vec![Box::new(task)]),
+      5 => runtime.on_instant_in(delay, Box::new(task)),
+
+      // Signal waiters, woken up by an emission registered along with them
+      6 => {
+        signal.runtime().later_on_present(runtime, move |r: &mut Runtime, _: ()| task.call(r, ()));
+        runtime.on_instant_in(delay, Box::new(emit));
+      },
+      _ => {
+        signal.runtime().on_present(runtime, task);
+        runtime.on_instant_in(delay - 1, Box::new(emit));
+      }
+    }
+  }
+
+  #[test]
+  fn scheduled_tasks_run_exactly_once () {
+    for seed in 1..301 {
+      let schedule = Rc::new(RefCell::new(Schedule {
+        rng   : XorShift::new(seed),
+        signal: PureSignal::new(),
+
+        earliest_instants: Vec::new(),
+        executions       : Vec::new()
+      }));
+
+      let mut runtime = Runtime::new();
+      let root_tasks  = 1 + schedule.borrow_mut().rng.below(8);
+      for _ in 0..root_tasks {
+        spawn(&mut runtime, &schedule, 0);
+      }
+
+      runtime.execute();
+
+      let schedule   = schedule.borrow();
+      let mut counts = vec![0; schedule.earliest_instants.len()];
+      for &(id, instant) in &schedule.executions {
+        counts[id] += 1;
+        assert!(instant >= schedule.earliest_instants[id],
+          "seed {}: task {} ran during instant {}, before instant {}",
+          seed, id, instant, schedule.earliest_instants[id]);
+      }
+
+      for (id, &count) in counts.iter().enumerate() {
+        assert_eq!(count, 1, "seed {}: task {} ran {} times", seed, id, count);
+      }
+    }
   }
 }
@@ -14,8 +14,16 @@ pub mod runtime;
 /// This sub-module contains the definition of the generic `Signal` trait, which must be implemented
 /// by any structure which represents and actual signal. This trait exposes several methods
 /// for building processes based on signals, which are also defined in this module.
+#[allow(clippy::module_inception)]
 pub mod signals;
 
+/// **Signal cores.**
+///
+/// This sub-module contains the `SignalCore` trait, which abstracts the core of a signal
+/// away from the processes built from it, so that other kinds of signals can be implemented.
+///
+pub mod signal_core;
+
 /// **Pure signals.**
 ///
 /// This sub-module contains the implementation of pure signals (i.e. holding no value).
@@ -27,3 +35,23 @@ pub mod pure_signal;
 /// This sub-module contains the implementation of value signals.
 ///
 pub mod value_signal;
+
+/// **Signal sets.**
+///
+/// This sub-module contains collections of pure signals, providing bulk operations over them.
+///
+pub mod signal_set;
+
+/// **Signal slots.**
+///
+/// This sub-module contains placeholders for signals bound after the processes using them
+/// are defined, e.g. for building mutually recursive processes.
+///
+pub mod signal_slot;
+
+/// **Promise signals.**
+///
+/// This sub-module contains the implementation of one-shot signals, resolved by their first
+/// emission, which are built on top of the `SignalCore` trait only.
+///
+pub mod promise_signal;
@@ -0,0 +1,249 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use runtime::Runtime;
+use continuations::Continuation;
+use signals::signals::*;
+use signals::signal_core::SignalCore;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PROMISE SIGNAL
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// State of a promise signal, and continuations waiting for it.
+struct PromiseState<V> {
+  // Value the promise has been resolved with, and value delivered from the following instant on
+  value    : Option<V>,
+  delivered: Option<V>,
+
+  // Continuations waiting for the resolution (or the absence) of the promise, in registration order
+  on_present      : Vec<Box<dyn Continuation<()>>>,
+  later_on_present: Vec<Box<dyn Continuation<Option<V>>>>,
+  later_on_absent : Vec<Box<dyn Continuation<()>>>,
+
+  // Whether the continuations waiting for the absence of the promise are scheduled
+  later_on_absent_registered: bool
+}
+
+
+/// One-shot signal, resolved by its first emission.
+///
+/// Once resolved, the signal is present during every instant, with the value it has been resolved
+/// with: awaiting a resolved promise completes without waiting for another emission.
+/// Emissions following the first one are ignored. The value of the signal is `None` until the
+/// instant following its resolution.
+///
+/// It is entirely implemented on top of the `SignalCore` trait, as an example of a signal kind
+/// which does not rely on the core of the other signals of this crate.
+pub struct PromiseSignal<V> {
+  state: Rc<RefCell<PromiseState<V>>>
+}
+
+
+impl<V> PromiseSignal<V>
+where
+  V: Clone + 'static
+{
+  /// Create a new, unresolved `PromiseSignal`.
+  pub fn new() -> Self {
+    PromiseSignal {
+      state: Rc::new(RefCell::new(PromiseState {
+        value    : None,
+        delivered: None,
+
+        on_present      : Vec::new(),
+        later_on_present: Vec::new(),
+        later_on_absent : Vec::new(),
+
+        later_on_absent_registered: false
+      }))
+    }
+  }
+
+  /// Resolve the promise with the given value during current instant,
+  /// unless it has already been resolved.
+  /// This function is equivalent to calling `emit_value`.
+  pub fn resolve(self, value: V) -> EmitProcess<Self> {
+    self.emit_value(value)
+  }
+
+  /// Returns whether the promise has been resolved.
+  pub fn is_resolved(&self) -> bool {
+    self.state.borrow().value.is_some()
+  }
+}
+
+
+impl<V> Clone for PromiseSignal<V> {
+  fn clone(&self) -> Self {
+    PromiseSignal { state: self.state.clone() }
+  }
+}
+
+
+impl<V> Default for PromiseSignal<V>
+where
+  V: Clone + 'static
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+impl<V> SignalCore<Option<V>, V> for PromiseSignal<V>
+where
+  V: Clone + 'static
+{
+  fn emit_element(&self, runtime: &mut Runtime, element: V) {
+    let (value, on_present, later_on_present) = {
+      let mut state = self.state.borrow_mut();
+      if state.value.is_some() {
+        return;
+      }
+
+      state.value = Some(element.clone());
+      state.later_on_absent.clear();
+
+      (Some(element), state.on_present.split_off(0), state.later_on_present.split_off(0))
+    };
+
+    // Waiting continuations are bound to the cancellation context of whoever registered them
+    let state = self.state.clone();
+    runtime.with_cancel_handle(None, |r| {
+      let delivered = value.clone();
+      r.schedule_end_of_instant(move |_: &mut Runtime, _: ()| state.borrow_mut().delivered = delivered);
+
+      r.on_current_instant_batch(on_present);
+      for c in later_on_present {
+        let value = value.clone();
+        r.schedule_next(move |r: &mut Runtime, _: ()| c.call(r, value));
+      }
+    });
+  }
+
+  fn register_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    if self.is_resolved() {
+      runtime.schedule_current(c);
+    }
+    else {
+      let c = runtime.bind_to_cancel_handle(c);
+      self.state.borrow_mut().on_present.push(c);
+    }
+  }
+
+  fn register_later_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<Option<V>> {
+    let value = self.state.borrow().value.clone();
+    if value.is_some() {
+      runtime.schedule_next(move |r: &mut Runtime, _: ()| c.call(r, value));
+    }
+    else {
+      let c = runtime.bind_to_cancel_handle(c);
+      self.state.borrow_mut().later_on_present.push(c);
+    }
+  }
+
+  fn register_later_on_absent<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    if self.is_resolved() {
+      return;
+    }
+
+    let c = runtime.bind_to_cancel_handle(c);
+    let mut state = self.state.borrow_mut();
+    state.later_on_absent.push(c);
+
+    // The absence of the promise is known at the end of current instant
+    if ! state.later_on_absent_registered {
+      state.later_on_absent_registered = true;
+
+      let state = self.state.clone();
+      runtime.with_cancel_handle(None, |r| {
+        r.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+          let later_on_absent = {
+            let mut state = state.borrow_mut();
+            state.later_on_absent_registered = false;
+            state.later_on_absent.split_off(0)
+          };
+
+          r.on_next_instant_batch(later_on_absent);
+        });
+      });
+    }
+  }
+
+  fn delivered_value(&self) -> Option<V> {
+    self.state.borrow().delivered.clone()
+  }
+}
+
+
+impl<V> SignalHandle for PromiseSignal<V>
+where
+  V: Clone + 'static
+{
+  type Value   = Option<V>;
+  type Element = V;
+  type Core    = Self;
+
+  fn core(self) -> Self {
+    self
+  }
+}
+
+
+impl<V> ObservableSignal for PromiseSignal<V> where V: Clone + 'static {}
+
+
+impl<V> EmittableSignal for PromiseSignal<V> where V: Clone + 'static {}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use super::*;
+
+
+  #[test]
+  fn await_resolution () {
+    let promise_1 = PromiseSignal::new();
+    let promise_2 = promise_1.clone();
+
+    // Only the first resolution is taken into account
+    let resolve = value(()).pause().pause()
+      .and_then(move |_| promise_1.clone().resolve(42).join(promise_1.resolve(7)));
+    let waiter  = promise_2.await().and_then(|v| instant_index().map(move |i| (i, v)));
+
+    let (result, _) = execute_process(waiter.join(resolve));
+    assert_eq!(result, (3, Some(42)));
+  }
+
+  #[test]
+  fn resolved_promise_stays_present () {
+    let promise_1 = PromiseSignal::new();
+    let promise_2 = promise_1.clone();
+
+    let late_observer = value(()).pause().pause()
+      .and_then(move |_| promise_1.clone().await_immediate().and_then(|_| instant_index())
+        .join(promise_1.clone().is_present())
+        .join(promise_1.pre()));
+
+    let ((instant, present), pre) = execute_process(late_observer.join(promise_2.resolve(1))).0;
+    assert_eq!(instant, 2);
+    assert!(present);
+    assert_eq!(pre, Some(1));
+  }
+
+  #[test]
+  fn unresolved_promise_is_absent () {
+    let promise: PromiseSignal<usize> = PromiseSignal::new();
+
+    let observer = promise.clone().is_present().join(promise.clone().pre());
+    assert_eq!(execute_process(observer), (false, None));
+    assert!(!promise.is_resolved());
+  }
+}
@@ -1,5 +1,4 @@
-use std::marker::PhantomData;
-
+use runtime::Runtime;
 use signals::signals::*;
 use signals::runtime::SignalRuntimeRef;
 
@@ -21,21 +20,71 @@ impl PureSignal {
     PureSignal { runtime_ref: SignalRuntimeRef::new((), Box::new(|_, _| { })) }
   }
 
+  /// Set the name of the signal, used for diagnostics (see `Describe`).
+  pub fn with_name(self, name: &'static str) -> Self {
+    self.runtime_ref.set_name(name);
+    self
+  }
+
   /// Emit the signal during current instant.
   /// This function is equivalent to calling `emit_value` with value `()`.
-  pub fn emit(self) -> EmitProcess<Self, (), ()> {
+  pub fn emit(self) -> EmitProcess<Self> {
     self.emit_value(())
   }
+
+  /// Create a derived signal, present during each instant following an instant during which
+  /// this signal is absent.
+  ///
+  /// Since the absence of a signal is only known at the end of an instant, the derived signal
+  /// is shifted by one instant: it is present during instant `n + 1` if and only if this signal
+  /// is absent during instant `n`, like the continuations registered using `later_on_absent`.
+  /// Instants preceding the first use of this signal (or of the derived signal) are ignored.
+  pub fn negation(&self) -> PureSignal {
+    let negation = PureSignal::new();
+    negation.runtime_ref.depends_on(&self.runtime_ref);
+
+    let negation_ref = negation.runtime_ref.clone();
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&()>| {
+      if value.is_none() {
+        let negation_ref = negation_ref.clone();
+        r.schedule_next(move |r: &mut Runtime, _: ()| negation_ref.emit(r, ()));
+      }
+    });
+
+    negation
+  }
+
+  /// Returns a read-only handle to the signal, which cannot be used to emit it.
+  pub fn reader(&self) -> SignalReader<(), ()> {
+    SignalReader::new(self.runtime_ref.clone())
+  }
+}
+
+
+impl Default for PureSignal {
+  fn default() -> Self {
+    Self::new()
+  }
 }
 
 
-impl Signal<(), ()> for PureSignal {
-  fn runtime(self) -> SignalRuntimeRef<(), ()> {
+impl SignalHandle for PureSignal {
+  type Value   = ();
+  type Element = ();
+  type Core    = SignalRuntimeRef<(), ()>;
+
+  fn core(self) -> SignalRuntimeRef<(), ()> {
     self.runtime_ref.clone()
   }
 }
 
 
+impl ObservableSignal for PureSignal {}
+
+
+impl EmittableSignal for PureSignal {}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // TESTS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -43,10 +92,11 @@ impl Signal<(), ()> for PureSignal {
 #[cfg(test)]
 mod tests {
   use std::rc::Rc;
-  use std::cell::{Cell, RefCell};
+  use std::cell::Cell;
 
-  use runtime::Runtime;
+  use continuations::Continuation;
   use processes::*;
+  use testing::{SignalAssertions, Recorder};
   use super::*;
 
   // This function joins and emitting process and an awaiting process, in both possible orders.
@@ -63,24 +113,24 @@ mod tests {
     let pure_signal_received_3 = pure_signal_received_1.clone();
 
     // First process: check that the signal has *not* been received before, and emit the signal
-    let process_emit = pure_signal_1.emit().map(move |signal| {
+    let process_emit = pure_signal_1.emit().map(move |_| {
       // println!("Signal has been emitted from process_emit");
-      assert_eq!(pure_signal_received_1.get(), false);
+      assert!(!pure_signal_received_1.get());
       pure_signal_emitted_1.set(true);
     });
 
     // Second process: check that the signal has been emitted before, once received
-    let process_await = pure_signal_2.await_immediate().map(move |v| {
+    let process_await = pure_signal_2.await_immediate().map(move |_| {
       // println!("Signal has been received in process_await");
-      assert_eq!(pure_signal_emitted_2.get(), true);
+      assert!(pure_signal_emitted_2.get());
       pure_signal_received_2.set(true);
     });
 
     // Third (main) process: run both processes and makes sure the signal is emitted and received
     // The order of the join operation is decided at this point, according to the given parameter
-    let check_joined_processes = move |v| {
-      assert_eq!(pure_signal_emitted_3.get(), true);
-      assert_eq!(pure_signal_received_3.get(), true);
+    let check_joined_processes = move |_| {
+      assert!(pure_signal_emitted_3.get());
+      assert!(pure_signal_received_3.get());
     };
 
     if await_first {
@@ -111,25 +161,29 @@ mod tests {
     let present_signal_3  = present_signal_1.clone();
 
     // First process: ran if the signal is present (during current instant)
-    let process_if = value(()).map(move |v| {
+    let process_if = value(()).map(move |_| {
       // println!("Signal is present");
-      assert_eq!(present_signal_1.get(), true);
+      assert!(present_signal_1.get());
     });
 
     // Second process: ran if the signal was absent (during previous instant)
-    let process_else = value(()).map(move |v| {
+    let process_else = value(()).map(move |_| {
       // println!("Signal is absent");
-      assert_eq!(present_signal_2.get(), false);
+      assert!(!present_signal_2.get());
     });
 
     // Run a present process, and possibly emit the signal during the same instant (using join construct)
     // Whether the signal shall be emitted or not is decided at this point, according to the given parameter
+    let log = pure_signal_1.attach();
+
     if emit_signal {
-      let emit_process = pure_signal_1.emit().map(move |v| { present_signal_3.set(true); });
+      let emit_process = pure_signal_1.emit().map(move |_| { present_signal_3.set(true); });
       execute_process(pure_signal_2.present(process_if, process_else).join(emit_process));
+      log.assert_present_at(&[0]);
     }
     else {
       execute_process(pure_signal_1.present(process_if, process_else));
+      log.assert_absent_at(&[0, 1]);
     }
   }
 
@@ -142,4 +196,136 @@ mod tests {
   fn signal_is_absent () {
     general_present_or_absent_signal(false);
   }
+
+
+  #[test]
+  fn wake_many_waiters_in_order () {
+    let pure_signal = PureSignal::new();
+
+    let log    = Recorder::new();
+    let record = log.recording();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      for i in 0..1000 {
+        let mut record = record.clone();
+        pure_signal.clone().await_immediate().call(r, move |_: &mut Runtime, _: ()| record(i));
+      }
+
+      let batches_scheduled = r.stats().batches_scheduled;
+      pure_signal.clone().emit().call(r, |_: &mut Runtime, _: ()| {});
+      assert_eq!(r.stats().batches_scheduled, batches_scheduled + 1);
+    });
+
+    runtime.execute();
+    assert_eq!(log.values(), (0..1000).collect::<Vec<_>>());
+  }
+
+
+  #[test]
+  fn dump_staged_state () {
+    let pure_signal_1 = PureSignal::new();
+    // Dropped signals are not reported: keep the signal alive until the end of the test
+    let pure_signal_2 = pure_signal_1.clone();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      r.on_next_instant(Box::new((|_: &mut Runtime, _: ()| {}).named("tick")));
+      r.on_instant(5, Box::new((|_: &mut Runtime, _: ()| {}).named("timer")));
+      pure_signal_1.await().call(r, |_: &mut Runtime, _: ()| {});
+    });
+    runtime.instant();
+
+    let dump = runtime.dump_state();
+    assert!(dump.contains("instant 1\n"));
+    assert!(dump.contains("current instant tasks: 1 [tick]"));
+    assert!(dump.contains("instant 5: 1 [timer]"));
+    assert!(dump.contains("1 awaiting presence, 0 awaiting absence"));
+
+    drop(pure_signal_2);
+  }
+
+
+  #[test]
+  #[should_panic(expected = "signal emitted at the end of an instant")]
+  fn emit_at_end_of_instant () {
+    let pure_signal = PureSignal::new();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| pure_signal.emit_from_host(r, ()));
+    runtime.execute();
+  }
+
+
+  #[test]
+  fn negation_is_shifted_by_one_instant () {
+    let pure_signal = PureSignal::new();
+    let negation    = pure_signal.negation();
+    let log         = negation.attach();
+
+    let mut runtime = Runtime::new();
+    for instant in 0..6 {
+      if instant == 0 || instant == 2 {
+        pure_signal.clone().emit_from_host(&mut runtime, ());
+      }
+      runtime.instant();
+    }
+
+    // The signal is absent during instants 1, 3 and 4: the negation is present during the
+    // following instants (the absence during instant 5 would be observed during instant 6)
+    log.assert_present_at(&[2, 4, 5]);
+    log.assert_absent_at(&[0, 1, 3]);
+  }
+
+
+  // Run three labeled waiters built by `waiter` on a signal, emitted before or after they are
+  // registered, and return the labels in the order the waiters ran.
+  fn wake_order<F, P>(emit_first: bool, waiter: F) -> Vec<&'static str>
+  where
+    F: Fn(PureSignal) -> P,
+    P: Process<Value = ()>
+  {
+    let pure_signal = PureSignal::new();
+
+    let log = Recorder::new();
+
+    let mut runtime = Runtime::new();
+    if emit_first {
+      pure_signal.clone().emit_from_host(&mut runtime, ());
+    }
+
+    for &label in &["first", "second", "third"] {
+      let mut record = log.recording();
+      waiter(pure_signal.clone()).call(&mut runtime, move |_: &mut Runtime, _: ()| record(label));
+    }
+
+    if ! emit_first {
+      pure_signal.emit_from_host(&mut runtime, ());
+    }
+
+    runtime.execute();
+    log.values()
+  }
+
+  #[test]
+  fn wake_waiters_in_registration_order () {
+    let in_order = vec!["first", "second", "third"];
+
+    for &emit_first in &[true, false] {
+      assert_eq!(wake_order(emit_first, |s| s.await_immediate()), in_order);
+      assert_eq!(wake_order(emit_first, |s| s.await()), in_order);
+    }
+
+    let absent_signal = PureSignal::new();
+    assert_eq!(wake_order(false, move |_| absent_signal.clone().present(value(()), value(()))), in_order);
+  }
+
+  #[test]
+  fn absent_branch_runs_during_next_instant () {
+    let pure_signal = PureSignal::new();
+
+    // The absence is known at the end of instant 0
+    let branch = instant_index().join(pure_signal.present(instant_index(), instant_index()));
+    assert_eq!(execute_process(branch), (0, 1));
+  }
 }
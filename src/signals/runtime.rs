@@ -1,8 +1,39 @@
-use std::rc::Rc;
+use std::rc::{Rc, Weak};
 use std::cell::{Cell, RefCell};
 
-use runtime::Runtime;
-use continuations::Continuation;
+use runtime::{Runtime, Phase};
+use continuations::{Continuation, CancelHandle};
+
+
+/// Boxed gather function of a signal, receiving an element `E`
+/// and a mutable reference to the current value (of type `V`).
+pub type GatherFunction<V, E> = Box<dyn FnMut(E, &mut V)>;
+
+/// Boxed gather function of a signal, receiving an element `E`, a mutable reference
+/// to the current value (of type `V`), and the index of the instant of the emission.
+pub type IndexedGatherFunction<V, E> = Box<dyn FnMut(E, &mut V, usize)>;
+
+/// Continuation waiting for a signal to be present, along with the cancel handle
+/// of the context it was registered in (if any).
+type Waiter = (Option<CancelHandle>, Box<dyn Continuation<()>>);
+
+/// Gather function shared by all references to a signal with the same element type.
+type SharedGatherFunction<V, E> = Rc<RefCell<IndexedGatherFunction<V, E>>>;
+
+/// Boxed finalizer of a signal, called once per instant during which the signal is present,
+/// with the value gathered during this instant.
+pub type Finalizer<V> = Box<dyn FnMut(&mut V)>;
+
+/// Boxed tap of a signal, called at the end of each instant with the value of the signal
+/// if it has been emitted during this instant, or with `None` otherwise.
+pub type SignalTap<V> = Box<dyn FnMut(&mut Runtime, Option<&V>)>;
+
+/// Boxed emit listener of a signal, called after each emission of the signal
+/// with the value gathered so far during current instant.
+pub type EmitListener<V> = Box<dyn FnMut(&mut Runtime, &V)>;
+
+/// Boxed action to run when a signal is bound to a runtime.
+type BindAction = Box<dyn FnOnce(&mut Runtime)>;
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -13,37 +44,57 @@ use continuations::Continuation;
 ///
 /// It is unique to each signal, and contains all the information concerning the signal:
 /// its emit state, registered continuations, and value-related fields.
-struct SignalRuntime<V, E> {
+/// The gather function, which depends on the type of the emitted elements,
+/// is kept by the references to the signal (see `SignalRuntimeRef`).
+struct SignalRuntime<V> {
   // Emit state
   is_currently_emitted  : Cell<bool>,
+  last_emission_instant : Cell<Option<usize>>,
+  gathered_elements     : Cell<usize>,
 
   // Registered continuations
-  call_on_present: RefCell<Vec<Box<Continuation<()>>>>,
-  call_later_on_present: RefCell<Vec<Box<Continuation<V>>>>,
-  call_later_on_absent: RefCell<Vec<Box<Continuation<()>>>>,
+  call_on_present: RefCell<Vec<Waiter>>,
+  call_later_on_present: RefCell<Vec<Box<dyn Continuation<V>>>>,
+  call_later_on_absent: RefCell<Vec<Box<dyn Continuation<()>>>>,
 
   // FLag indicating whether a continuation to run later_on_absent continuations
   // has been added to the runtime
   call_later_on_absent_registered: Cell<bool>,
 
-  // Default, current, previous value and their gather function
+  // Default, current, previous value and their finalizer, and flag indicating whether the value
+  // is held across instants (instead of being reset to the default value)
   default_value: V,
+  holds_value: Cell<bool>,
   current_value: Cell<Option<V>>,
   previous_value: Cell<Option<V>>,
-  gather_value_function: Cell<Option<Box<FnMut(E, &mut V)>>>
+  finalizer: RefCell<Option<Finalizer<V>>>,
+
+  // Listeners called on each emission
+  emit_listeners: RefCell<Vec<EmitListener<V>>>,
+
+  // Per-instant taps, and flag indicating whether they are called by the runtime
+  taps: RefCell<Vec<SignalTap<V>>>,
+  taps_hooked: Cell<bool>,
+
+  // Actions to run the first time the signal is used by a runtime
+  on_bind: RefCell<Vec<BindAction>>,
+  is_bound: Cell<bool>,
+
+  // Name of the signal, for diagnostics
+  name: Cell<Option<&'static str>>
 }
 
 
-impl<V, E> SignalRuntime<V, E>
+impl<V> SignalRuntime<V>
 where
-  E: Clone,
   V: Clone
 {
-  /// Create a new `SignalRuntime`, with a default value of type `V`, and a gather function
-  /// receiving an element `E` and a mutable reference to the current value (of type `V`).
-  pub fn new(default_value: V, gather_value_function: Box<FnMut(E, &mut V)>) -> Self {
+  /// Create a new `SignalRuntime`, with a default value of type `V`.
+  pub fn new(default_value: V) -> Self {
     SignalRuntime {
       is_currently_emitted  : Cell::new(false),
+      last_emission_instant : Cell::new(None),
+      gathered_elements     : Cell::new(0),
 
       call_on_present: RefCell::new(Vec::new()),
       call_later_on_present: RefCell::new(Vec::new()),
@@ -52,9 +103,20 @@ where
       call_later_on_absent_registered: Cell::new(false),
 
       default_value: default_value.clone(),
+      holds_value: Cell::new(false),
       current_value: Cell::new(Some(default_value.clone())),
       previous_value: Cell::new(None),
-      gather_value_function: Cell::new(Some(gather_value_function))
+      finalizer: RefCell::new(None),
+
+      emit_listeners: RefCell::new(Vec::new()),
+
+      taps: RefCell::new(Vec::new()),
+      taps_hooked: Cell::new(false),
+
+      on_bind: RefCell::new(Vec::new()),
+      is_bound: Cell::new(false),
+
+      name: Cell::new(None)
     }
   }
 }
@@ -66,9 +128,11 @@ where
 /// Shared pointer to a signal runtime.
 ///
 /// It is meant to be shared and manipulated by all references to the same signal.
+/// It also holds the gather function of the signal, which receives the emitted elements.
 #[derive(Clone)]
 pub struct SignalRuntimeRef<V, E> {
-  runtime: Rc<SignalRuntime<V, E>>
+  runtime: Rc<SignalRuntime<V>>,
+  gather_value_function: SharedGatherFunction<V, E>
 }
 
 
@@ -79,21 +143,233 @@ where
 {
   /// Create a new `SignalRuntimeRef`, and its inner `SignalRuntime`,
   /// using the given default value and gather function.
-  /// See `new` method of `SignalRuntime` for more details.
-  pub fn new(default_value: V, gather_value_function: Box<FnMut(E, &mut V)>) -> Self {
-    SignalRuntimeRef { runtime: Rc::new(SignalRuntime::new(default_value, gather_value_function)) }
+  /// See `new_indexed` method for more details.
+  pub fn new(default_value: V, mut gather_value_function: GatherFunction<V, E>) -> Self {
+    Self::new_indexed(default_value, Box::new(move |e, v, _| gather_value_function(e, v)))
   }
 
-  /// Modify the current value of the signal runtime,
-  /// by applying the gather function to the given value.
-  fn gather_value(&self, value: E) {
-    let mut current_value         = self.runtime.current_value.take().unwrap();
-    let mut gather_value_function = self.runtime.gather_value_function.take().unwrap();
+  /// Create a new `SignalRuntimeRef`, and its inner `SignalRuntime`,
+  /// using the given default value and gather function, which receives an element `E`,
+  /// a mutable reference to the current value (of type `V`),
+  /// and the index of the instant of the emission.
+  pub fn new_indexed(default_value: V, gather_value_function: IndexedGatherFunction<V, E>) -> Self {
+    SignalRuntimeRef {
+      runtime: Rc::new(SignalRuntime::new(default_value)),
+      gather_value_function: Rc::new(RefCell::new(gather_value_function))
+    }
+  }
+
+  /// Create a new `SignalRuntimeRef`, and its inner `SignalRuntime`, for a *memory* signal:
+  /// unlike other signals, its value is not reset to `initial_value` at the end of each instant
+  /// during which it is emitted, but is held until next emission, and gathered into.
+  pub fn new_memory(initial_value: V, gather_value_function: GatherFunction<V, E>) -> Self {
+    let runtime_ref = Self::new(initial_value, gather_value_function);
+    runtime_ref.runtime.holds_value.set(true);
+
+    runtime_ref
+  }
+
+  /// Returns a reference to the same signal, whose emitted elements of type `E2` are mapped
+  /// through `f` before being gathered by the gather function of this reference.
+  pub fn map_elements<E2, F>(&self, mut f: F) -> SignalRuntimeRef<V, E2>
+  where
+    E2: Clone + 'static,
+    F: FnMut(E2) -> E + 'static
+  {
+    let gather_value_function = self.gather_value_function.clone();
+
+    SignalRuntimeRef {
+      runtime: self.runtime.clone(),
+      gather_value_function: Rc::new(RefCell::new(Box::new(move |e, v: &mut V, instant_index| {
+        (gather_value_function.borrow_mut())(f(e), v, instant_index);
+      })))
+    }
+  }
+
+  /// Returns the default value of the signal.
+  pub fn default_value(&self) -> V {
+    self.runtime.default_value.clone()
+  }
+
+  /// Returns the value of the signal during the last instant it has been emitted
+  /// (excluding current instant), or its default value if it has never been emitted.
+  pub fn last_value(&self) -> V {
+    let previous_value = self.runtime.previous_value.take();
+    self.runtime.previous_value.set(previous_value.clone());
+
+    previous_value.unwrap_or_else(|| self.default_value())
+  }
+
+  /// Returns the index of the last instant during which the signal has been emitted
+  /// (including current instant), if any.
+  pub fn last_emission_instant(&self) -> Option<usize> {
+    self.runtime.last_emission_instant.get()
+  }
+
+  /// Returns the name of the signal, if it has one.
+  pub fn name(&self) -> Option<&'static str> {
+    self.runtime.name.get()
+  }
+
+  /// Set the name of the signal, used for diagnostics (see `Describe`).
+  pub fn set_name(&self, name: &'static str) {
+    self.runtime.name.set(Some(name));
+  }
+
+  /// Returns the number of elements gathered by the signal during current instant.
+  pub fn gathered_elements(&self) -> usize {
+    self.runtime.gathered_elements.get()
+  }
+
+  /// Returns the number of continuations waiting for the signal to be present
+  /// during current instant (see `on_present`).
+  pub fn awaiting_presence(&self) -> usize {
+    self.runtime.call_on_present.borrow().len()
+  }
 
-    gather_value_function(value, &mut current_value);
+  /// Bind the signal to the given runtime. This is done automatically whenever the signal
+  /// is used by a runtime (i.e. emitted or awaited).
+  ///
+  /// The first time the signal is bound, it binds the signals it depends on (see `depends_on`).
+  /// The taps of the signal (see `on_each_instant`) are only called once it is bound.
+  pub fn bind(&self, runtime: &mut Runtime) {
+    if ! self.runtime.is_bound.get() {
+      self.runtime.is_bound.set(true);
+      self.add_state_reporter_to_runtime(runtime);
+
+      let on_bind = self.runtime.on_bind.replace(Vec::new());
+      for action in on_bind {
+        action(runtime);
+      }
+    }
+
+    if ! self.runtime.taps_hooked.get() && ! self.runtime.taps.borrow().is_empty() {
+      self.runtime.taps_hooked.set(true);
+      self.add_taps_to_runtime(runtime);
+    }
+  }
+
+  /// Declare that this signal depends on the `source` signal,
+  /// so that binding this signal to a runtime also binds the source.
+  ///
+  /// This is used by derived signals, whose source taps must be called as soon as
+  /// either the source or the derived signal is used.
+  pub fn depends_on<V2, E2>(&self, source: &SignalRuntimeRef<V2, E2>)
+  where
+    V2: Clone + 'static,
+    E2: Clone + 'static
+  {
+    let source = source.clone();
+    self.runtime.on_bind.borrow_mut().push(Box::new(move |r: &mut Runtime| source.bind(r)));
+  }
+
+  /// Register a tap, called at the end of each instant (once the signal has been bound to a
+  /// runtime), with the value of the signal if it has been emitted during this instant,
+  /// or with `None` otherwise.
+  ///
+  /// Taps are called once all end-of-instant tasks have been executed: a tap which wants to
+  /// react to the signal (e.g. by emitting another signal) must do so during next instant.
+  pub fn on_each_instant<F>(&self, tap: F) where F: FnMut(&mut Runtime, Option<&V>) + 'static {
+    self.runtime.taps.borrow_mut().push(Box::new(tap));
+  }
+
+  /// Set the finalizer of the signal, called once per instant during which the signal is present,
+  /// after all its emissions, with the value gathered during this instant.
+  /// It replaces any previous finalizer.
+  pub fn set_finalizer(&self, finalizer: Finalizer<V>) {
+    self.runtime.finalizer.replace(Some(finalizer));
+  }
 
-    self.runtime.gather_value_function.set(Some(gather_value_function));
+  /// Register a listener, called after each emission of the signal (in the same instant),
+  /// with the value gathered so far during current instant.
+  pub fn on_emit<F>(&self, listener: F) where F: FnMut(&mut Runtime, &V) + 'static {
+    self.runtime.emit_listeners.borrow_mut().push(Box::new(listener));
+  }
+
+  /// Call all emit listeners of the signal, with the value gathered so far.
+  fn call_emit_listeners(&self, runtime: &mut Runtime) {
+    if self.runtime.emit_listeners.borrow().is_empty() {
+      return;
+    }
+
+    let current_value = self.runtime.current_value.take().unwrap();
+    self.runtime.current_value.set(Some(current_value.clone()));
+
+    let mut listeners = self.runtime.emit_listeners.replace(Vec::new());
+    for listener in listeners.iter_mut() {
+      listener(runtime, &current_value);
+    }
+
+    // Listeners may have been registered by the listeners themselves
+    listeners.append(&mut self.runtime.emit_listeners.borrow_mut());
+    self.runtime.emit_listeners.replace(listeners);
+  }
+
+  /// Register a reporter describing the continuations awaiting the signal, if any
+  /// (see `Runtime::dump_state`). It does not keep the signal alive.
+  fn add_state_reporter_to_runtime(&self, runtime: &mut Runtime) {
+    let signal_runtime: Weak<SignalRuntime<V>> = Rc::downgrade(&self.runtime);
+
+    runtime.register_state_reporter(Box::new(move || {
+      let signal_runtime = signal_runtime.upgrade()?;
+      if signal_runtime.is_currently_emitted.get() {
+        return None;
+      }
+
+      let awaiting_presence = signal_runtime.call_on_present.borrow().len()
+                            + signal_runtime.call_later_on_present.borrow().len();
+      let awaiting_absence  = signal_runtime.call_later_on_absent.borrow().len();
+
+      if awaiting_presence + awaiting_absence == 0 {
+        return None;
+      }
+
+      Some(format!("signal {:p}: {} awaiting presence, {} awaiting absence",
+        Rc::as_ptr(&signal_runtime), awaiting_presence, awaiting_absence))
+    }));
+  }
+
+  /// Register a hook calling the taps of the signal at the end of each instant.
+  ///
+  /// The hook does not keep the signal alive: it unregisters itself once the signal is dropped.
+  fn add_taps_to_runtime(&self, runtime: &mut Runtime) {
+    let signal_runtime: Weak<SignalRuntime<V>> = Rc::downgrade(&self.runtime);
+
+    runtime.on_end_of_each_instant(Box::new(move |r: &mut Runtime| {
+      let signal_runtime = match signal_runtime.upgrade() {
+        Some(signal_runtime) => signal_runtime,
+        None                 => return false
+      };
+
+      let value = if signal_runtime.last_emission_instant.get() == Some(r.instant_index()) {
+        let value = signal_runtime.previous_value.take();
+        signal_runtime.previous_value.set(value.clone());
+        value
+      }
+      else {
+        None
+      };
+
+      let mut taps = signal_runtime.taps.replace(Vec::new());
+      for tap in taps.iter_mut() {
+        tap(r, value.as_ref());
+      }
+
+      // Taps may have been registered by the taps themselves
+      taps.append(&mut signal_runtime.taps.borrow_mut());
+      signal_runtime.taps.replace(taps);
+
+      true
+    }));
+  }
+
+  /// Modify the current value of the signal runtime,
+  /// by applying the gather function to the given value, emitted during instant `instant_index`.
+  fn gather_value(&self, value: E, instant_index: usize) {
+    let mut current_value = self.runtime.current_value.take().unwrap();
+    (self.gather_value_function.borrow_mut())(value, &mut current_value, instant_index);
     self.runtime.current_value.set(Some(current_value));
+    self.runtime.gathered_elements.set(self.runtime.gathered_elements.get() + 1);
   }
 
   /// Add a continuation to the end of current instant for updating the signal.
@@ -102,26 +378,44 @@ where
     let signal_runtime_ref = self.clone();
     let signal_runtime     = self.runtime.clone();
 
-    runtime.on_end_of_instant(Box::new(move |r: &mut Runtime, v: ()| {
-      let signal_has_been_emitted = signal_runtime_ref.runtime.is_currently_emitted.get();
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+      debug_assert_eq!(r.phase(), Phase::EndOfInstant, "signal updated outside of the end of an instant");
       signal_runtime_ref.runtime.is_currently_emitted.set(false);
+      signal_runtime_ref.runtime.gathered_elements.set(0);
 
       // Those continuations now are useless
       signal_runtime_ref.runtime.call_on_present.borrow_mut().clear();
       signal_runtime_ref.runtime.call_later_on_present.borrow_mut().clear();
 
-      signal_runtime.previous_value.replace(signal_runtime.current_value.take());
-      signal_runtime.current_value.set(Some(signal_runtime.default_value.clone()));
-    }));
+      // The value is finalized once all emissions of the instant are gathered
+      let mut current_value = signal_runtime.current_value.take();
+      if let Some(ref mut value) = current_value {
+        if let Some(finalizer) = signal_runtime.finalizer.borrow_mut().as_mut() {
+          finalizer(value);
+        }
+      }
+
+      // Memory signals start gathering from their held value during next instants
+      let next_value = if signal_runtime.holds_value.get() {
+        current_value.clone()
+      }
+      else {
+        Some(signal_runtime.default_value.clone())
+      };
+
+      signal_runtime.previous_value.replace(current_value);
+      signal_runtime.current_value.set(next_value);
+    });
   }
 
   /// Add all continuations stored in the `on_present_continuations` field of the signal runtime
   /// to current instant.
   fn add_on_present_continuations_to_runtime(&self, runtime: &mut Runtime) {
-    let mut on_present_continuations = self.runtime.call_on_present.borrow_mut();
-    for boxed_continuation in on_present_continuations.drain(..) {
-      runtime.on_current_instant(boxed_continuation);
-    }
+    let on_present_continuations = self.runtime.call_on_present.replace(Vec::new())
+      .into_iter()
+      .map(|(_, c)| c)
+      .collect();
+    runtime.on_current_instant_batch(on_present_continuations);
   }
 
   /// Add all continuations stored in the `later_on_present_continuations`
@@ -134,57 +428,78 @@ where
     for boxed_continuation in later_on_present_continuations.drain(..) {
       let signal_runtime_ref = self.clone();
 
-      runtime.on_next_instant(Box::new(move |r: &mut Runtime, v: ()| {
+      runtime.schedule_next(move |r: &mut Runtime, _: ()| {
         let previous_value = signal_runtime_ref.runtime.previous_value.take().unwrap();
         signal_runtime_ref.runtime.previous_value.set(Some(previous_value.clone()));
 
         boxed_continuation.call_box(r, previous_value.clone());
-      }));
+      });
     }
   }
 
   /// Add all continuations stored in the `later_on_absent_continuations` field of the signal runtime
-  /// to next instant.
+  /// to next instant, in registration order.
   fn add_later_on_absent_continuations_to_runtime(&self, runtime: &mut Runtime) {
-    let mut later_on_absent_continuations = self.runtime.call_later_on_absent.borrow_mut();
-    for boxed_continuation in later_on_absent_continuations.drain(..) {
-      runtime.on_next_instant(boxed_continuation);
-    }
+    let later_on_absent_continuations = self.runtime.call_later_on_absent.replace(Vec::new());
+    self.runtime.call_later_on_absent_registered.set(false);
+
+    runtime.on_next_instant_batch(later_on_absent_continuations);
   }
 
 
   /// Emit the signal during current instant.
   ///
-  /// It updates the state of the signal runtime, gather the given value,
-  /// drop any pending continuations to run if the signal was absent,
-  /// and add all pending continuations to run if the signal is present to the runtime.
-  pub fn emit(self, mut runtime: &mut Runtime, value: E) {
-    if self.runtime.is_currently_emitted.get() {
-      return;
+  /// It gathers the given value, and if the signal was not emitted yet during current instant,
+  /// updates the state of the signal runtime, drop any pending continuations to run if the
+  /// signal was absent, and add all pending continuations to run if the signal is present
+  /// to the runtime. Finally, it calls the emit listeners of the signal.
+  pub fn emit(self, runtime: &mut Runtime, value: E) {
+    debug_assert_ne!(runtime.phase(), Phase::EndOfInstant, "signal emitted at the end of an instant");
+    self.bind(runtime);
+    self.gather_value(value, runtime.instant_index());
+
+    if ! self.runtime.is_currently_emitted.get() {
+      self.runtime.is_currently_emitted.set(true);
+      self.runtime.last_emission_instant.set(Some(runtime.instant_index()));
+
+      // Empty the list of continuations to execute during next instant if there is *no* signal
+      self.runtime.call_later_on_absent.borrow_mut().clear();
+      self.runtime.call_later_on_absent_registered.set(false);
+
+      // Waiting continuations are bound to the cancellation context of whoever registered them,
+      // hence they (and the signal update) must not be bound to the context of the emitter
+      runtime.with_cancel_handle(None, |r| {
+        self.add_update_on_end_of_instant(r);
+
+        // Add awaiting continuations to current instant
+        self.add_on_present_continuations_to_runtime(r);
+        self.add_later_on_present_continuations_to_runtime(r);
+      });
     }
 
-    self.runtime.is_currently_emitted.set(true);
-    self.add_update_on_end_of_instant(runtime);
-
-    self.gather_value(value);
-
-    // Empty the list of continuations to execute during next instant if there is *no* signal
-    self.runtime.call_later_on_absent.borrow_mut().clear();
-    self.runtime.call_later_on_absent_registered.set(false);
-
-    // Add awaiting continuations to current instant
-    self.add_on_present_continuations_to_runtime(runtime);
-    self.add_later_on_present_continuations_to_runtime(runtime);
+    self.call_emit_listeners(runtime);
   }
 
   /// Register a continuation to run during current instant
   /// if the signal is present during current instant.
+  ///
+  /// Continuations run in registration order, whether the signal was already emitted
+  /// when they were registered or not.
   pub fn on_present<C>(self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    self.bind(runtime);
+
     if self.runtime.is_currently_emitted.get() {
-      runtime.on_current_instant(Box::new(c));
+      runtime.schedule_current(c);
     }
     else {
-      self.runtime.call_on_present.borrow_mut().push(Box::new(c));
+      let handle = runtime.cancel_handle();
+      let c = runtime.bind_to_cancel_handle(c);
+
+      // Waiters whose context has been cancelled would never run: drop them now,
+      // so that a signal awaited in a loop does not accumulate them
+      let mut call_on_present = self.runtime.call_on_present.borrow_mut();
+      call_on_present.retain(|(h, _)| !h.as_ref().is_some_and(|h| h.is_cancelled()));
+      call_on_present.push((handle, c));
     }
   }
 
@@ -192,37 +507,48 @@ where
   /// if the signal is present during current instant.
   ///
   /// If executed, the continuation will be given the previous value of the signal.
+  /// Continuations run in registration order.
   pub fn later_on_present<C>(self, runtime: &mut Runtime, c: C) where C: Continuation<V> {
+    self.bind(runtime);
+
     if self.runtime.is_currently_emitted.get() {
-      runtime.on_next_instant(Box::new(move |r: &mut Runtime, v: ()| {
+      runtime.schedule_next(move |r: &mut Runtime, _: ()| {
         let previous_value = self.runtime.previous_value.take().unwrap();
         self.runtime.previous_value.set(Some(previous_value.clone()));
 
         c.call(r, previous_value.clone());
-      }));
+      });
     }
     else {
-      self.runtime.call_later_on_present.borrow_mut().push(Box::new(c));
+      let c = runtime.bind_to_cancel_handle(c);
+      self.runtime.call_later_on_present.borrow_mut().push(c);
     }
   }
 
-  /// Register a continuation to run during current instant
+  /// Register a continuation to run during next instant
   /// if the signal is absent during current instant.
+  ///
+  /// Continuations registered during the same instant run in registration order.
   pub fn later_on_absent<C>(self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    self.bind(runtime);
+
     if self.runtime.is_currently_emitted.get() {
       return;
     }
-    else {
-      self.runtime.call_later_on_absent.borrow_mut().push(Box::new(c));
 
-      if ! self.runtime.call_later_on_absent_registered.get() {
-        let signal_runtime_ref = self.clone();
-        runtime.on_next_instant(Box::new(move |r: &mut Runtime, v: ()| {
+    let c = runtime.bind_to_cancel_handle(c);
+    self.runtime.call_later_on_absent.borrow_mut().push(c);
+
+    // The absence of the signal is known at the end of current instant
+    if ! self.runtime.call_later_on_absent_registered.get() {
+      let signal_runtime_ref = self.clone();
+      runtime.with_cancel_handle(None, |r| {
+        r.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
           signal_runtime_ref.add_later_on_absent_continuations_to_runtime(r);
-        }));
+        });
+      });
 
-        self.runtime.call_later_on_absent_registered.set(true);
-      }
+      self.runtime.call_later_on_absent_registered.set(true);
     }
   }
 
@@ -0,0 +1,84 @@
+use runtime::Runtime;
+use continuations::Continuation;
+use signals::runtime::SignalRuntimeRef;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL CORE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Core of a signal, i.e. everything the processes built from a signal rely on
+/// (see `SignalHandle`).
+///
+/// It is implemented by `SignalRuntimeRef`, the core of the signals of this crate, and can be
+/// implemented by other kinds of signals. An implementation must honor the following contract,
+/// on which the timing of the signal processes relies:
+///
+/// * A signal is *present* during an instant if it is emitted during this instant
+///   (an implementation may also consider it present during other instants, e.g. once resolved),
+///   and *absent* otherwise. Absence is only known once the instant is over.
+/// * Continuations must be bound to the cancellation context of the runtime at the moment they
+///   are registered (see `Runtime::bind_to_cancel_handle`), and continuations registered with the
+///   same method must run in registration order.
+/// * Continuations must run outside of the cancellation context of the emitter
+///   (see `Runtime::with_cancel_handle`).
+pub trait SignalCore<V, E>: Clone + 'static {
+  /// Emits the signal with the given element during current instant,
+  /// which wakes the continuations waiting for the presence of the signal up.
+  fn emit_element(&self, runtime: &mut Runtime, element: E);
+
+  /// Registers a continuation to run during the first instant the signal is present, starting
+  /// from current instant: immediately if it has already been emitted, or when it is emitted.
+  fn register_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()>;
+
+  /// Registers a continuation to run during the instant following the first instant the signal
+  /// is present, starting from current instant (whether it has already been emitted or not).
+  /// The continuation receives the value of the signal during the instant it is present,
+  /// i.e. `delivered_value` as of the instant the continuation runs.
+  fn register_later_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<V>;
+
+  /// Registers a continuation to run during next instant if the signal is absent during
+  /// current instant. If the signal has already been emitted, or is emitted later during
+  /// current instant, the continuation is dropped.
+  fn register_later_on_absent<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()>;
+
+  /// Returns the value of the signal during the last instant it has been present
+  /// (excluding current instant), or a default value if there is none.
+  fn delivered_value(&self) -> V;
+
+  /// Returns the name of the signal, if it has one (see `Describe`).
+  fn name(&self) -> Option<&'static str> {
+    None
+  }
+}
+
+
+impl<V, E> SignalCore<V, E> for SignalRuntimeRef<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  fn emit_element(&self, runtime: &mut Runtime, element: E) {
+    self.clone().emit(runtime, element);
+  }
+
+  fn register_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    self.clone().on_present(runtime, c);
+  }
+
+  fn register_later_on_present<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<V> {
+    self.clone().later_on_present(runtime, c);
+  }
+
+  fn register_later_on_absent<C>(&self, runtime: &mut Runtime, c: C) where C: Continuation<()> {
+    self.clone().later_on_absent(runtime, c);
+  }
+
+  fn delivered_value(&self) -> V {
+    self.last_value()
+  }
+
+  fn name(&self) -> Option<&'static str> {
+    SignalRuntimeRef::name(self)
+  }
+}
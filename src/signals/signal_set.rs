@@ -0,0 +1,295 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use runtime::Runtime;
+use continuations::Continuation;
+use processes::{Process, ProcessMut};
+use describe::{Describe, ProcessShape};
+use signals::signals::SignalHandle;
+use signals::pure_signal::PureSignal;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL SET
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shared state of a signal set, updated by the emissions of its signals.
+///
+/// It acts as a dispatcher: whatever the number of signals of the set, each consumer
+/// only registers one continuation, called once per instant during which a signal is present.
+struct SignalSetState {
+  // Presence of each signal during the last instant any of them has been emitted
+  present: Vec<bool>,
+  instant: Option<usize>,
+
+  // Continuations awaiting the presence of any signal of the set
+  waiters: Vec<Box<dyn Continuation<Vec<usize>>>>
+}
+
+
+impl SignalSetState {
+  /// Returns the indices of the signals present during instant `instant_index`.
+  fn present_indices(&self, instant_index: usize) -> Vec<usize> {
+    if self.instant != Some(instant_index) {
+      return Vec::new();
+    }
+
+    self.present.iter().enumerate().filter(|&(_, &present)| present).map(|(i, _)| i).collect()
+  }
+}
+
+type SignalSetStateRef = Rc<RefCell<SignalSetState>>;
+
+
+/// Collection of pure signals, providing bulk operations over them.
+#[derive(Clone)]
+pub struct SignalSet {
+  signals: Vec<PureSignal>,
+  state  : SignalSetStateRef
+}
+
+
+impl SignalSet {
+  /// Create a new `SignalSet`, made of `n` new pure signals.
+  pub fn new(n: usize) -> Self {
+    let signals = (0..n).map(|_| PureSignal::new()).collect::<Vec<_>>();
+    let state   = Rc::new(RefCell::new(SignalSetState {
+      present: vec![false; n],
+      instant: None,
+      waiters: Vec::new()
+    }));
+
+    for (i, signal) in signals.iter().enumerate() {
+      let state = state.clone();
+      signal.clone().runtime().on_emit(move |r: &mut Runtime, _: &()| mark_present(r, &state, i));
+    }
+
+    SignalSet { signals, state }
+  }
+
+  /// Returns the number of signals of the set.
+  pub fn len(&self) -> usize {
+    self.signals.len()
+  }
+
+  /// Returns whether the set contains no signal.
+  pub fn is_empty(&self) -> bool {
+    self.signals.is_empty()
+  }
+
+  /// Returns the signal of index `i`.
+  pub fn signal(&self, i: usize) -> PureSignal {
+    self.signals[i].clone()
+  }
+
+  /// Returns a process which emits the signals of the given indices during current instant.
+  pub fn emit_indices(&self, indices: &[usize]) -> EmitIndicesProcess {
+    EmitIndicesProcess { signals: indices.iter().map(|&i| self.signal(i)).collect() }
+  }
+
+  /// Returns a process which waits for any signal of the set to be emitted,
+  /// and returns the (sorted) indices of all the signals present during this instant.
+  /// Like `await`, it returns during the instant following the emissions.
+  pub fn await_any_of(&self) -> AwaitAnyOfProcess {
+    AwaitAnyOfProcess { state: self.state.clone() }
+  }
+
+  /// Returns a process which returns the presence of each signal of the set
+  /// during current instant. Since absence is only known at the end of an instant,
+  /// it returns during next instant.
+  pub fn present_mask(&self) -> PresentMaskProcess {
+    PresentMaskProcess { state: self.state.clone() }
+  }
+}
+
+
+/// Mark the signal of index `i` as present during current instant.
+///
+/// The first time a signal of the set is emitted during an instant, the presence of all signals
+/// is reset, and a task is added to the end of the instant to wake the waiters of the set up.
+fn mark_present(runtime: &mut Runtime, state: &SignalSetStateRef, i: usize) {
+  let instant_index = runtime.instant_index();
+  let mut s = state.borrow_mut();
+
+  if s.instant != Some(instant_index) {
+    for present in s.present.iter_mut() {
+      *present = false;
+    }
+    s.instant = Some(instant_index);
+
+    // Waiters are bound to the cancellation context of whoever registered them
+    let state = state.clone();
+    runtime.with_cancel_handle(None, |r| {
+      r.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+        let indices = state.borrow().present_indices(r.instant_index());
+        let waiters = ::std::mem::take(&mut state.borrow_mut().waiters);
+
+        for waiter in waiters {
+          let indices = indices.clone();
+          r.schedule_next(move |r: &mut Runtime, _: ()| waiter.call_box(r, indices));
+        }
+      });
+    });
+  }
+
+  s.present[i] = true;
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// EMIT INDICES PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process emitting several signals of a set.
+pub struct EmitIndicesProcess {
+  signals: Vec<PureSignal>
+}
+
+
+impl Process for EmitIndicesProcess {
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    for signal in self.signals {
+      signal.runtime().emit(runtime, ());
+    }
+
+    next.call(runtime, ());
+  }
+}
+
+
+impl ProcessMut for EmitIndicesProcess {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    for signal in self.signals.iter() {
+      signal.clone().runtime().emit(runtime, ());
+    }
+
+    next.call(runtime, (self, ()));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// AWAIT ANY OF PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process awaiting for any signal of a set to be emitted.
+pub struct AwaitAnyOfProcess {
+  state: SignalSetStateRef
+}
+
+
+impl Process for AwaitAnyOfProcess {
+  type Value = Vec<usize>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let next = runtime.bind_to_cancel_handle(next);
+    self.state.borrow_mut().waiters.push(next);
+  }
+}
+
+
+impl ProcessMut for AwaitAnyOfProcess {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let state = self.state.clone();
+    self.call(runtime, move |r: &mut Runtime, indices: Vec<usize>| {
+      next.call(r, (AwaitAnyOfProcess { state }, indices));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PRESENT MASK PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process returning the presence of each signal of a set during current instant.
+pub struct PresentMaskProcess {
+  state: SignalSetStateRef
+}
+
+
+impl Process for PresentMaskProcess {
+  type Value = Vec<bool>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let state = self.state;
+
+    // No signal can be emitted at the end of an instant: the presence of the signals is known
+    runtime.schedule_end_of_instant(move |r: &mut Runtime, _: ()| {
+      let mut mask = vec![false; state.borrow().present.len()];
+      for i in state.borrow().present_indices(r.instant_index()) {
+        mask[i] = true;
+      }
+
+      r.schedule_next(move |r: &mut Runtime, _: ()| next.call(r, mask));
+    });
+  }
+}
+
+
+impl ProcessMut for PresentMaskProcess {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let state = self.state.clone();
+    self.call(runtime, move |r: &mut Runtime, mask: Vec<bool>| {
+      next.call(r, (PresentMaskProcess { state }, mask));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DESCRIBE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl Describe for EmitIndicesProcess {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "EmitIndices" }
+  }
+}
+
+impl Describe for AwaitAnyOfProcess {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "AwaitAnyOf" }
+  }
+}
+
+impl Describe for PresentMaskProcess {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "PresentMask" }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use super::*;
+
+
+  #[test]
+  fn await_indices_emitted_in_one_instant () {
+    let signal_set = SignalSet::new(16);
+
+    let consumer = signal_set.await_any_of().join(signal_set.present_mask());
+    let emitter  = value(()).pause().and_then(move |_| signal_set.emit_indices(&[9, 2, 5]));
+
+    // The mask is taken during the first instant, during which no signal is emitted
+    let ((indices, mask), _) = execute_process(consumer.join(emitter));
+    assert_eq!(indices, vec![2, 5, 9]);
+    assert_eq!(mask, vec![false; 16]);
+  }
+
+  #[test]
+  fn present_mask_of_current_instant () {
+    let signal_set = SignalSet::new(4);
+
+    let mask = signal_set.emit_indices(&[1, 3]).join(signal_set.present_mask()).map(|(_, mask)| mask);
+    assert_eq!(execute_process(mask), vec![false, true, false, true]);
+  }
+}
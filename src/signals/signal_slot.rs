@@ -0,0 +1,142 @@
+use std::rc::Rc;
+use std::cell::RefCell;
+
+use signals::signals::{SignalHandle, ObservableSignal, EmittableSignal};
+use signals::runtime::SignalRuntimeRef;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL SLOT
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Placeholder for a signal which is bound later, usable wherever a signal is expected.
+///
+/// This allows to define processes referencing a signal before the signal itself,
+/// e.g. for mutually recursive processes. The slot must be bound (see `bind`) before any of
+/// those processes is executed: using an unbound slot panics.
+pub struct SignalSlot<V, E> {
+  runtime_ref: Rc<RefCell<Option<SignalRuntimeRef<V, E>>>>
+}
+
+
+impl<V, E> SignalSlot<V, E>
+where
+  V: Clone,
+  E: Clone
+{
+  /// Create a new, unbound `SignalSlot`.
+  pub fn new() -> Self {
+    SignalSlot { runtime_ref: Rc::new(RefCell::new(None)) }
+  }
+
+  /// Bind the slot (and all its clones) to the given signal.
+  ///
+  /// Panics if the slot has already been bound.
+  pub fn bind<S>(&self, signal: S) where S: SignalHandle<Value = V, Element = E, Core = SignalRuntimeRef<V, E>> {
+    let mut runtime_ref = self.runtime_ref.borrow_mut();
+    assert!(runtime_ref.is_none(), "signal slot bound more than once");
+
+    *runtime_ref = Some(signal.runtime());
+  }
+
+  /// Returns whether the slot has been bound to a signal.
+  pub fn is_bound(&self) -> bool {
+    self.runtime_ref.borrow().is_some()
+  }
+}
+
+
+impl<V, E> Clone for SignalSlot<V, E> {
+  fn clone(&self) -> Self {
+    SignalSlot { runtime_ref: self.runtime_ref.clone() }
+  }
+}
+
+
+impl<V, E> Default for SignalSlot<V, E>
+where
+  V: Clone,
+  E: Clone
+{
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+impl<V, E> SignalHandle for SignalSlot<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  type Value   = V;
+  type Element = E;
+  type Core    = SignalRuntimeRef<V, E>;
+
+  fn core(self) -> SignalRuntimeRef<V, E> {
+    match *self.runtime_ref.borrow() {
+      Some(ref runtime_ref) => runtime_ref.clone(),
+      None                  => panic!("signal slot used before being bound")
+    }
+  }
+}
+
+
+impl<V, E> ObservableSignal for SignalSlot<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{}
+
+
+impl<V, E> EmittableSignal for SignalSlot<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use signals::pure_signal::PureSignal;
+  use super::*;
+
+
+  #[test]
+  fn ping_pong_through_slots () {
+    let slot_a = SignalSlot::new();
+    let slot_b = SignalSlot::new();
+
+    // Each loop awaits its own signal, and then emits the signal of the other loop
+    let slot_a_1 = slot_a.clone();
+    let slot_b_1 = slot_b.clone();
+    let pong_a   = move |_: ()| instant_index().join(slot_b_1.clone().emit_value(()));
+    let pong_b   = move |_: ()| instant_index().join(slot_a_1.clone().emit_value(()));
+
+    let loop_a = slot_a.clone().await().and_then(pong_a).take(5);
+    let loop_b = slot_b.clone().await().and_then(pong_b).take(5);
+    let start  = slot_a.clone().emit_value(());
+
+    // Signals are only created once both loops are defined
+    slot_a.bind(PureSignal::new());
+    slot_b.bind(PureSignal::new());
+
+    let ((runs_a, runs_b), _) = execute_process(loop_a.join(loop_b).join(start));
+    let instants = |runs: Vec<(usize, ())>| runs.into_iter().map(|(i, _)| i).collect::<Vec<_>>();
+
+    assert_eq!(instants(runs_a), vec![1, 3, 5, 7, 9]);
+    assert_eq!(instants(runs_b), vec![2, 4, 6, 8, 10]);
+  }
+
+  #[test]
+  #[should_panic(expected = "signal slot used before being bound")]
+  fn use_unbound_slot () {
+    let slot: SignalSlot<(), ()> = SignalSlot::new();
+    execute_process(slot.await());
+  }
+}
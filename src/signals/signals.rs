@@ -1,118 +1,318 @@
 use std::rc::Rc;
 use std::cell::*;
-use std::marker::PhantomData;
 
 use runtime::Runtime;
-use continuations::Continuation;
-use processes::{Process, ProcessMut};
+use continuations::{Continuation, CancelHandle};
+use processes::{Process, ProcessMut, ValueProcess, value, preempt_at_end_of_instant};
 use signals::runtime::SignalRuntimeRef;
+use signals::signal_core::SignalCore;
+use describe::{Describe, ProcessShape};
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // SIGNAL
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Reactive signal.
+/// Handle to a signal, giving access to its core.
 ///
-/// It provides various methods for creating processes related to the signal.
-/// It only requires to implement the `runtime` method, in order to access the related `SignalRuntimeRef`.
-pub trait Signal<V, E>
-where
-  Self: Clone,
-  V: Clone,
-  E: Clone
-{
-  /// Returns a reference to the signal's runtime.
-  fn runtime(self) -> SignalRuntimeRef<V, E>;
-
-  /// Emit the signal with the given value.
-  fn emit_value(self, value: E) -> EmitProcess<Self, V, E> {
-    EmitProcess { signal: Box::new(self), value: value, phantom: PhantomData }
+/// It only requires to implement the `core` method, in order to access the core of the signal
+/// (see `SignalCore`), which is a `SignalRuntimeRef` for the signals of this crate.
+/// The processes which can be built from a handle are provided by the `ObservableSignal` trait
+/// (for observing the signal) and by the `EmittableSignal` trait (for emitting it).
+pub trait SignalHandle: Clone {
+  /// Type of the values of the signal.
+  type Value: Clone + 'static;
+
+  /// Type of the elements the signal is emitted with.
+  type Element: Clone + 'static;
+
+  /// Type of the core of the signal.
+  type Core: SignalCore<Self::Value, Self::Element>;
+
+  /// Returns the core of the signal.
+  fn core(self) -> Self::Core;
+
+  /// Returns a reference to the signal's runtime, for signals whose core is a `SignalRuntimeRef`.
+  fn runtime(self) -> SignalRuntimeOf<Self> where Self: SignalHandle<Core = SignalRuntimeOf<Self>> {
+    self.core()
   }
+}
 
+
+/// Type of the `SignalRuntimeRef` holding the values and elements of the signals of type `S`.
+pub type SignalRuntimeOf<S> = SignalRuntimeRef<<S as SignalHandle>::Value, <S as SignalHandle>::Element>;
+
+
+/// Observable signal.
+///
+/// It provides various methods for creating processes observing the signal, i.e. awaiting it or
+/// testing its presence, but none for emitting it (see `SignalReader`).
+pub trait ObservableSignal: SignalHandle {
   /// Return a process which waits for the signal to be emitted,
   /// and run on next instant if it does.
-  fn await(self) -> AwaitProcess<Self, V, E>
-  where
-    Self: Sized + 'static
-  {
-    AwaitProcess { signal: Box::new(self), phantom: PhantomData }
+  ///
+  /// Processes awaiting the same signal are woken up in the order they started awaiting it.
+  fn await(self) -> AwaitProcess<Self> where Self: 'static {
+    AwaitProcess { signal: Box::new(self) }
   }
 
   /// Return a process which waits for the signal to be emitted,
   /// and run on current instant if it does.
-  fn await_immediate(self) -> AwaitImmediateProcess<Self, V, E>
-  where
-    Self: Sized + 'static
-  {
-    AwaitImmediateProcess { signal: Box::new(self), phantom: PhantomData }
+  ///
+  /// Processes awaiting the same signal are woken up in the order they started awaiting it,
+  /// including when the signal has already been emitted.
+  fn await_immediate(self) -> AwaitImmediateProcess<Self> where Self: 'static {
+    AwaitImmediateProcess { signal: Box::new(self) }
   }
 
   /// Return a process which waits for the signal to be emitted, and either:
   ///
   /// * run `process_if` on current instant if the signal is emitted;
   /// * run `process_else` on next instant if the signal is **not** emitted.
-  fn present<P1, P2, PV>(self, process_if: P1, process_else: P2) -> PresentProcess<Self, P1, P2, PV, V, E>
+  ///
+  /// Like for `await` and `await_immediate`, processes testing the same signal run their
+  /// branches in the order they started testing it.
+  fn present<P1, P2>(self, process_if: P1, process_else: P2) -> PresentProcess<Self, P1, P2>
   where
-    Self: Sized + 'static,
-    P1: Process<Value = PV>,
-    P2: Process<Value = PV>,
-    V: 'static
+    Self: 'static,
+    P1: Process,
+    P2: Process<Value = P1::Value>
   {
     PresentProcess {
       signal      : Box::new(self),
-      process_if  : process_if,
-      process_else: process_else,
-      phantom: PhantomData
+      process_if,
+      process_else
     }
   }
+
+  /// Return a process which, each time the signal is emitted, cancels the process started
+  /// for the previous emission (if it is still running), and starts a new process built by
+  /// `function` from the value of the signal, during next instant.
+  ///
+  /// The resulting process never terminates.
+  fn switch_map<F, P>(self, function: F) -> SwitchMapProcess<Self, F>
+  where
+    Self: 'static,
+    F: FnMut(Self::Value) -> P + 'static,
+    P: Process
+  {
+    SwitchMapProcess { signal: Box::new(self), function }
+  }
+
+  /// Return a process which returns whether the signal is present: `true` during current instant
+  /// if the signal is emitted, `false` during next instant otherwise.
+  #[allow(clippy::wrong_self_convention)]
+  fn is_present(self) -> PresentProcess<Self, ValueProcess<bool>, ValueProcess<bool>> where Self: 'static {
+    self.present(value(true), value(false))
+  }
+
+  /// Return a process which returns the value of the signal during the last instant
+  /// it has been emitted (excluding current instant), or its default value
+  /// if it has never been emitted.
+  fn pre(self) -> PreProcess<Self> where Self: 'static {
+    PreProcess { signal: Box::new(self) }
+  }
+}
+
+
+/// Emittable signal.
+///
+/// It provides the methods for emitting the signal, but none for observing it.
+pub trait EmittableSignal: SignalHandle {
+  /// Emit the signal with the given value.
+  fn emit_value(self, value: Self::Element) -> EmitProcess<Self> {
+    EmitProcess { signal: Box::new(self), value }
+  }
+
+  /// Emit the signal with the given value from host code, i.e. outside of any process.
+  ///
+  /// When called between two instants (e.g. from the callback of `Runtime::execute_interactive`),
+  /// the signal is emitted during the instant the runtime is about to execute.
+  fn emit_from_host(self, runtime: &mut Runtime, value: Self::Element) {
+    self.core().emit_element(runtime, value);
+  }
+
+  /// Returns a write-only handle to the signal, which cannot be used to observe it.
+  fn emitter(self) -> SignalEmitter<Self::Element> where Self::Core: 'static {
+    let core = self.core();
+    SignalEmitter { emit: Rc::new(move |r: &mut Runtime, e: Self::Element| core.emit_element(r, e)) }
+  }
 }
 
 
+/// Reactive signal, which can be both observed and emitted.
+///
+/// It is implemented by any signal implementing `ObservableSignal` and `EmittableSignal`.
+pub trait Signal: ObservableSignal + EmittableSignal {}
+
+
+impl<S> Signal for S where S: ObservableSignal + EmittableSignal {}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
-// AWAIT
+// SIGNAL READER
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 
-/// Process awaiting for a signal to be emitted, and running during next instant if it does.
+/// Read-only handle to a signal: it can be awaited or tested, but not emitted.
 #[derive(Clone)]
-pub struct AwaitProcess<S, V, E>
+pub struct SignalReader<V, E> {
+  runtime_ref: SignalRuntimeRef<V, E>
+}
+
+
+impl<V, E> SignalReader<V, E> {
+  /// Create a new `SignalReader` observing the signal of the given runtime.
+  pub fn new(runtime_ref: SignalRuntimeRef<V, E>) -> Self {
+    SignalReader { runtime_ref }
+  }
+}
+
+
+impl<V, E> SignalHandle for SignalReader<V, E>
 where
-  S: Signal<V, E> + Sized + Clone,
   V: Clone + 'static,
   E: Clone + 'static
 {
-  signal: Box<S>,
-  phantom: PhantomData<(V, E)>
+  type Value   = V;
+  type Element = E;
+  type Core    = SignalRuntimeRef<V, E>;
+
+  fn core(self) -> SignalRuntimeRef<V, E> {
+    self.runtime_ref
+  }
 }
 
 
-impl<S, V, E> Process for AwaitProcess<S, V, E>
+impl<V, E> ObservableSignal for SignalReader<V, E>
 where
-  S: Signal<V, E> + Sized + 'static,
   V: Clone + 'static,
   E: Clone + 'static
-{
-  type Value = V;
+{}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL EMITTER
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Shared function emitting a signal with a given element.
+type EmitFunction<E> = Rc<dyn Fn(&mut Runtime, E)>;
+
+/// Write-only handle to a signal (see `EmittableSignal::emitter`): it can be emitted,
+/// but not awaited or tested. Only the type of the elements it emits is exposed.
+pub struct SignalEmitter<E> {
+  emit: EmitFunction<E>
+}
+
+
+impl<E> Clone for SignalEmitter<E> {
+  fn clone(&self) -> Self {
+    SignalEmitter { emit: self.emit.clone() }
+  }
+}
+
+
+impl<E> SignalEmitter<E> where E: 'static {
+  /// Returns a process emitting the signal with the given value.
+  pub fn emit_value(&self, value: E) -> EmitterProcess<E> where E: Clone {
+    EmitterProcess { emitter: self.clone(), value }
+  }
+
+  /// Returns a process emitting the signal with the value returned by `f`,
+  /// called each time the process is ran.
+  pub fn emit_with<F>(&self, f: F) -> EmitWithProcess<E, F> where F: FnMut() -> E {
+    EmitWithProcess { emitter: self.clone(), function: f }
+  }
+
+  /// Emit the signal with the given value from host code, i.e. outside of any process
+  /// (see `EmittableSignal::emit_from_host`).
+  pub fn emit_now(&self, runtime: &mut Runtime, value: E) {
+    (self.emit)(runtime, value);
+  }
+}
+
+
+/// Process emitting a signal through a `SignalEmitter` with the given value.
+#[derive(Clone)]
+pub struct EmitterProcess<E> {
+  emitter: SignalEmitter<E>,
+  value  : E
+}
+
+
+impl<E> Process for EmitterProcess<E> where E: Clone + 'static {
+  type Value = ();
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    self.signal.runtime().later_on_present(runtime, next);
+    self.emitter.emit_now(runtime, self.value);
+    next.call(runtime, ());
   }
 }
 
 
-impl<S, V, E> ProcessMut for AwaitProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + Clone + 'static,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+impl<E> ProcessMut for EmitterProcess<E> where E: Clone + 'static {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    self.emitter.emit_now(runtime, self.value.clone());
+    next.call(runtime, (self, ()));
+  }
+}
+
+
+/// Process emitting a signal through a `SignalEmitter`,
+/// with the value returned by a function each time it is ran.
+#[derive(Clone)]
+pub struct EmitWithProcess<E, F> {
+  emitter : SignalEmitter<E>,
+  function: F
+}
+
+
+impl<E, F> Process for EmitWithProcess<E, F> where E: 'static, F: FnMut() -> E + 'static {
+  type Value = ();
+
+  fn call<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let value = (self.function)();
+    self.emitter.emit_now(runtime, value);
+    next.call(runtime, ());
+  }
+}
+
+
+impl<E, F> ProcessMut for EmitWithProcess<E, F> where E: 'static, F: FnMut() -> E + 'static {
+  fn call_mut<C>(mut self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let value = (self.function)();
+    self.emitter.emit_now(runtime, value);
+    next.call(runtime, (self, ()));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// AWAIT
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Process awaiting for a signal to be emitted, and running during next instant if it does.
+#[derive(Clone)]
+pub struct AwaitProcess<S> {
+  signal: Box<S>
+}
+
+
+impl<S> Process for AwaitProcess<S> where S: ObservableSignal + 'static {
+  type Value = S::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    next.delay_after_present(self.signal.core()).call(runtime, ());
+  }
+}
+
+
+impl<S> ProcessMut for AwaitProcess<S> where S: ObservableSignal + 'static {
   fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
     let s1 = self.signal;
     let s2 = s1.clone();
 
-    s1.runtime().later_on_present(runtime, move |r: &mut Runtime, v: Self::Value| {
-      next.call(r, (s2.await(), v));
-    });
+    let next = move |r: &mut Runtime, v: Self::Value| next.call(r, (s2.await(), v));
+    next.delay_after_present(s1.core()).call(runtime, ());
   }
 }
 
@@ -123,44 +323,27 @@ where
 
 /// Process awaiting for a signal to be emitted, and running during current instant if it does.
 #[derive(Clone)]
-pub struct AwaitImmediateProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + Clone,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
-  signal: Box<S>,
-  phantom: PhantomData<(V, E)>
+pub struct AwaitImmediateProcess<S> {
+  signal: Box<S>
 }
 
 
-impl<S, V, E> Process for AwaitImmediateProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + 'static,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+impl<S> Process for AwaitImmediateProcess<S> where S: ObservableSignal + 'static {
   type Value = ();
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
-    self.signal.runtime().on_present(runtime, next);
+    next.delay_until_present(self.signal.core()).call(runtime, ());
   }
 }
 
 
-impl<S, V, E> ProcessMut for AwaitImmediateProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + Clone + 'static,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+impl<S> ProcessMut for AwaitImmediateProcess<S> where S: ObservableSignal + 'static {
   fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
     let s1 = *self.signal;
     let s2 = s1.clone();
 
-    s1.runtime().on_present(runtime, move |r: &mut Runtime, v: ()| {
-      next.call(r, (s2.await_immediate(), ()));
-    });
+    let next = move |r: &mut Runtime, _: ()| next.call(r, (s2.await_immediate(), ()));
+    next.delay_until_present(s1.core()).call(runtime, ());
   }
 }
 
@@ -171,53 +354,80 @@ where
 
 /// Process emitting a signal with the given value.
 #[derive(Clone)]
-pub struct EmitProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + Clone,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+pub struct EmitProcess<S> where S: EmittableSignal {
   signal: Box<S>,
-  value: E,
-  phantom: PhantomData<(V, E)>
+  value: S::Element
 }
 
 
-impl<S, V, E> Process for EmitProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + 'static,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+impl<S> Process for EmitProcess<S> where S: EmittableSignal + 'static {
   type Value = ();
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
     //println!("Call in EmitProcess");
 
-    self.signal.runtime().emit(runtime, self.value);
+    self.signal.core().emit_element(runtime, self.value);
     next.call(runtime, ());
   }
 }
 
 
-impl<S, V, E> ProcessMut for EmitProcess<S, V, E>
-where
-  S: Signal<V, E> + Sized + Clone + 'static,
-  V: Clone + 'static,
-  E: Clone + 'static
-{
+impl<S> ProcessMut for EmitProcess<S> where S: EmittableSignal + 'static {
   fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
     //println!("Call mut in Emit");
 
     let signal_1 = self.signal;
     let signal_2 = signal_1.clone();
 
-    signal_1.runtime().emit(runtime, self.value.clone());
+    signal_1.core().emit_element(runtime, self.value.clone());
     next.call(runtime, (signal_2.emit_value(self.value), ()));
   }
 }
 
 
+/// Process emitting a signal with the value of another process, during the instant it completes,
+/// and returning this value (see `Process::emit_output`).
+#[derive(Clone)]
+pub struct EmitOutputProcess<P, S> {
+  pub(crate) process: P,
+  pub(crate) signal : S
+}
+
+
+impl<P, S> Process for EmitOutputProcess<P, S>
+where
+  P: Process<Value = S::Element>,
+  S: EmittableSignal + 'static
+{
+  type Value = P::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let signal = self.signal;
+
+    self.process.call(runtime, move |r: &mut Runtime, v: P::Value| {
+      signal.core().emit_element(r, v.clone());
+      next.call(r, v);
+    });
+  }
+}
+
+
+impl<P, S> ProcessMut for EmitOutputProcess<P, S>
+where
+  P: ProcessMut<Value = S::Element>,
+  S: EmittableSignal + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let signal = self.signal;
+
+    self.process.call_mut(runtime, move |r: &mut Runtime, (process, v): (P, P::Value)| {
+      signal.clone().core().emit_element(r, v.clone());
+      next.call(r, (EmitOutputProcess { process, signal }, v));
+    });
+  }
+}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // PRESENT
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -228,32 +438,20 @@ where
 /// * run `process_if` during current instant, if the signal is emitted;
 /// * run `process_else` during next instant, if the signal is **not** emitted.
 #[derive(Clone)]
-pub struct PresentProcess<S, P1, P2, PV, SV, E>
-where
-  S: Signal<SV, E> + Sized + Clone,
-  P1: Process<Value = PV>,
-  P2: Process<Value = PV>,
-  PV: 'static,
-  SV:Clone +  'static,
-  E: Clone + 'static
-{
+pub struct PresentProcess<S, P1, P2> {
   signal      : Box<S>,
   process_if  : P1,
-  process_else: P2,
-  phantom     : PhantomData<(SV, E)>
+  process_else: P2
 }
 
 
-impl<S, P1, P2, PV, SV, E> Process for PresentProcess<S, P1, P2, PV, SV, E>
+impl<S, P1, P2> Process for PresentProcess<S, P1, P2>
 where
-  S: Signal<SV, E> + Sized + 'static,
-  P1: Process<Value = PV>,
-  P2: Process<Value = PV>,
-  PV: 'static,
-  SV:Clone +  'static,
-  E: Clone + 'static
+  S: ObservableSignal + 'static,
+  P1: Process,
+  P2: Process<Value = P1::Value>
 {
-  type Value = PV;
+  type Value = P1::Value;
 
   fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
     //println!("Call in PresentProcess");
@@ -267,28 +465,33 @@ where
     // Case 1: the signal is present during current instant
     let process_if = self.process_if;
 
-    signal_1.runtime().on_present(runtime, move |r: &mut Runtime, v: ()| {
-      process_if.call(r, next_1.take().unwrap());
-    });
+    // The continuation of the branch which is not taken may be called later (e.g. if the signal
+    // is emitted during a later instant): only the first one to be called runs its branch
+    let run_if = move |r: &mut Runtime, _: ()| {
+      if let Some(next) = next_1.take() {
+        process_if.call(r, next);
+      }
+    };
+    run_if.delay_until_present(signal_1.core()).call(runtime, ());
 
     // Case 2: the signal is absent during current instant
     let process_else = self.process_else;
 
-    signal_2.runtime().later_on_absent(runtime, move |r: &mut Runtime, v: ()| {
-      process_else.call(r, next_2.take().unwrap());
-    });
+    let run_else = move |r: &mut Runtime, _: ()| {
+      if let Some(next) = next_2.take() {
+        process_else.call(r, next);
+      }
+    };
+    run_else.delay_until_absent(signal_2.core()).call(runtime, ());
   }
 }
 
 
-impl<S, P1, P2, PV, SV, E> ProcessMut for PresentProcess<S, P1, P2, PV, SV, E>
+impl<S, P1, P2> ProcessMut for PresentProcess<S, P1, P2>
 where
-  S: Signal<SV, E> + Sized + Clone + 'static,
-  P1: ProcessMut<Value = PV>,
-  P2: ProcessMut<Value = PV>,
-  PV: 'static,
-  SV:Clone +  'static,
-  E: Clone + 'static
+  S: ObservableSignal + 'static,
+  P1: ProcessMut,
+  P2: ProcessMut<Value = P1::Value>
 {
   fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
     //println!("Call mut in PresentProcess");
@@ -310,19 +513,344 @@ where
     let process_else_2 = process_else_1.clone();
 
     // Case 1: the signal is present during current instant
-    signal_1.runtime().on_present(runtime, move |r: &mut Runtime, v: ()| {
-      process_if_1.take().unwrap().call_mut(r, move |r: &mut Runtime, (p, v): (P1, PV)| {
-        let present = signal_4.take().unwrap().present(p, process_else_1.take().unwrap());
-        next_1.take().unwrap().call(r, (present, v));
-      });
-    });
+    // As for `call`, only the first branch to be called runs
+    let run_if = move |r: &mut Runtime, _: ()| {
+      if let Some(next) = next_1.take() {
+        process_if_1.take().unwrap().call_mut(r, move |r: &mut Runtime, (p, v): (P1, P1::Value)| {
+          let present = signal_4.take().unwrap().present(p, process_else_1.take().unwrap());
+          next.call(r, (present, v));
+        });
+      }
+    };
+    run_if.delay_until_present(signal_1.core()).call(runtime, ());
 
     // Case 2: the signal is absent during current instant
-    signal_2.runtime().later_on_absent(runtime, move |r: &mut Runtime, v: ()| {
-      process_else_2.take().unwrap().call_mut(r, move |r: &mut Runtime, (p, v): (P2, PV)| {
-        let present = signal_5.take().unwrap().present(process_if_2.take().unwrap(), p);
-        next_2.take().unwrap().call(r, (present, v));
+    let run_else = move |r: &mut Runtime, _: ()| {
+      if let Some(next) = next_2.take() {
+        process_else_2.take().unwrap().call_mut(r, move |r: &mut Runtime, (p, v): (P2, P1::Value)| {
+          let present = signal_5.take().unwrap().present(process_if_2.take().unwrap(), p);
+          next.call(r, (present, v));
+        });
+      }
+    };
+    run_else.delay_until_absent(signal_2.core()).call(runtime, ());
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PRE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Process returning the value of a signal during the last instant it has been emitted.
+#[derive(Clone)]
+pub struct PreProcess<S> {
+  signal: Box<S>
+}
+
+
+impl<S> Process for PreProcess<S> where S: ObservableSignal + 'static {
+  type Value = S::Value;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    let value = self.signal.core().delivered_value();
+    next.call(runtime, value);
+  }
+}
+
+
+impl<S> ProcessMut for PreProcess<S> where S: ObservableSignal + 'static {
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let value = self.signal.clone().core().delivered_value();
+    next.call(runtime, (self, value));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SWITCH MAP
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Process running a new process built from the value of a signal each time it is emitted,
+/// and cancelling the process started for the previous emission.
+#[derive(Clone)]
+pub struct SwitchMapProcess<S, F> {
+  signal  : Box<S>,
+  function: F
+}
+
+
+impl<S, F, P> Process for SwitchMapProcess<S, F>
+where
+  S: ObservableSignal + 'static,
+  F: FnMut(S::Value) -> P + 'static,
+  P: Process
+{
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, _: C) where C: Continuation<Self::Value> {
+    switch_to_next_process(runtime, *self.signal, self.function, None);
+  }
+}
+
+
+/// Wait for the signal to be emitted, then cancel the `current` process (if any),
+/// and run a new one built by `function` during next instant, before starting over.
+fn switch_to_next_process<S, F, P>(runtime: &mut Runtime, signal: S, mut function: F,
+                                   current: Option<CancelHandle>)
+where
+  S: ObservableSignal + 'static,
+  F: FnMut(S::Value) -> P + 'static,
+  P: Process
+{
+  let signal_1 = signal.clone();
+
+  signal.await_immediate().call(runtime, move |r: &mut Runtime, _: ()| {
+    if let Some(handle) = current {
+      handle.cancel();
+    }
+
+    let signal_2 = signal_1.clone();
+
+    signal_1.await().call(r, move |r: &mut Runtime, v: S::Value| {
+      let handle = r.new_cancel_handle();
+
+      r.with_cancel_handle(Some(handle.clone()), |r| {
+        function(v).call(r, |_: &mut Runtime, _: P::Value| {});
       });
+
+      switch_to_next_process(r, signal_2, function, Some(handle));
+    });
+  });
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DO UNTIL
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Process running another process until the end of an instant during which a signal is emitted
+/// (see `Process::do_until`).
+///
+/// The process is preempted by cancelling it at the end of the instant: its pending continuations
+/// (e.g. the ones registered for next instant by a pause) are thus never called.
+#[derive(Clone)]
+pub struct DoUntilProcess<P, S> {
+  pub(crate) process: P,
+  pub(crate) signal : S
+}
+
+
+impl<P, S> Process for DoUntilProcess<P, S>
+where
+  P: Process,
+  S: ObservableSignal + 'static
+{
+  type Value = Option<P::Value>;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    preempt_at_end_of_instant(runtime, self.process, self.signal.await_immediate(), next);
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DESCRIBE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<E> Describe for EmitterProcess<E> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Emit { signal_name: None }
+  }
+}
+
+impl<E, F> Describe for EmitWithProcess<E, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Emit { signal_name: None }
+  }
+}
+
+impl<S> Describe for AwaitProcess<S> where S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Await { signal_name: (*self.signal).clone().core().name() }
+  }
+}
+
+impl<S> Describe for AwaitImmediateProcess<S> where S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::AwaitImmediate { signal_name: (*self.signal).clone().core().name() }
+  }
+}
+
+impl<S> Describe for EmitProcess<S> where S: EmittableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Emit { signal_name: (*self.signal).clone().core().name() }
+  }
+}
+
+impl<P, S> Describe for EmitOutputProcess<P, S> where P: Describe, S: EmittableSignal {
+  fn describe(&self) -> ProcessShape {
+    let emit = ProcessShape::Emit { signal_name: self.signal.clone().core().name() };
+    ProcessShape::Combinator { name: "EmitOutput", inner: vec![self.process.describe(), emit] }
+  }
+}
+
+impl<S, P1, P2> Describe for PresentProcess<S, P1, P2> where S: ObservableSignal, P1: Describe, P2: Describe {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Present {
+      signal_name: (*self.signal).clone().core().name(),
+      if_present : Box::new(self.process_if.describe()),
+      if_absent  : Box::new(self.process_else.describe())
+    }
+  }
+}
+
+impl<S> Describe for PreProcess<S> where S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Pre { signal_name: (*self.signal).clone().core().name() }
+  }
+}
+
+impl<P, S> Describe for DoUntilProcess<P, S> where P: Describe, S: ObservableSignal {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Combinator { name: "DoUntil", inner: vec![self.process.describe()] }
+  }
+}
+
+impl<S, F> Describe for SwitchMapProcess<S, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "SwitchMap" }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use signals::pure_signal::PureSignal;
+  use signals::value_signal::ValueSignal;
+  use testing::{SignalAssertions, Recorder};
+  use super::*;
+
+
+  #[test]
+  fn present_on_signal_emitted_later () {
+    let signal = PureSignal::new();
+
+    // The signal is absent during instant 0, and emitted during instant 2, once the process
+    // of the else branch has been started
+    let present_process = signal.clone().present(value(1), value(2));
+    let emit_process    = value(()).pause().pause().and_then(move |_| signal.emit());
+
+    let (branch, _) = execute_process(present_process.join(emit_process));
+    assert_eq!(branch, 2);
+  }
+
+  #[test]
+  fn present_mut_on_signal_emitted_later () {
+    let signal = PureSignal::new();
+
+    let present_process = signal.clone().present(value(1), value(2)).take(1);
+    let emit_process    = value(()).pause().pause().and_then(move |_| signal.emit());
+
+    let (branches, _) = execute_process(present_process.join(emit_process));
+    assert_eq!(branches, vec![2]);
+  }
+
+  #[test]
+  fn switch_map_cancels_previous_process () {
+    let value_signal_1 = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let value_signal_2 = value_signal_1.clone();
+    let value_signal_3 = value_signal_1.clone();
+
+    let log    = Recorder::new();
+    let record = log.recording();
+
+    // Emit the signal on instants 0 and 2
+    let emit_process = value_signal_1.emit_value(0)
+      .pause()
+      .pause()
+      .and_then(move |_| value_signal_2.emit_value(2));
+
+    // Each inner process takes 5 instants to terminate
+    let switch_map_process = value_signal_3.switch_map(move |v| {
+      let mut record_start = record.clone();
+      let mut record_end   = record.clone();
+
+      value(v)
+        .map(move |v| { record_start((v, "start")); v })
+        .pause().pause().pause().pause().pause()
+        .map(move |v| record_end((v, "end")))
     });
+
+    let mut runtime = Runtime::new();
+    runtime.on_current_instant(Box::new(move |r: &mut Runtime, _: ()| {
+      emit_process.call(r, |_: &mut Runtime, _: ()| {});
+      switch_map_process.call(r, |_: &mut Runtime, _: ()| {});
+    }));
+    runtime.execute();
+
+    assert_eq!(log.values(), vec![(0, "start"), (2, "start"), (2, "end")]);
+  }
+
+
+  #[test]
+  fn do_until_preempts_at_end_of_instant () {
+    let signal = PureSignal::new();
+    let stop   = PureSignal::new();
+    let log    = signal.attach();
+
+    // The loop still emits the signal during instant 2, when it is preempted
+    let emit_loop = signal.clone().emit().pause().loop_forever().do_until(stop.clone());
+    let (preempted, _) = execute_process(emit_loop.join(at_instant(2, stop.emit())));
+
+    assert_eq!(preempted, None);
+    log.assert_present_at(&[0, 1, 2]);
+    log.assert_absent_at(&[3]);
+  }
+
+  #[test]
+  fn do_until_returns_value_of_completed_process () {
+    let stop = PureSignal::new();
+
+    // The process completes during the instant of the emission, before the end of the instant
+    let process = value(42).pause().do_until(stop.clone());
+    assert_eq!(execute_process(process.join(at_instant(1, stop.emit()))).0, Some(42));
+  }
+
+  #[test]
+  fn emit_output_streams_loop_values () {
+    let signal    = ValueSignal::new();
+    let recording = signal.record();
+
+    let mut counter = 0;
+    let count = move || { counter += 1; counter };
+
+    let process = from_fn(count).emit_output(signal.clone()).pause().take(3);
+    assert_eq!(execute_process(process), vec![1, 2, 3]);
+
+    // The last pause ends during instant 3, during which nothing is emitted
+    let expected = vec![(0, Some(vec![1])), (1, Some(vec![2])), (2, Some(vec![3])), (3, None)];
+    assert_eq!(recording.into_vec(), expected);
+  }
+
+
+  #[test]
+  fn reader_awaits_owner_emission () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v += e; }));
+    let reader       = value_signal.reader();
+
+    // A reader only exposes the observation methods: emitting requires the signal itself
+    let observe = reader.clone().await().join(reader.clone().is_present()).join(reader.pre());
+    let emit    = value_signal.clone().emit_value(20).join(value_signal.emit_value(22));
+
+    let (((awaited, present), pre), _) = execute_process(observe.join(emit));
+    assert_eq!(awaited, 42);
+    assert!(present);
+    assert_eq!(pre, 0);
   }
 }
@@ -1,7 +1,14 @@
-use std::marker::PhantomData;
+use std::rc::{Rc, Weak};
+use std::cell::RefCell;
+use std::collections::VecDeque;
+use std::ops::{Add, Sub, Mul};
 
+use runtime::Runtime;
+use continuations::Continuation;
+use processes::{Process, ProcessMut};
+use describe::{Describe, ProcessShape};
 use signals::signals::*;
-use signals::runtime::SignalRuntimeRef;
+use signals::runtime::{SignalRuntimeRef, GatherFunction};
 
 
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -23,9 +30,434 @@ where
   /// Create a new `ValueSignal`, and its inner `SignalRuntimeRef`,
   /// using the given default value and gather function.
   /// See `new` method of `SignalRuntimeRef` for more details.
-  pub fn new_with_gather_function(default_value: V, gather_value_function: Box<FnMut(E, &mut V)>) -> Self {
+  pub fn new_with_gather_function(default_value: V, gather_value_function: GatherFunction<V, E>) -> Self {
     ValueSignal { runtime_ref: SignalRuntimeRef::new(default_value, gather_value_function) }
   }
+
+  /// Create a new `ValueSignal`, and its inner `SignalRuntimeRef`, using the given default value
+  /// and gather function, which also receives the index of the instant of each emission.
+  pub fn new_with_gather_indexed<F>(default_value: V, gather_value_function: F) -> Self
+  where
+    F: FnMut(E, &mut V, usize) + 'static
+  {
+    ValueSignal { runtime_ref: SignalRuntimeRef::new_indexed(default_value, Box::new(gather_value_function)) }
+  }
+
+  /// Returns a read-only handle to the signal, which cannot be used to emit it.
+  pub fn reader(&self) -> SignalReader<V, E> {
+    SignalReader::new(self.runtime_ref.clone())
+  }
+
+  /// Returns a facade to this signal, whose emitted elements of type `E2` are mapped through `f`
+  /// before being gathered. Awaiting the facade is the same as awaiting this signal.
+  pub fn with_emit_map<E2, F>(self, f: F) -> ValueSignal<V, E2>
+  where
+    E2: Clone + 'static,
+    F: FnMut(E2) -> E + 'static
+  {
+    ValueSignal { runtime_ref: self.runtime_ref.map_elements(f) }
+  }
+
+  /// Set the name of the signal, used for diagnostics (see `Describe`).
+  pub fn with_name(self, name: &'static str) -> Self {
+    self.runtime_ref.set_name(name);
+    self
+  }
+
+  /// Set a finalizer, called once per instant during which the signal is present,
+  /// once all its emissions have been gathered, and before its value is delivered.
+  /// This allows e.g. to sort the values gathered during an instant only once.
+  pub fn with_finalizer<F>(self, f: F) -> Self where F: FnMut(&mut V) + 'static {
+    self.runtime_ref.set_finalizer(Box::new(f));
+    self
+  }
+
+  /// Create a child signal, gathering its own elements in a vector: any emission of the child
+  /// also emits this signal during the same instant, with the element mapped through `map`.
+  ///
+  /// Since a child is always a new signal, children cannot be forwarded to in a cycle.
+  pub fn child<E2, F>(&self, mut map: F) -> ValueSignal<Vec<E2>, E2>
+  where
+    E2: Clone + 'static,
+    F: FnMut(E2) -> E + 'static
+  {
+    let child      = ValueSignal::new();
+    let parent_ref = self.runtime_ref.clone();
+
+    // The gather function of the child pushes each element: the last one is the emitted one
+    child.runtime_ref.on_emit(move |r: &mut Runtime, elements: &Vec<E2>| {
+      let element = elements.last().cloned().unwrap();
+      parent_ref.clone().emit(r, map(element));
+    });
+
+    child
+  }
+
+  /// Create a derived memory signal, emitted whenever this signal is emitted (in the same
+  /// instant), with the value of this signal mapped through `f`.
+  /// Its value is initially the last value of this signal, mapped through `f`.
+  pub fn map_signal<V2, F>(&self, mut f: F) -> ValueSignal<V2, V2>
+  where
+    V2: Clone + 'static,
+    F: FnMut(V) -> V2 + 'static
+  {
+    let mapped     = ValueSignal::new_memory(f(self.runtime_ref.last_value()));
+    let mapped_ref = mapped.runtime_ref.clone();
+
+    // Each emission replaces the value of the mapped signal by the value gathered so far
+    self.runtime_ref.on_emit(move |r: &mut Runtime, value: &V| {
+      mapped_ref.clone().emit(r, f(value.clone()));
+    });
+
+    mapped
+  }
+
+  /// Create a derived signal, grouping the values of this signal over windows of `k` instants.
+  ///
+  /// Windows are aligned on instant indices: the `n`-th window contains instants `n*k` to
+  /// `(n+1)*k - 1`. The derived signal is emitted during the instant following each window,
+  /// with the values of the signal during each instant of the window
+  /// (its default value if it has not been emitted during an instant).
+  ///
+  /// Empty windows, i.e. during which this signal has never been emitted, are skipped:
+  /// the derived signal is not emitted for them.
+  pub fn window(&self, k: usize) -> ValueSignal<Vec<V>, Vec<V>> {
+    assert!(k > 0, "a window must contain at least one instant");
+
+    let window = ValueSignal::new_with_gather_function(Vec::new(), Box::new(|e, v: &mut Vec<V>| { *v = e; }));
+    window.runtime_ref.depends_on(&self.runtime_ref);
+
+    let window_ref    = window.runtime_ref.clone();
+    let default_value = self.runtime_ref.default_value();
+    let mut values    = Vec::with_capacity(k);
+    let mut is_empty  = true;
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      // Instants of the window preceding the first call of this tap are silent
+      let position = r.instant_index() % k;
+      while values.len() < position {
+        values.push(default_value.clone());
+      }
+
+      values.push(value.cloned().unwrap_or_else(|| default_value.clone()));
+      is_empty = is_empty && value.is_none();
+
+      if position == k - 1 {
+        let values = ::std::mem::replace(&mut values, Vec::with_capacity(k));
+
+        if ! is_empty {
+          let window_ref = window_ref.clone();
+          r.schedule_next(move |r: &mut Runtime, _: ()| window_ref.emit(r, values));
+        }
+
+        is_empty = true;
+      }
+    });
+
+    window
+  }
+
+  /// Create a derived signal, emitted once this signal has been emitted, and then has not been
+  /// emitted during `k` consecutive instants. It is emitted during the instant following those
+  /// `k` instants, with the last value of this signal.
+  ///
+  /// Any emission of this signal during the `k` silent instants restarts the countdown.
+  pub fn debounce(&self, k: usize) -> ValueSignal<V, V> {
+    let default_value = self.runtime_ref.default_value();
+    let debounced     = ValueSignal::new_with_gather_function(default_value, Box::new(|e, v: &mut V| { *v = e; }));
+    debounced.runtime_ref.depends_on(&self.runtime_ref);
+
+    let debounced_ref       = debounced.runtime_ref.clone();
+    let mut last_value      = None;
+    let mut silent_instants = 0;
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      match value {
+        Some(value) => {
+          last_value      = Some(value.clone());
+          silent_instants = 0;
+        },
+        None => {
+          silent_instants += 1;
+        }
+      }
+
+      if silent_instants == k {
+        if let Some(value) = last_value.take() {
+          let debounced_ref = debounced_ref.clone();
+          r.schedule_next(move |r: &mut Runtime, _: ()| debounced_ref.emit(r, value));
+        }
+      }
+    });
+
+    debounced
+  }
+
+  /// Create a derived memory signal, whose value is the running sum of the values of this signal
+  /// (starting from `initial`), as a discrete integral over instants. Instants during which this
+  /// signal is absent contribute nothing to the sum.
+  ///
+  /// The derived signal is emitted during the instant following each instant this signal is
+  /// present, with the sum up to this instant: it holds this sum during the following instants.
+  pub fn integrate(&self, initial: V) -> ValueSignal<V, V> where V: Add<Output = V> + Default {
+    let integral = ValueSignal::new_memory(initial.clone());
+    integral.runtime_ref.depends_on(&self.runtime_ref);
+
+    let integral_ref = integral.runtime_ref.clone();
+    let mut sum      = initial;
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      if let Some(value) = value {
+        sum = ::std::mem::take(&mut sum) + value.clone();
+
+        let integral_ref = integral_ref.clone();
+        let sum          = sum.clone();
+        r.schedule_next(move |r: &mut Runtime, _: ()| integral_ref.emit(r, sum));
+      }
+    });
+
+    integral
+  }
+
+  /// Create a derived memory signal, whose value is the difference between the value of this
+  /// signal during an instant and during the previous instant, as a discrete derivative over
+  /// instants. This signal keeps its last value during the instants it is absent (initially the
+  /// default value of `V`), which thus have a zero derivative.
+  ///
+  /// The derived signal is emitted during the instant following each instant this signal is
+  /// present, with the difference, and during the instant following the first instant this signal
+  /// is absent again, with a zero difference.
+  pub fn differentiate(&self) -> ValueSignal<V, V> where V: Sub<Output = V> + Default {
+    let derivative = ValueSignal::new_memory(V::default());
+    derivative.runtime_ref.depends_on(&self.runtime_ref);
+
+    let derivative_ref  = derivative.runtime_ref.clone();
+    let mut last_value  = V::default();
+    let mut was_present = false;
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      let difference = match value {
+        Some(value) => {
+          let last_value = ::std::mem::replace(&mut last_value, value.clone());
+          value.clone() - last_value
+        },
+        None if was_present => V::default(),
+        None => return
+      };
+
+      was_present = value.is_some();
+
+      let derivative_ref = derivative_ref.clone();
+      r.schedule_next(move |r: &mut Runtime, _: ()| derivative_ref.emit(r, difference));
+    });
+
+    derivative
+  }
+
+  /// Create a derived signal, thinning the presence of this signal out to one instant every `k`
+  /// instants. Kept instants are aligned on instant indices: they are instants `k - 1`,
+  /// `2*k - 1`, etc.
+  ///
+  /// The derived signal is emitted during each kept instant this signal is present, in the same
+  /// instant, with a vector containing either the value of this signal during this instant
+  /// (`Keep::Latest`), or its values during all the instants it has been present since the
+  /// previous kept instant, including this one (`Keep::All`).
+  pub fn downsample(&self, k: usize, keep: Keep) -> ValueSignal<Vec<V>, Vec<V>> {
+    assert!(k > 0, "at least one instant out of k must be kept");
+
+    let downsampled = ValueSignal::new_with_gather_function(Vec::new(), Box::new(|e, v: &mut Vec<V>| { *v = e; }));
+    downsampled.runtime_ref.depends_on(&self.runtime_ref);
+
+    let downsampled_ref    = downsampled.runtime_ref.clone();
+    let skipped_values     = Rc::new(RefCell::new(Vec::new()));
+    let skipped_values_ref = skipped_values.clone();
+
+    // Each emission during a kept instant replaces the value of the derived signal
+    self.runtime_ref.on_emit(move |r: &mut Runtime, value: &V| {
+      if r.instant_index() % k == k - 1 {
+        let mut values = match keep {
+          Keep::Latest => Vec::with_capacity(1),
+          Keep::All    => skipped_values.borrow().clone()
+        };
+
+        values.push(value.clone());
+        downsampled_ref.clone().emit(r, values);
+      }
+    });
+
+    // Values of the skipped instants are gathered once they are over
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      let mut skipped_values = skipped_values_ref.borrow_mut();
+
+      if r.instant_index() % k == k - 1 {
+        skipped_values.clear();
+      }
+      else if let (Keep::All, Some(value)) = (keep, value) {
+        skipped_values.push(value.clone());
+      }
+    });
+
+    downsampled
+  }
+
+  /// Create a derived memory signal, whose value is the mean of the values of this signal over
+  /// the last `window` instants. Instants during which this signal is absent either count as its
+  /// default value (`AbsentInstants::AsDefault`), or are skipped, in which case the mean is
+  /// computed over the last `window` instants during which this signal is present
+  /// (`AbsentInstants::Skipped`). Until `window` instants have been observed, the mean is
+  /// computed over the observed instants.
+  ///
+  /// The derived signal is emitted during the instant following each instant changing the mean,
+  /// i.e. each instant during which this signal is present or, when absent instants count,
+  /// during which the window still contains (or has just evicted) an instant it is present.
+  pub fn moving_average(&self, window: usize, absent_instants: AbsentInstants) -> ValueSignal<f64, f64>
+  where
+    V: Into<f64>
+  {
+    assert!(window > 0, "a window must contain at least one instant");
+
+    let average = ValueSignal::new_memory(0.0);
+    average.runtime_ref.depends_on(&self.runtime_ref);
+
+    let average_ref   = average.runtime_ref.clone();
+    let default_value = self.runtime_ref.default_value().into();
+    let mut values    = VecDeque::with_capacity(window + 1);
+    let mut sum       = 0.0;
+
+    // Number of instants in the window during which the signal is present
+    let mut present_instants = 0;
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      let value = value.map(|value| value.clone().into());
+      if value.is_none() && absent_instants == AbsentInstants::Skipped {
+        return;
+      }
+
+      // The running sum is updated with the new value and the evicted one only
+      values.push_back(value);
+      sum += value.unwrap_or(default_value);
+      present_instants += value.is_some() as usize;
+
+      let mut evicted_present_instant = false;
+      if values.len() > window {
+        let evicted = values.pop_front().unwrap();
+        sum -= evicted.unwrap_or(default_value);
+        evicted_present_instant = evicted.is_some();
+        present_instants -= evicted_present_instant as usize;
+      }
+
+      if present_instants > 0 || evicted_present_instant {
+        let average_ref = average_ref.clone();
+        let mean        = sum / values.len() as f64;
+        r.schedule_next(move |r: &mut Runtime, _: ()| average_ref.emit(r, mean));
+      }
+    });
+
+    average
+  }
+
+  /// Record the value of this signal during each instant (`None` if it is absent),
+  /// from the first use of the signal by a runtime, until the recording is dropped.
+  /// See `record_with` for recording present instants only.
+  pub fn record(&self) -> Recording<V> {
+    self.record_with(RecordingMode::AllInstants)
+  }
+
+  /// Record the value of this signal during each instant, according to the given mode,
+  /// from the first use of the signal by a runtime, until the recording is dropped.
+  ///
+  /// The recording does not keep the signal alive.
+  pub fn record_with(&self, mode: RecordingMode) -> Recording<V> {
+    let records = Rc::new(RefCell::new(Vec::new()));
+    let records_ref: Weak<RefCell<Records<V>>> = Rc::downgrade(&records);
+
+    self.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      if let Some(records) = records_ref.upgrade() {
+        if value.is_some() || mode == RecordingMode::AllInstants {
+          records.borrow_mut().push((r.instant_index(), value.cloned()));
+        }
+      }
+    });
+
+    Recording { records }
+  }
+
+  /// Returns a process which emits this signal with the given element once fewer than
+  /// `capacity` elements have been gathered by the signal during an instant: if the signal is
+  /// already full during current instant, the process pauses and tries again during next instant.
+  ///
+  /// This allows a producer to wait for the consumers of the signal, which receive at most
+  /// `capacity` elements per instant, instead of dropping elements. There is no fairness between
+  /// several waiting producers: during each instant, the first ones to run take the room left.
+  pub fn emit_when_below(self, element: E, capacity: usize) -> EmitWhenBelowProcess<V, E> {
+    assert!(capacity > 0, "a signal must have room for at least one element per instant");
+    EmitWhenBelowProcess { signal: self, element, capacity }
+  }
+
+  /// Returns a process which awaits for the next instant this signal is present,
+  /// folds its value into an accumulator (initially `init`) using `f`,
+  /// and returns the new accumulator.
+  ///
+  /// When ran multiple times, the accumulator is kept between runs: this allows to compute
+  /// e.g. running sums over the lifetime of a signal.
+  pub fn fold_over_instants<A, F>(self, init: A, f: F) -> FoldOverInstantsProcess<V, E, A, F>
+  where
+    A: Clone + 'static,
+    F: FnMut(A, V) -> A + 'static
+  {
+    FoldOverInstantsProcess { signal: self, accumulator: init, function: f }
+  }
+}
+
+
+/// Create a signal combining the values of two signals, emitted whenever one of them is emitted,
+/// with a couple formed by the values of both signals.
+///
+/// The value of a signal which has not been emitted during current instant is its value during
+/// the last instant it has been emitted, or its default value if it has never been emitted.
+pub fn combine_latest<VA, EA, VB, EB>(a: ValueSignal<VA, EA>, b: ValueSignal<VB, EB>)
+  -> ValueSignal<(VA, VB), (VA, VB)>
+where
+  VA: Clone + 'static,
+  EA: Clone + 'static,
+  VB: Clone + 'static,
+  EB: Clone + 'static
+{
+  let latest_values = (a.runtime_ref.last_value(), b.runtime_ref.last_value());
+  let combined      = ValueSignal::new_with_gather_function(latest_values.clone(), Box::new(|e, v| { *v = e; }));
+
+  let latest_values_a = Rc::new(RefCell::new(latest_values));
+  let latest_values_b = latest_values_a.clone();
+  let combined_a      = combined.runtime_ref.clone();
+  let combined_b      = combined.runtime_ref.clone();
+
+  // Each emission of a signal updates its latest value, and re-emits the combined signal
+  a.runtime_ref.on_emit(move |r: &mut Runtime, value: &VA| {
+    latest_values_a.borrow_mut().0 = value.clone();
+    combined_a.clone().emit(r, latest_values_a.borrow().clone());
+  });
+
+  b.runtime_ref.on_emit(move |r: &mut Runtime, value: &VB| {
+    latest_values_b.borrow_mut().1 = value.clone();
+    combined_b.clone().emit(r, latest_values_b.borrow().clone());
+  });
+
+  combined
+}
+
+
+impl<V> ValueSignal<V, V>
+where
+  V: Clone + 'static
+{
+  /// Create a new *memory* `ValueSignal`, holding its last emitted value (initially `initial`)
+  /// across the instants during which it is not emitted, like a register.
+  /// Each emission replaces the value of the signal.
+  ///
+  /// Presence is unchanged: a memory signal is only present during the instants it is emitted.
+  pub fn new_memory(initial: V) -> Self {
+    ValueSignal { runtime_ref: SignalRuntimeRef::new_memory(initial, Box::new(|e, v| { *v = e; })) }
+  }
 }
 
 
@@ -43,17 +475,266 @@ where
 }
 
 
-impl<V, E> Signal<V, E> for ValueSignal<V, E>
+impl<E> Default for ValueSignal<Vec<E>, E>
 where
-  V: Clone,
-  E: Clone
+  E: Clone + 'static
 {
-  fn runtime(self) -> SignalRuntimeRef<V, E> {
+  fn default() -> Self {
+    Self::new()
+  }
+}
+
+
+impl<V, E> SignalHandle for ValueSignal<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  type Value   = V;
+  type Element = E;
+  type Core    = SignalRuntimeRef<V, E>;
+
+  fn core(self) -> SignalRuntimeRef<V, E> {
     self.runtime_ref.clone()
   }
 }
 
 
+impl<V, E> ObservableSignal for ValueSignal<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{}
+
+
+impl<V, E> EmittableSignal for ValueSignal<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL ARITHMETIC
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+// Arithmetic operators on (references to) value signals, between two signals, or between a
+// signal and a scalar. Operations between two signals are built on `combine_latest`: the result
+// is present whenever one of the signals is, and a signal which is absent contributes its value
+// during the last instant it has been emitted (or its default value).
+// Operations with a scalar are built on `map_signal`.
+macro_rules! impl_signal_operator {
+  ($operator:ident, $method:ident) => {
+    impl<'a, 'b, V, EA, EB> $operator<&'b ValueSignal<V, EB>> for &'a ValueSignal<V, EA>
+    where
+      V: $operator<Output = V> + Clone + 'static,
+      EA: Clone + 'static,
+      EB: Clone + 'static
+    {
+      type Output = ValueSignal<V, V>;
+
+      fn $method(self, other: &'b ValueSignal<V, EB>) -> ValueSignal<V, V> {
+        combine_latest(self.clone(), other.clone()).map_signal(|(a, b)| a.$method(b))
+      }
+    }
+
+    impl<'a, V, E> $operator<V> for &'a ValueSignal<V, E>
+    where
+      V: $operator<Output = V> + Clone + 'static,
+      E: Clone + 'static
+    {
+      type Output = ValueSignal<V, V>;
+
+      fn $method(self, scalar: V) -> ValueSignal<V, V> {
+        self.map_signal(move |a| a.$method(scalar.clone()))
+      }
+    }
+  }
+}
+
+impl_signal_operator!(Add, add);
+impl_signal_operator!(Sub, sub);
+impl_signal_operator!(Mul, mul);
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// RECORDING
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Instants recorded by a `Recording`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum RecordingMode {
+  /// Every instant, including those during which the signal is absent.
+  AllInstants,
+  /// Only the instants during which the signal is present.
+  PresentInstants
+}
+
+
+/// Values carried by a downsampled signal during each kept instant (see `ValueSignal::downsample`).
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Keep {
+  /// Only the value of the signal during the kept instant.
+  Latest,
+  /// The values of the signal during all the instants it has been present since the previous
+  /// kept instant, including the kept instant.
+  All
+}
+
+
+/// Handling of the instants during which a signal is absent by `ValueSignal::moving_average`.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum AbsentInstants {
+  /// Absent instants count as the default value of the signal.
+  AsDefault,
+  /// Absent instants are skipped.
+  Skipped
+}
+
+
+/// Values of a signal recorded during each instant, with the index of the instant.
+type Records<V> = Vec<(usize, Option<V>)>;
+
+/// Recording of the values of a signal, indexed by instant (see `ValueSignal::record`).
+pub struct Recording<V> {
+  records: Rc<RefCell<Records<V>>>
+}
+
+
+impl<V> Recording<V> where V: Clone {
+  /// Returns the values recorded so far.
+  pub fn records(&self) -> Vec<(usize, Option<V>)> {
+    self.records.borrow().clone()
+  }
+
+  /// Stops the recording, and returns the recorded values.
+  pub fn into_vec(self) -> Vec<(usize, Option<V>)> {
+    self.records.replace(Vec::new())
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// FOLD OVER INSTANTS PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process folding the values of a signal over the instants it is present.
+pub struct FoldOverInstantsProcess<V, E, A, F> {
+  signal     : ValueSignal<V, E>,
+  accumulator: A,
+  function   : F
+}
+
+
+impl<V, E, A, F> Process for FoldOverInstantsProcess<V, E, A, F>
+where
+  V: Clone + 'static,
+  E: Clone + 'static,
+  A: Clone + 'static,
+  F: FnMut(A, V) -> A + 'static
+{
+  type Value = A;
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    self.call_mut(runtime, |r: &mut Runtime, (_, accumulator): (Self, A)| next.call(r, accumulator));
+  }
+}
+
+
+impl<V, E, A, F> ProcessMut for FoldOverInstantsProcess<V, E, A, F>
+where
+  V: Clone + 'static,
+  E: Clone + 'static,
+  A: Clone + 'static,
+  F: FnMut(A, V) -> A + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    let signal       = self.signal;
+    let accumulator  = self.accumulator;
+    let mut function = self.function;
+
+    signal.runtime_ref.clone().later_on_present(runtime, move |r: &mut Runtime, v: V| {
+      let accumulator = function(accumulator, v);
+      let process     = FoldOverInstantsProcess { signal, accumulator: accumulator.clone(), function };
+
+      next.call(r, (process, accumulator));
+    });
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// EMIT WHEN BELOW PROCESS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// A process emitting a signal once it has room for the emitted element during an instant.
+pub struct EmitWhenBelowProcess<V, E> {
+  signal  : ValueSignal<V, E>,
+  element : E,
+  capacity: usize
+}
+
+
+impl<V, E> EmitWhenBelowProcess<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  /// Emits the signal if it has room during current instant, or tries again during next instant.
+  /// Calls `next` once the signal is emitted.
+  fn emit_or_retry<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self> {
+    if self.signal.runtime_ref.gathered_elements() < self.capacity {
+      self.signal.runtime_ref.clone().emit(runtime, self.element.clone());
+      next.call(runtime, self);
+    }
+    else {
+      runtime.schedule_next(move |r: &mut Runtime, _: ()| self.emit_or_retry(r, next));
+    }
+  }
+}
+
+
+impl<V, E> Process for EmitWhenBelowProcess<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    self.emit_or_retry(runtime, |r: &mut Runtime, _: Self| next.call(r, ()));
+  }
+}
+
+
+impl<V, E> ProcessMut for EmitWhenBelowProcess<V, E>
+where
+  V: Clone + 'static,
+  E: Clone + 'static
+{
+  fn call_mut<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<(Self, Self::Value)> {
+    self.emit_or_retry(runtime, |r: &mut Runtime, process: Self| next.call(r, (process, ())));
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// DESCRIBE
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+impl<V, E, A, F> Describe for FoldOverInstantsProcess<V, E, A, F> {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Opaque { name: "FoldOverInstants" }
+  }
+}
+
+impl<V, E> Describe for EmitWhenBelowProcess<V, E> where V: Clone + 'static, E: Clone + 'static {
+  fn describe(&self) -> ProcessShape {
+    ProcessShape::Emit { signal_name: self.signal.runtime_ref.name() }
+  }
+}
+
+
 ///////////////////////////////////////////////////////////////////////////////////////////////////
 // TESTS
 ///////////////////////////////////////////////////////////////////////////////////////////////////
@@ -61,9 +742,10 @@ where
 #[cfg(test)]
 mod tests {
   use std::rc::Rc;
-  use std::cell::{Cell, RefCell};
+  use std::cell::Cell;
 
   use processes::*;
+  use testing::{SignalAssertions, Recorder, Counter};
   use super::*;
 
 
@@ -73,9 +755,9 @@ mod tests {
     let gather_function = |e: u32, v: &mut u32| { *v = e };
     let value_signal_1  = ValueSignal::new_with_gather_function(0, Box::new(gather_function));
     let value_signal_2  = value_signal_1.clone();
+    let recording       = value_signal_1.record();
 
     let emit_counter_1 = Rc::new(Cell::new(0));
-    let emit_counter_2 = emit_counter_1.clone();
 
     let signal_value_sum_1 = Rc::new(Cell::new(0));
     let signal_value_sum_2 = signal_value_sum_1.clone();
@@ -101,5 +783,477 @@ mod tests {
 
     execute_process(join_process);
     assert_eq!(signal_value_sum_2.get(), 42);
+
+    // The signal is emitted during each of the 14 instants of the execution
+    assert_eq!(recording.into_vec(), (0..14).map(|i| (i, Some(3))).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn gather_every_emission_of_an_instant () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v += e; }));
+
+    let emit_values = value_signal.clone().emit_value(1)
+      .join(value_signal.clone().emit_value(2))
+      .join(value_signal.clone().emit_value(3));
+
+    let (_, awaited) = execute_process(emit_values.join(value_signal.await()));
+    assert_eq!(awaited, 6);
+  }
+
+  #[test]
+  fn emit_listeners_see_every_emission () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v += e; }));
+    let seen         = Recorder::new();
+    let mut record   = seen.recording();
+
+    value_signal.runtime_ref.on_emit(move |_: &mut Runtime, v: &u32| record(*v));
+
+    let emit_last   = value_signal.clone().emit_value(3);
+    let emit_values = value_signal.clone().emit_value(1)
+      .join(value_signal.emit_value(2))
+      .pause()
+      .map(move |_| emit_last)
+      .flatten();
+
+    execute_process(emit_values);
+    assert_eq!(seen.values(), vec![1, 3, 3]);
+  }
+
+
+  // Run `instants` instants, calling `f` with the runtime during each of them.
+  fn run_instants<F>(instants: usize, f: F) where F: Fn(&mut Runtime) + Clone + 'static {
+    let mut runtime = Runtime::new();
+
+    for _ in 0..instants {
+      let f = f.clone();
+      runtime.on_current_instant(Box::new(move |r: &mut Runtime, _: ()| f(r)));
+      runtime.instant();
+    }
+  }
+
+  // Emit `signal` with the given value during current instant.
+  fn emit_now<V, E>(runtime: &mut Runtime, signal: &ValueSignal<V, E>, value: E)
+  where
+    V: Clone + 'static,
+    E: Clone + 'static
+  {
+    signal.clone().emit_value(value).call(runtime, |_: &mut Runtime, _: ()| {});
+  }
+
+  // Run `instants` instants, emitting `signal` with the index of the instant during each
+  // instant of `emissions`.
+  fn emit_on_instants(signal: &ValueSignal<u32, u32>, emissions: &[usize], instants: usize) {
+    let signal    = signal.clone();
+    let emissions = emissions.to_vec();
+
+    run_instants(instants, move |r: &mut Runtime| {
+      if emissions.contains(&r.instant_index()) {
+        emit_now(r, &signal, r.instant_index() as u32);
+      }
+    });
+  }
+
+  // Record the instants during which `signal` is emitted, with its value.
+  fn record<V, E>(signal: &ValueSignal<V, E>) -> Recorder<(usize, V)>
+  where
+    V: Clone + 'static,
+    E: Clone + 'static
+  {
+    let records    = Recorder::new();
+    let mut record = records.recording();
+
+    signal.runtime_ref.on_each_instant(move |r: &mut Runtime, value: Option<&V>| {
+      if let Some(value) = value {
+        record((r.instant_index(), value.clone()));
+      }
+    });
+
+    records
+  }
+
+  #[test]
+  fn window_groups_bursts () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let window       = value_signal.window(3);
+    let records      = record(&window);
+
+    emit_on_instants(&value_signal, &[1, 2, 3, 7], 13);
+
+    // The last window (instants 9 to 11) is empty, and thus skipped
+    assert_eq!(records.values(), vec![
+      (3, vec![0, 1, 2]),
+      (6, vec![3, 0, 0]),
+      (9, vec![0, 7, 0])
+    ]);
+  }
+
+  #[test]
+  fn window_skips_empty_windows () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let window       = value_signal.window(2);
+    let records      = record(&window);
+
+    emit_on_instants(&value_signal, &[4], 10);
+    assert_eq!(records.values(), vec![(6, vec![4, 0])]);
+  }
+
+
+  #[test]
+  fn debounce_after_silent_instants () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let debounced    = value_signal.debounce(2);
+    let records      = record(&debounced);
+
+    emit_on_instants(&value_signal, &[0, 1, 2, 8], 14);
+    assert_eq!(records.values(), vec![(5, 2), (11, 8)]);
+  }
+
+
+  #[test]
+  fn integrate_constant_source () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let integral     = value_signal.integrate(0);
+    let records      = record(&integral);
+
+    let signal = value_signal.clone();
+    run_instants(6, move |r: &mut Runtime| emit_now(r, &signal, 1));
+
+    // The sum up to instant n - 1 is emitted during instant n
+    assert_eq!(records.values(), (1..6).map(|n| (n, n as u32)).collect::<Vec<_>>());
+  }
+
+  #[test]
+  fn differentiate_ramp () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: i32, v: &mut i32| { *v = e; }));
+    let derivative   = value_signal.differentiate();
+    let records      = record(&derivative);
+
+    let signal = value_signal.clone();
+    run_instants(8, move |r: &mut Runtime| {
+      if r.instant_index() < 4 {
+        emit_now(r, &signal, 2 * r.instant_index() as i32);
+      }
+    });
+
+    assert_eq!(records.values(), vec![(1, 0), (2, 2), (3, 2), (4, 2), (5, 0)]);
+  }
+
+  #[test]
+  fn downsample_every_third_instant () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let latest       = record(&value_signal.downsample(3, Keep::Latest));
+    let all          = record(&value_signal.downsample(3, Keep::All));
+
+    let emissions: Vec<usize> = (0..10).collect();
+    emit_on_instants(&value_signal, &emissions, 10);
+
+    assert_eq!(latest.values(), vec![(2, vec![2]), (5, vec![5]), (8, vec![8])]);
+    assert_eq!(all.values(), vec![(2, vec![0, 1, 2]), (5, vec![3, 4, 5]), (8, vec![6, 7, 8])]);
+  }
+
+  #[test]
+  fn moving_average_of_step () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let average      = record(&value_signal.moving_average(4, AbsentInstants::AsDefault));
+
+    let signal = value_signal.clone();
+    run_instants(9, move |r: &mut Runtime| {
+      emit_now(r, &signal, if r.instant_index() < 3 { 0 } else { 10 });
+    });
+
+    // The average of instant n is emitted during instant n + 1
+    assert_eq!(average.values(), vec![
+      (1, 0.0), (2, 0.0), (3, 0.0), (4, 2.5), (5, 5.0), (6, 7.5), (7, 10.0), (8, 10.0)
+    ]);
+  }
+
+  #[test]
+  fn moving_average_of_absent_instants () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let as_default   = record(&value_signal.moving_average(2, AbsentInstants::AsDefault));
+    let skipped      = record(&value_signal.moving_average(2, AbsentInstants::Skipped));
+
+    let signal = value_signal.clone();
+    run_instants(8, move |r: &mut Runtime| {
+      match r.instant_index() {
+        0 => emit_now(r, &signal, 4),
+        2 => emit_now(r, &signal, 8),
+        _ => {}
+      }
+    });
+
+    assert_eq!(as_default.values(), vec![(1, 4.0), (2, 2.0), (3, 4.0), (4, 4.0), (5, 0.0)]);
+    assert_eq!(skipped.values(), vec![(1, 4.0), (3, 6.0)]);
+  }
+
+  #[test]
+  fn add_and_scale_signals () {
+    let a     = ValueSignal::new_memory(0.0);
+    let b     = ValueSignal::new_memory(0.5);
+    let total = record(&(&a + &b));
+    let twice = record(&(&a * 2.0));
+    let diffs = record(&(&(&a - &b) - 1.0));
+
+    let (a_1, b_1) = (a.clone(), b.clone());
+    run_instants(5, move |r: &mut Runtime| {
+      match r.instant_index() {
+        0 => emit_now(r, &a_1, 1.0),
+        1 => emit_now(r, &b_1, 2.0),
+        3 => {
+          emit_now(r, &a_1, 5.0);
+          emit_now(r, &b_1, 4.0);
+        },
+        _ => {}
+      }
+    });
+
+    // An absent signal contributes its held value (or its initial one)
+    assert_eq!(total.values(), vec![(0, 1.5), (1, 3.0), (3, 9.0)]);
+    assert_eq!(twice.values(), vec![(0, 2.0), (3, 10.0)]);
+    assert_eq!(diffs.values(), vec![(0, -0.5), (1, -2.0), (3, 0.0)]);
+  }
+
+  #[test]
+  fn producer_waits_for_room () {
+    let signal = ValueSignal::new();
+    let log    = signal.attach();
+
+    // The producer emits 12 elements in order, as fast as it can
+    let produced         = Counter::new();
+    let mut produce_next = produced.incrementing();
+    let produce = move |_| {
+      let i = produce_next() - 1;
+      signal.clone().emit_when_below(i, 2)
+    };
+    let loop_status = move |_| if produced.get() < 12 { LoopStatus::Continue } else { LoopStatus::Exit(()) };
+
+    let producer = value(()).and_then(produce).map(loop_status).while_loop();
+    let done_at  = execute_process(producer.and_then(|_| instant_index()));
+
+    // The consumers receive 2 elements per instant: none is lost, and the producer completes
+    // during the sixth instant
+    for i in 0..6 {
+      log.assert_value_at(i, vec![2 * i, 2 * i + 1]);
+    }
+    assert_eq!(done_at, 5);
+  }
+
+  #[test]
+  fn gather_several_emissions () {
+    let value_signal = ValueSignal::new();
+    let records      = record(&value_signal);
+
+    let signal = value_signal.clone();
+    run_instants(2, move |r: &mut Runtime| {
+      emit_now(r, &signal, r.instant_index());
+      emit_now(r, &signal, 10 + r.instant_index());
+    });
+
+    assert_eq!(records.values(), vec![(0, vec![0, 10]), (1, vec![1, 11])]);
+  }
+
+  #[test]
+  fn combine_latest_values () {
+    let value_signal_a = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let value_signal_b = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let combined       = combine_latest(value_signal_a.clone(), value_signal_b.clone());
+    let records        = record(&combined);
+
+    run_instants(5, move |r: &mut Runtime| {
+      match r.instant_index() {
+        0 => emit_now(r, &value_signal_a, 1),
+        2 => emit_now(r, &value_signal_b, 20),
+        3 => emit_now(r, &value_signal_a, 3),
+        _ => {}
+      }
+    });
+
+    assert_eq!(records.values(), vec![(0, (1, 0)), (2, (1, 20)), (3, (3, 20))]);
+  }
+
+
+  #[test]
+  fn gather_with_instant_index () {
+    let gather_function = |e: char, v: &mut Vec<(usize, char)>, instant_index: usize| v.push((instant_index, e));
+    let value_signal    = ValueSignal::new_with_gather_indexed(Vec::new(), gather_function);
+    let records         = record(&value_signal);
+
+    let value_signal_1 = value_signal.clone();
+    run_instants(3, move |r: &mut Runtime| {
+      match r.instant_index() {
+        0 => { emit_now(r, &value_signal_1, 'a'); emit_now(r, &value_signal_1, 'b'); },
+        2 => { emit_now(r, &value_signal_1, 'c'); },
+        _ => {}
+      }
+    });
+
+    assert_eq!(records.values(), vec![
+      (0, vec![(0, 'a'), (0, 'b')]),
+      (2, vec![(2, 'c')])
+    ]);
+  }
+
+
+  #[test]
+  fn fold_over_instants_running_sum () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v += e; }));
+    let running_sums = value_signal.clone().fold_over_instants(0, |sum, v| sum + v).take(3);
+
+    let emit_values = value_signal.clone().emit_value(1)
+      .pause().and_then(move |_| value_signal.clone().emit_value(2)
+      .pause().and_then(move |_| value_signal.emit_value(3)));
+
+    let (running_sums, _) = execute_process(running_sums.join(emit_values));
+    assert_eq!(running_sums, vec![1, 3, 6]);
+  }
+
+
+  #[test]
+  fn finalize_gathered_values () {
+    let finalizations = Counter::new();
+    let mut finalize  = finalizations.incrementing();
+
+    let value_signal = ValueSignal::new().with_finalizer(move |v: &mut Vec<u32>| {
+      finalize();
+      v.sort();
+    });
+
+    let received   = Recorder::new();
+    let mut record = received.recording();
+
+    let mut runtime = Runtime::new();
+    value_signal.clone().await().call(&mut runtime, move |_: &mut Runtime, v: Vec<u32>| record(v));
+
+    // The signal is emitted during instants 0 and 2, out of order
+    for instant in 0..4 {
+      if instant % 2 == 0 {
+        for &v in &[3, 1, 2] {
+          value_signal.clone().emit_from_host(&mut runtime, v);
+        }
+      }
+      runtime.instant();
+    }
+
+    assert_eq!(received.values(), vec![vec![1, 2, 3]]);
+    assert_eq!(finalizations.get(), 2);
+  }
+
+
+  #[test]
+  fn record_present_instants_until_dropped () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e; }));
+    let recording    = value_signal.record_with(RecordingMode::PresentInstants);
+    let dropped      = value_signal.record();
+    drop(dropped);
+
+    emit_on_instants(&value_signal, &[1, 4], 6);
+    assert_eq!(recording.records(), vec![(1, Some(1)), (4, Some(4))]);
+  }
+
+
+  #[test]
+  fn count_using_reader_and_emitter () {
+    let value_signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v = e }));
+    let reader       = value_signal.reader();
+    let emitter      = value_signal.emitter();
+
+    let signal_values = Recorder::new();
+
+    // The producer only holds the emitter, and the consumer only holds the reader
+    let emit_loop_process  = emitter.emit_value(3).pause();
+    let await_loop_process = reader.await().map(signal_values.recording());
+
+    let mut emit_counter = 0;
+    let loop_map = move |_| {
+      emit_counter += 1;
+
+      match emit_counter {
+        14 => LoopStatus::Exit(()),
+        _  => LoopStatus::Continue
+      }
+    };
+
+    execute_process(await_loop_process.join(emit_loop_process).map(loop_map).while_loop());
+    assert_eq!(signal_values.values().iter().sum::<u32>(), 42);
+  }
+
+
+  #[test]
+  fn forward_children_emissions () {
+    let errors      = ValueSignal::new();
+    let disk_errors = errors.child(|code: u32| format!("disk: {}", code));
+    let net_errors  = errors.child(|code: u32| format!("network: {}", code));
+
+    let awaiters = errors.clone().await()
+      .join(disk_errors.clone().await())
+      .join(net_errors.clone().await());
+    let emitters = disk_errors.emit_value(28).join(net_errors.emit_value(113));
+
+    let (((errors, disk_errors), net_errors), _) = execute_process(awaiters.join(emitters));
+    assert_eq!(errors, vec![String::from("disk: 28"), String::from("network: 113")]);
+    assert_eq!(disk_errors, vec![28]);
+    assert_eq!(net_errors, vec![113]);
+  }
+
+
+  #[test]
+  fn normalize_emitted_elements () {
+    let words      = ValueSignal::new();
+    let raw_words  = words.clone().with_emit_map(|word: &'static str| word.trim().to_lowercase());
+
+    let emit_words = raw_words.clone().emit_value(" Hello").join(raw_words.clone().emit_value("WORLD "));
+    let emit_word  = words.clone().emit_value(String::from("again"));
+
+    // Both the signal and its facade can be awaited
+    let awaiters = words.await().join(raw_words.await());
+
+    let ((words, raw_words), _) = execute_process(awaiters.join(emit_words.join(emit_word)));
+    assert_eq!(words, vec![String::from("hello"), String::from("world"), String::from("again")]);
+    assert_eq!(raw_words, words);
+  }
+
+
+  #[test]
+  fn memory_holds_last_value () {
+    let memory = ValueSignal::new_memory(0);
+
+    let pre_values = Recorder::new();
+    let record     = pre_values.recording();
+
+    let memory_1 = memory.clone();
+    run_instants(7, move |r: &mut Runtime| {
+      let mut record = record.clone();
+      memory_1.clone().pre().call(r, move |_: &mut Runtime, v: u32| record(v));
+
+      match r.instant_index() {
+        0 => emit_now(r, &memory_1, 5),
+        4 => emit_now(r, &memory_1, 7),
+        _ => {}
+      }
+    });
+
+    assert_eq!(pre_values.values(), vec![0, 5, 5, 5, 5, 7, 7]);
+  }
+
+  #[test]
+  fn memory_gathers_into_held_value () {
+    let counter = ValueSignal::new_with_gather_function(0, Box::new(|e: u32, v: &mut u32| { *v += e; }));
+    let total   = ValueSignal {
+      runtime_ref: SignalRuntimeRef::new_memory(0, Box::new(|e: u32, v: &mut u32| { *v += e; }))
+    };
+    let records = record(&total);
+    let counts  = record(&counter);
+
+    let total_1   = total.clone();
+    let counter_1 = counter.clone();
+    run_instants(4, move |r: &mut Runtime| {
+      if r.instant_index() != 2 {
+        emit_now(r, &total_1, 1);
+        emit_now(r, &counter_1, 1);
+      }
+    });
+
+    assert_eq!(records.values(), vec![(0, 1), (1, 2), (3, 3)]);
+    assert_eq!(counts.values(), vec![(0, 1), (1, 1), (3, 1)]);
   }
 }
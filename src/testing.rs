@@ -0,0 +1,460 @@
+use std::rc::Rc;
+use std::cell::{Cell, RefCell};
+use std::fmt::Debug;
+
+use runtime::Runtime;
+use continuations::Continuation;
+use processes::Process;
+use signals::signals::{SignalHandle, SignalRuntimeOf, EmittableSignal};
+use signals::signal_core::SignalCore;
+use signals::runtime::SignalRuntimeRef;
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// SIGNAL ASSERTIONS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Signals which can be logged for making assertions about their presence and values in tests.
+pub trait SignalAssertions: SignalHandle<Core = SignalRuntimeOf<Self>> {
+  /// Returns a log of the presence and value of the signal during each instant,
+  /// from the first use of the signal by a runtime on.
+  fn attach(&self) -> SignalLog<Self::Value, Self::Element> {
+    let instants_1 = Rc::new(RefCell::new(Vec::new()));
+    let instants_2 = instants_1.clone();
+
+    let signal = self.clone().core();
+    signal.on_each_instant(move |r: &mut Runtime, value: Option<&Self::Value>| {
+      let mut instants = instants_1.borrow_mut();
+      while instants.len() < r.instant_index() {
+        instants.push(None);
+      }
+
+      instants.push(value.cloned());
+    });
+
+    SignalLog { signal, instants: instants_2 }
+  }
+}
+
+
+impl<S> SignalAssertions for S where S: SignalHandle<Core = SignalRuntimeOf<S>> {}
+
+
+/// Log of the value of a signal during each instant (`None` if it is absent),
+/// indexed by instant (see `SignalAssertions::attach`).
+///
+/// Instants which have not been logged (i.e. before the first use of the signal by a runtime,
+/// or after the end of the execution) count as instants during which the signal is absent.
+///
+/// The log keeps the signal alive, so that it is logged until the end of the execution.
+pub struct SignalLog<V, E> {
+  // Logged signal, only kept alive by the log
+  #[allow(dead_code)]
+  signal  : SignalRuntimeRef<V, E>,
+  instants: Rc<RefCell<Vec<Option<V>>>>
+}
+
+
+impl<V, E> SignalLog<V, E>
+where
+  V: Clone
+{
+  /// Returns the value of the signal during the given instant, if it is present.
+  pub fn value_at(&self, instant: usize) -> Option<V> {
+    self.instants.borrow().get(instant).cloned().unwrap_or(None)
+  }
+
+  /// Returns whether the signal is present during the given instant.
+  pub fn is_present_at(&self, instant: usize) -> bool {
+    self.instants.borrow().get(instant).is_some_and(|value| value.is_some())
+  }
+
+  /// Returns the presence of the signal during each instant, from the first instant to the last
+  /// instant among the logged instants and the given ones, as a string (`X` if it is present,
+  /// `.` otherwise).
+  pub fn timeline(&self, instants: &[usize]) -> String {
+    let length = instants.iter().map(|i| i + 1).chain(Some(self.instants.borrow().len())).max().unwrap();
+    (0..length).map(|i| if self.is_present_at(i) { 'X' } else { '.' }).collect()
+  }
+
+  /// Asserts that the signal is present during each of the given instants.
+  /// Panics otherwise, with the expected and actual presence timelines.
+  pub fn assert_present_at(&self, instants: &[usize]) {
+    self.assert_presence_at(instants, true);
+  }
+
+  /// Asserts that the signal is absent during each of the given instants.
+  /// Panics otherwise, with the expected and actual presence timelines.
+  pub fn assert_absent_at(&self, instants: &[usize]) {
+    self.assert_presence_at(instants, false);
+  }
+
+  /// Asserts that the signal has the given presence during each of the given instants.
+  fn assert_presence_at(&self, instants: &[usize], present: bool) {
+    let actual = self.timeline(instants);
+    let mut expected: Vec<char> = actual.chars().collect();
+    for &i in instants {
+      expected[i] = if present { 'X' } else { '.' };
+    }
+
+    let expected: String = expected.into_iter().collect();
+    assert!(expected == actual,
+      "unexpected presence of the signal\nexpected: {}\nactual:   {}", expected, actual);
+  }
+}
+
+
+impl<V, E> SignalLog<V, E>
+where
+  V: Clone + PartialEq + Debug
+{
+  /// Asserts that the signal is present during the given instant, with the given value.
+  /// Panics otherwise, with the presence timeline of the signal.
+  pub fn assert_value_at(&self, instant: usize, expected: V) {
+    match self.value_at(instant) {
+      Some(ref value) if *value == expected => {},
+      value => panic!("unexpected value of the signal during instant {}\nexpected: {:?}\nactual:   {:?}\ntimeline: {}",
+        instant, Some(expected), value, self.timeline(&[instant]))
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// PSEUDO-RANDOM NUMBERS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Pseudo-random number generator (xorshift), entirely determined by its seed,
+/// so that randomized tests are reproducible.
+#[derive(Debug, Clone)]
+pub struct XorShift {
+  state: u64
+}
+
+
+impl XorShift {
+  /// Create a new `XorShift` from the given seed.
+  pub fn new(seed: u64) -> Self {
+    // The state of a xorshift generator must not be zero
+    XorShift { state: if seed == 0 { 0x9E37_79B9_7F4A_7C15 } else { seed } }
+  }
+
+  /// Returns the next pseudo-random number.
+  pub fn next_u64(&mut self) -> u64 {
+    self.state ^= self.state << 13;
+    self.state ^= self.state >> 7;
+    self.state ^= self.state << 17;
+    self.state
+  }
+
+  /// Returns a pseudo-random number between 0 (included) and `n` (excluded).
+  pub fn below(&mut self, n: u64) -> u64 {
+    self.next_u64() % n
+  }
+
+  /// Returns `true` with the given probability.
+  pub fn chance(&mut self, probability: f64) -> bool {
+    // The 53 high bits of the next number give a uniform float between 0 and 1
+    let uniform = (self.next_u64() >> 11) as f64 / (1u64 << 53) as f64;
+    uniform < probability
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// STRESS EMITTER
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Configuration of a `StressEmitter`.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct StressConfig {
+  /// Number of instants during which the emitter runs.
+  pub instants: usize,
+  /// Probability for the signal to be emitted during each instant.
+  pub emission_probability: f64,
+  /// Maximal number of elements emitted during an instant the signal is emitted.
+  pub max_per_instant: usize
+}
+
+
+impl Default for StressConfig {
+  fn default() -> Self {
+    StressConfig { instants: 100, emission_probability: 0.5, max_per_instant: 4 }
+  }
+}
+
+
+/// Elements emitted by a `StressEmitter`, with the index of the instant of each emission.
+pub type Emissions<E> = Rc<RefCell<Vec<(usize, E)>>>;
+
+
+/// Process emitting a signal chaotically, for testing the robustness of the processes using it.
+///
+/// During each instant, it decides pseudo-randomly whether to emit the signal and how many
+/// elements to emit, whose payloads are chosen by a function using the same pseudo-random number
+/// generator. Given the same seed, the emissions always are the same.
+pub struct StressEmitter<S, F> where S: SignalHandle {
+  signal   : S,
+  rng      : XorShift,
+  config   : StressConfig,
+  payload  : F,
+  emissions: Emissions<S::Element>
+}
+
+
+impl<S, F> StressEmitter<S, F>
+where
+  S: EmittableSignal + 'static,
+  F: FnMut(&mut XorShift) -> S::Element + 'static
+{
+  /// Create a new `StressEmitter`, emitting `signal` according to the given seed and
+  /// configuration, with payloads returned by `payload`.
+  pub fn new(signal: S, seed: u64, config: StressConfig, payload: F) -> Self {
+    StressEmitter {
+      signal,
+      rng      : XorShift::new(seed),
+      config,
+      payload,
+      emissions: Rc::new(RefCell::new(Vec::new()))
+    }
+  }
+
+  /// Returns the elements emitted by the emitter so far, with the index of the instant of each
+  /// emission. The record is shared: it is updated during the execution of the emitter.
+  pub fn emissions(&self) -> Emissions<S::Element> {
+    self.emissions.clone()
+  }
+
+  /// Runs the emitter during current instant, and during the `remaining_instants` next instants.
+  fn run_instant<C>(mut self, runtime: &mut Runtime, remaining_instants: usize, next: C)
+  where
+    C: Continuation<()>
+  {
+    if self.rng.chance(self.config.emission_probability) {
+      let elements = self.rng.below(self.config.max_per_instant as u64 + 1);
+      for _ in 0..elements {
+        let element = (self.payload)(&mut self.rng);
+        self.emissions.borrow_mut().push((runtime.instant_index(), element.clone()));
+        self.signal.clone().core().emit_element(runtime, element);
+      }
+    }
+
+    if remaining_instants == 0 {
+      next.call(runtime, ());
+    }
+    else {
+      runtime.schedule_next(move |r: &mut Runtime, _: ()| self.run_instant(r, remaining_instants - 1, next));
+    }
+  }
+}
+
+
+impl<S, F> Process for StressEmitter<S, F>
+where
+  S: EmittableSignal + 'static,
+  F: FnMut(&mut XorShift) -> S::Element + 'static
+{
+  type Value = ();
+
+  fn call<C>(self, runtime: &mut Runtime, next: C) where C: Continuation<Self::Value> {
+    match self.config.instants {
+      0        => next.call(runtime, ()),
+      instants => self.run_instant(runtime, instants - 1, next)
+    }
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// RECORDERS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+/// Log of the values recorded by the processes of a test, to be checked once they are done.
+///
+/// All the clones of a recorder share the same log: a clone can thus be moved into a process,
+/// while the test keeps the original to read the log.
+pub struct Recorder<T> {
+  values: Rc<RefCell<Vec<T>>>
+}
+
+
+impl<T> Recorder<T> {
+  /// Create a new, empty `Recorder`.
+  pub fn new() -> Self {
+    Recorder { values: Rc::new(RefCell::new(Vec::new())) }
+  }
+
+  /// Record the given value.
+  pub fn record(&self, value: T) {
+    self.values.borrow_mut().push(value);
+  }
+
+  /// Returns a function recording each value it is called with.
+  pub fn recording(&self) -> impl FnMut(T) + Clone where T: 'static {
+    let recorder = self.clone();
+    move |value: T| recorder.record(value)
+  }
+
+  /// Returns the number of recorded values.
+  pub fn len(&self) -> usize {
+    self.values.borrow().len()
+  }
+
+  /// Returns whether no value has been recorded.
+  pub fn is_empty(&self) -> bool {
+    self.values.borrow().is_empty()
+  }
+
+  /// Returns the recorded values, in the order they have been recorded.
+  pub fn values(&self) -> Vec<T> where T: Clone {
+    self.values.borrow().clone()
+  }
+}
+
+
+impl<T> Clone for Recorder<T> {
+  fn clone(&self) -> Self {
+    Recorder { values: self.values.clone() }
+  }
+}
+
+
+impl<T> Default for Recorder<T> {
+  fn default() -> Self {
+    Recorder::new()
+  }
+}
+
+
+/// Counter incremented by the processes of a test, e.g. for counting the runs of a process.
+///
+/// As for `Recorder`, all the clones of a counter share the same count.
+#[derive(Clone, Default)]
+pub struct Counter {
+  count: Rc<Cell<usize>>
+}
+
+
+impl Counter {
+  /// Create a new `Counter`, starting from zero.
+  pub fn new() -> Self {
+    Counter::default()
+  }
+
+  /// Increment the counter, and returns its new count.
+  pub fn increment(&self) -> usize {
+    self.count.set(self.count.get() + 1);
+    self.count.get()
+  }
+
+  /// Returns a function incrementing the counter each time it is called, returning its new count.
+  pub fn incrementing(&self) -> impl FnMut() -> usize + Clone {
+    let counter = self.clone();
+    move || counter.increment()
+  }
+
+  /// Returns the current count.
+  pub fn get(&self) -> usize {
+    self.count.get()
+  }
+}
+
+
+///////////////////////////////////////////////////////////////////////////////////////////////////
+// TESTS
+///////////////////////////////////////////////////////////////////////////////////////////////////
+
+#[cfg(test)]
+mod tests {
+  use processes::*;
+  use signals::signals::*;
+  use signals::pure_signal::PureSignal;
+  use signals::value_signal::ValueSignal;
+  use super::*;
+
+
+  #[test]
+  fn periodic_signal_timeline () {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: usize, v: &mut usize| { *v = e; }));
+    let log    = signal.attach();
+
+    // The signal is emitted every 3 instants, with the index of the instant
+    let emitter = for_each_concurrent(vec![0, 3, 6, 9], move |i| at_instant(i, signal.clone().emit_value(i)));
+    execute_process(emitter);
+
+    assert_eq!(log.timeline(&[]), "X..X..X..X");
+    log.assert_present_at(&[0, 3, 6, 9]);
+    log.assert_absent_at(&[1, 2, 4, 5, 7, 8, 10, 11]);
+    log.assert_value_at(6, 6);
+  }
+
+  #[test]
+  fn sum_stress_emissions () {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: u64, v: &mut u64| { *v += e; }));
+    let log    = signal.attach();
+
+    let config    = StressConfig { instants: 200, .. StressConfig::default() };
+    let emitter   = StressEmitter::new(signal.clone(), 42, config, |rng: &mut XorShift| rng.below(100));
+    let emissions = emitter.emissions();
+
+    // The consumer sums the values of the signal until it is never emitted again
+    let sums       = Recorder::new();
+    let mut record = sums.recording();
+    let record_sum = move |sum| {
+      record(sum);
+      LoopStatus::Continue::<()>
+    };
+    let consumer = signal.fold_over_instants(0, |sum, v| sum + v).map(record_sum).while_loop();
+
+    let mut runtime = Runtime::new();
+    runtime.schedule_current(move |r: &mut Runtime, _: ()| {
+      emitter.call(r, |_: &mut Runtime, _: ()| {});
+      consumer.call(r, |_: &mut Runtime, _: ()| {});
+    });
+    runtime.execute();
+
+    let emissions = emissions.borrow();
+    assert_eq!(sums.values().last(), Some(&emissions.iter().map(|&(_, e)| e).sum()));
+
+    let mut instants: Vec<usize> = emissions.iter().map(|&(i, _)| i).collect();
+    instants.dedup();
+    log.assert_present_at(&instants);
+    assert!(instants.len() > 50 && instants.len() < 150);
+  }
+
+  #[test]
+  fn stress_emissions_are_reproducible () {
+    let emissions_with_seed = |seed: u64| {
+      let signal  = ValueSignal::new();
+      let emitter = StressEmitter::new(signal, seed, StressConfig::default(), |rng: &mut XorShift| rng.below(10));
+      let emissions = emitter.emissions();
+
+      execute_process(emitter);
+      let emissions = emissions.borrow().clone();
+      emissions
+    };
+
+    assert_eq!(emissions_with_seed(7), emissions_with_seed(7));
+    assert_ne!(emissions_with_seed(7), emissions_with_seed(8));
+  }
+
+  #[test]
+  #[should_panic(expected = "expected: .X.X\nactual:   .X..")]
+  fn render_presence_timelines () {
+    let signal = PureSignal::new();
+    let log    = signal.attach();
+
+    execute_process(value(()).pause().and_then(move |_| signal.emit()));
+    log.assert_present_at(&[1, 3]);
+  }
+
+  #[test]
+  #[should_panic(expected = "unexpected value of the signal during instant 2")]
+  fn report_absent_value () {
+    let signal = ValueSignal::new_with_gather_function(0, Box::new(|e: usize, v: &mut usize| { *v = e; }));
+    let log    = signal.attach();
+
+    execute_process(signal.emit_value(1));
+    log.assert_value_at(2, 1);
+  }
+}
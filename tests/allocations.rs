@@ -0,0 +1,121 @@
+//! Tests about the allocations made by the runtime, counted by a global allocator.
+//!
+//! They live in their own test crate, so that the counting allocator
+//! does not replace the allocator of the unit tests.
+
+extern crate reactrust;
+
+use std::cell::Cell;
+use std::alloc::{GlobalAlloc, Layout, System};
+
+use reactrust::continuations::Continuation;
+use reactrust::runtime::Runtime;
+use reactrust::processes::*;
+use reactrust::signals::signals::SignalHandle;
+use reactrust::signals::pure_signal::PureSignal;
+use reactrust::testing::Counter;
+
+
+/// Allocator counting the allocations made by each thread, for tests about allocations.
+struct CountingAllocator;
+
+thread_local! {
+  static ALLOCATIONS: Cell<usize> = const { Cell::new(0) };
+}
+
+unsafe impl GlobalAlloc for CountingAllocator {
+  unsafe fn alloc(&self, layout: Layout) -> *mut u8 {
+    let _ = ALLOCATIONS.try_with(|count| count.set(count.get() + 1));
+    System.alloc(layout)
+  }
+
+  unsafe fn dealloc(&self, ptr: *mut u8, layout: Layout) {
+    System.dealloc(ptr, layout)
+  }
+}
+
+#[global_allocator]
+static ALLOCATOR: CountingAllocator = CountingAllocator;
+
+/// Returns the number of allocations made by the current thread so far.
+fn allocations() -> usize {
+  ALLOCATIONS.with(|count| count.get())
+}
+
+/// Continuation awaiting a signal, and emitting another one once woken up.
+struct Pong {
+  awaited: PureSignal,
+  emitted: PureSignal
+}
+
+impl Continuation<()> for Pong {
+  fn call(self, runtime: &mut Runtime, _: ()) {
+    self.emitted.clone().runtime().emit(runtime, ());
+    self.awaited.clone().runtime().later_on_present(runtime, self);
+  }
+
+  fn call_box(self: Box<Self>, runtime: &mut Runtime, value: ()) {
+    (*self).call(runtime, value);
+  }
+}
+
+#[test]
+fn allocations_plateau () {
+  let signal_a = PureSignal::new();
+  let signal_b = PureSignal::new();
+
+  let mut runtime = Runtime::new();
+  let pong_a = Pong { awaited: signal_a.clone(), emitted: signal_b.clone() };
+  let pong_b = Pong { awaited: signal_b.clone(), emitted: signal_a.clone() };
+  signal_a.clone().runtime().later_on_present(&mut runtime, pong_a);
+  signal_b.clone().runtime().later_on_present(&mut runtime, pong_b);
+  signal_a.clone().runtime().emit(&mut runtime, ());
+
+  let mut allocations_during = |instants: usize| {
+    let start = allocations();
+    for _ in 0..instants {
+      assert!(runtime.instant());
+    }
+    allocations() - start
+  };
+
+  // Once the pool and the queues are warmed up, instants do not allocate anymore
+  allocations_during(10);
+  assert_eq!(allocations_during(10_000), 0);
+}
+
+#[test]
+fn fused_map_chain_allocations_plateau () {
+  let iterations          = Counter::new();
+  let mut count_iteration = iterations.incrementing();
+
+  let count       = move |_| count_iteration();
+  let double      = |i: usize| i * 2;
+  let increment   = |i: usize| i + 1;
+  let halve       = |i: usize| i / 2;
+  let loop_status = |i: usize| if i < 20_000 { LoopStatus::Continue } else { LoopStatus::Exit(i) };
+
+  // The five stages are fused into a single mapping function
+  let process: MapProcess<PauseProcess<ValueProcess<()>>, _> = value(()).pause()
+    .map(count)
+    .map(double)
+    .map(increment)
+    .map(halve)
+    .map(loop_status);
+
+  let mut runtime = Runtime::new();
+  process.while_loop().call(&mut runtime, |_: &mut Runtime, _: usize| {});
+
+  let mut allocations_during = |instants: usize| {
+    let start = allocations();
+    for _ in 0..instants {
+      assert!(runtime.instant());
+    }
+    allocations() - start
+  };
+
+  allocations_during(10);
+  assert_eq!(allocations_during(10_000), 0);
+  // Each iteration pauses before counting
+  assert_eq!(iterations.get(), 10_009);
+}